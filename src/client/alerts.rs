@@ -0,0 +1,212 @@
+//! Price threshold alerts
+//!
+//! Rules such as "91290A115 above 0.50" are kept in the subscription database and checked
+//! whenever `price-all` or `mmc alerts check` fetches prices. A rule fires when the price
+//! crosses its threshold, not on every check while it stays past it, and can run the
+//! `alert_command` from config.toml as a hook.
+
+use crate::client::api::PartPrices;
+use crate::models::product::PriceInfo;
+use crate::models::warning::{Warning, WarningKind};
+use crate::utils::error::{ClientError, Result};
+use serde::Serialize;
+use std::process::Command;
+
+/// Which side of the threshold fires the alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertDirection {
+    Above,
+    Below,
+}
+
+impl AlertDirection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AlertDirection::Above => "above",
+            AlertDirection::Below => "below",
+        }
+    }
+
+    pub fn parse(direction: &str) -> Option<Self> {
+        match direction {
+            "above" => Some(AlertDirection::Above),
+            "below" => Some(AlertDirection::Below),
+            _ => None,
+        }
+    }
+
+    /// Whether `price` is past `threshold` on this side
+    fn is_past(self, price: f64, threshold: f64) -> bool {
+        match self {
+            AlertDirection::Above => price > threshold,
+            AlertDirection::Below => price < threshold,
+        }
+    }
+}
+
+/// An alert rule on a part's price at its smallest price break
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertRule {
+    pub part_number: String,
+    pub direction: AlertDirection,
+    pub threshold: f64,
+    pub last_price: Option<f64>, // Price when the rule was last checked
+}
+
+/// A rule whose threshold the price just crossed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertTrigger {
+    pub rule: AlertRule,
+    pub price: f64,
+    pub currency: String,
+    pub unit_of_measure: String,
+}
+
+/// Alert methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Add or replace the alert on one side of a part's price
+    pub fn add_alert(&self, part: &str, direction: AlertDirection, threshold: f64) -> Result<()> {
+        if !(threshold >= 0.0 && threshold.is_finite()) {
+            return Err(ClientError::Generic(format!("Alert threshold {} must be 0 or more", threshold)));
+        }
+        self.subscriptions().set_alert(part, direction, threshold)
+    }
+
+    /// Remove a part's alerts; returns how many were removed
+    pub fn remove_alerts(&self, part: &str) -> Result<usize> {
+        self.subscriptions().remove_alerts(part)
+    }
+
+    /// Every alert rule, sorted by part number
+    pub fn alerts(&self) -> Result<Vec<AlertRule>> {
+        self.subscriptions().get_alerts()
+    }
+
+    /// Fetch prices for every part with an alert and check the alerts against them
+    pub async fn check_alerts(&self) -> Result<(Vec<PartPrices>, Vec<AlertTrigger>)> {
+        let mut parts: Vec<String> = self.alerts()?.into_iter().map(|rule| rule.part_number).collect();
+        parts.dedup();
+        if parts.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+        let results = self.get_prices(&parts).await?;
+        let triggers = self.evaluate_alerts(&results)?;
+        Ok((results, triggers))
+    }
+
+    /// `price-all`: fetch prices for every tracked part (or those with `tag`) and check their alerts
+    pub async fn get_price_all_with_alerts(&self, tag: Option<&str>) -> Result<(Vec<PartPrices>, Vec<AlertTrigger>)> {
+        let results = self.get_price_all(tag).await?;
+        let triggers = self.evaluate_alerts(&results)?;
+        Ok((results, triggers))
+    }
+
+    /// Check alerts on the parts in `results`, remember the prices, and run the hook for each that fired
+    pub fn evaluate_alerts(&self, results: &[PartPrices]) -> Result<Vec<AlertTrigger>> {
+        let rules = self.alerts()?;
+        let mut triggers = Vec::new();
+        for result in results {
+            let Some(price) = lowest_break(&result.prices) else {
+                continue;
+            };
+            for rule in rules.iter().filter(|rule| rule.part_number.eq_ignore_ascii_case(&result.part_number)) {
+                let was_past = rule.last_price.is_some_and(|last| rule.direction.is_past(last, rule.threshold));
+                if rule.direction.is_past(price.amount, rule.threshold) && !was_past {
+                    triggers.push(AlertTrigger {
+                        rule: rule.clone(),
+                        price: price.amount,
+                        currency: price.currency().to_string(),
+                        unit_of_measure: price.unit_of_measure.clone(),
+                    });
+                }
+                self.subscriptions().record_alert_price(&rule.part_number, rule.direction, price.amount)?;
+            }
+        }
+
+        for trigger in &triggers {
+            self.run_alert_hook(trigger);
+        }
+        Ok(triggers)
+    }
+
+    /// Run `alert_command` for a fired alert, with its details in `MMC_ALERT_*` variables
+    fn run_alert_hook(&self, trigger: &AlertTrigger) {
+        let Some(command) = &self.alert_command else {
+            return;
+        };
+        let mut shell = if cfg!(windows) { Command::new("cmd") } else { Command::new("sh") };
+        shell.arg(if cfg!(windows) { "/C" } else { "-c" })
+            .arg(command)
+            .env("MMC_ALERT_PART", &trigger.rule.part_number)
+            .env("MMC_ALERT_DIRECTION", trigger.rule.direction.as_str())
+            .env("MMC_ALERT_THRESHOLD", trigger.rule.threshold.to_string())
+            .env("MMC_ALERT_PRICE", trigger.price.to_string())
+            .env("MMC_ALERT_CURRENCY", &trigger.currency)
+            .env("MMC_ALERT_UNIT", &trigger.unit_of_measure);
+        let failure = match shell.status() {
+            Ok(status) if status.success() => return,
+            Ok(status) => format!("alert_command exited with {}", status),
+            Err(e) => format!("Could not run alert_command: {}", e),
+        };
+        self.warn(Warning::new(WarningKind::AlertHookFailed, &trigger.rule.part_number, failure));
+    }
+}
+
+/// The price break with the smallest minimum quantity, which alerts compare against
+fn lowest_break(prices: &[PriceInfo]) -> Option<&PriceInfo> {
+    prices.iter().min_by(|a, b| a.minimum_quantity.total_cmp(&b.minimum_quantity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockApi, PART_NUMBER};
+
+    #[tokio::test]
+    async fn test_alerts_fire_on_crossing() {
+        let api = MockApi::start().await;
+        let mut client = api.logged_in_client().unwrap();
+        let hook_output = api.data_dir().join("alerts.txt");
+        if cfg!(unix) {
+            client.set_alert_command(Some(format!("echo \"$MMC_ALERT_PART $MMC_ALERT_PRICE\" >> '{}'", hook_output.display())));
+        }
+
+        // The fixture's smallest break is 9.50
+        client.add_alert(&PART_NUMBER.to_lowercase(), AlertDirection::Above, 5.0).unwrap();
+        client.add_alert(PART_NUMBER, AlertDirection::Below, 2.0).unwrap();
+        assert!(client.add_alert(PART_NUMBER, AlertDirection::Below, -1.0).is_err());
+
+        let (results, triggers) = client.check_alerts().await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(triggers.len(), 1);
+        assert_eq!((triggers[0].rule.direction, triggers[0].price), (AlertDirection::Above, 9.5));
+        if cfg!(unix) {
+            assert_eq!(std::fs::read_to_string(&hook_output).unwrap(), "91290A115 9.5\n");
+        }
+
+        // Still above: no new alert until the price drops back and crosses again
+        assert!(client.check_alerts().await.unwrap().1.is_empty());
+        assert_eq!(client.alerts().unwrap()[0].last_price, Some(9.5));
+        assert_eq!(client.remove_alerts(PART_NUMBER).unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_price_all_fires_alerts() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        client.subscriptions().add_part(PART_NUMBER).unwrap();
+        client.add_alert(PART_NUMBER, AlertDirection::Below, 20.0).unwrap();
+
+        // Crossing from above the threshold to the fixture's 9.50
+        client.subscriptions().record_alert_price(PART_NUMBER, AlertDirection::Below, 25.0).unwrap();
+        let (results, triggers) = client.get_price_all_with_alerts(None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(triggers.len(), 1);
+        assert_eq!((triggers[0].rule.part_number.as_str(), triggers[0].price), (PART_NUMBER, 9.5));
+
+        // Untagged parts aren't priced, so their alerts aren't checked
+        assert!(client.get_price_all_with_alerts(Some("robot-v2")).await.unwrap().1.is_empty());
+        assert_eq!(client.alerts().unwrap()[0].last_price, Some(9.5));
+    }
+}
@@ -0,0 +1,116 @@
+//! Alternates for a part from the local product cache
+//!
+//! Candidates are cached parts in the same category. They must match the part's defining
+//! specifications (thread, length, head, ...), may differ in material or finish, and are
+//! ranked by how many of the remaining specifications they share.
+
+use crate::models::product::{ProductDetail, ProductDiff, SpecComparison};
+use crate::utils::error::Result;
+use serde::Serialize;
+
+/// Attribute words that define fit; an alternate must match every such specification
+const KEY_ATTRIBUTE_WORDS: &[&str] = &["thread", "length", "head", "diameter", "drive", "size"];
+
+/// Attribute words an alternate may freely differ in (and which don't count toward similarity)
+const VARIANT_ATTRIBUTE_WORDS: &[&str] = &["material", "finish", "color", "coating", "plating", "packaging", "pack quantity", "replace"];
+
+/// A cached part that could stand in for another
+#[derive(Debug, Clone, Serialize)]
+pub struct Alternate {
+    pub part_number: String,
+    pub description: String,
+    pub similarity: f64, // Share of the original's comparable specifications this part matches, 0.0 to 1.0
+    pub differences: Vec<SpecComparison>, // Specifications that differ from the original
+}
+
+/// Alternate lookup methods for McmasterClient
+impl super::api::McmasterClient {
+    /// Find up to `limit` cached active parts that can replace `product`, most similar first
+    ///
+    /// Only parts already in the cache are considered; `mmc refresh` or `mmc info` adds more.
+    pub async fn find_alternates(&self, product: &str, limit: usize) -> Result<(ProductDetail, Vec<Alternate>)> {
+        let original = self.get_product(product).await?;
+        let candidates = self.cache().products_in_category(&original.product_category)?;
+
+        let mut alternates: Vec<Alternate> = candidates.iter()
+            .filter(|candidate| candidate.is_active() && !candidate.part_number.eq_ignore_ascii_case(&original.part_number))
+            .filter_map(|candidate| rank_alternate(&original, candidate))
+            .collect();
+        alternates.sort_by(|a, b| b.similarity.total_cmp(&a.similarity).then_with(|| a.part_number.cmp(&b.part_number)));
+        alternates.truncate(limit);
+        Ok((original, alternates))
+    }
+}
+
+/// Score a candidate against the original, or `None` when a defining specification differs
+fn rank_alternate(original: &ProductDetail, candidate: &ProductDetail) -> Option<Alternate> {
+    let diff = ProductDiff::new(original, candidate);
+    let has_word = |spec: &SpecComparison, words: &[&str]| {
+        let attribute = spec.attribute.to_lowercase();
+        words.iter().any(|word| attribute.contains(word))
+    };
+
+    // Compare only what the original lists; extra attributes on the candidate don't matter
+    let comparable: Vec<&SpecComparison> = diff.specifications.iter()
+        .filter(|spec| !spec.first.is_empty() && !has_word(spec, VARIANT_ATTRIBUTE_WORDS))
+        .collect();
+    if comparable.iter().any(|spec| spec.differs && has_word(spec, KEY_ATTRIBUTE_WORDS)) {
+        return None;
+    }
+
+    let matching = comparable.iter().filter(|spec| !spec.differs).count();
+    let similarity = if comparable.is_empty() { 0.0 } else { matching as f64 / comparable.len() as f64 };
+    Some(Alternate {
+        part_number: candidate.part_number.clone(),
+        description: candidate.detail_description.clone(),
+        similarity,
+        differences: diff.differences().cloned().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{product_fixture, MockApi, PART_NUMBER};
+    use serde_json::json;
+
+    fn screw(part_number: &str, status: &str, specifications: serde_json::Value) -> ProductDetail {
+        let mut product = product_fixture();
+        product["PartNumber"] = json!(part_number);
+        product["ProductStatus"] = json!(status);
+        product["Specifications"] = specifications;
+        serde_json::from_value(product).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_alternates_match_key_specs() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+
+        let stainless = json!([
+            {"Attribute": "Thread Size", "Values": ["M3"]},
+            {"Attribute": "Material", "Values": ["18-8 Stainless Steel"]}
+        ]);
+        let larger_thread = json!([
+            {"Attribute": "Thread Size", "Values": ["M4"]},
+            {"Attribute": "Material", "Values": ["Alloy Steel"]}
+        ]);
+        for product in [
+            screw("91292A111", "Active", stainless.clone()),
+            screw("91292A112", "Discontinued", stainless),
+            screw("91290A211", "Active", larger_thread),
+        ] {
+            client.cache().store_product(&product).unwrap();
+        }
+        let mut washer = screw("93475A210", "Active", json!([{"Attribute": "Thread Size", "Values": ["M3"]}]));
+        washer.product_category = "Washers".to_string();
+        client.cache().store_product(&washer).unwrap();
+
+        let (original, alternates) = client.find_alternates(PART_NUMBER, 10).await.unwrap();
+        assert_eq!(original.part_number, PART_NUMBER);
+        assert_eq!(alternates.len(), 1);
+        assert_eq!(alternates[0].part_number, "91292A111");
+        assert_eq!(alternates[0].similarity, 1.0);
+        assert_eq!(alternates[0].differences[0].attribute, "Material");
+    }
+}
@@ -1,39 +1,89 @@
 //! Core McMaster-Carr API client
 
-use anyhow::Result;
-use reqwest::{Client, Identity};
+use crate::utils::error::{ClientError, Result};
+use reqwest::{Client, Request, RequestBuilder, Response, StatusCode};
+use reqwest::header::AUTHORIZATION;
+use serde::Serialize;
+use chrono::Utc;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{MutexGuard, PoisonError};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
 use serde_json;
 
-use crate::config::paths::{expand_path};
-use crate::models::auth::{Credentials, ErrorResponse};
-use crate::models::product::{ProductDetail, PriceInfo};
-use crate::utils::output::{OutputFormat, ProductField};
-use crate::client::subscriptions::SubscriptionManager;
+use crate::config::paths::{expand_path, get_cache_path, get_token_path};
+use crate::config::settings::NetworkConfig;
+use crate::models::auth::Credentials;
+use crate::models::change::{ChangeRecord, ChangesResponse};
+use crate::models::filter::PartFilter;
+use crate::models::product::{PriceInfo, PriceQuote, ProductDetail, ProductDiff, DEFAULT_CURRENCY};
+use crate::models::warning::{Warning, WarningKind};
+use crate::client::cancel::Cancellation;
+use crate::client::auth::{bearer_token, Session, TOKEN_RENEWAL_MARGIN};
+use crate::integrations::dxf::DxfOptions;
+use crate::client::cache::ProductCache;
+use crate::client::metrics::Metrics;
+use crate::client::certificate::{load_identity, read_certificate_info, CertificateInfo};
+use crate::client::subscriptions::{SubscriptionManager, TrackedPart};
+use crate::client::transcript::{build_response, Transcript};
+use crate::client::downloads::DEFAULT_DOWNLOAD_JOBS;
+use crate::client::journal::Operation;
+use crate::client::pinning::{parse_pin, PinnedKeys, SpkiHash};
+
+/// Root of the McMaster-Carr Product Information API
+pub const API_BASE_URL: &str = "https://api.mcmaster.com";
 
 /// Main client for McMaster-Carr API operations
 pub struct McmasterClient {
     pub(crate) client: Client,
-    pub(crate) token: Option<String>,
+    pub(crate) base_url: String, // API root, replaced by a mock server in tests
+    pub(crate) session: std::sync::Mutex<Option<Session>>, // Login token, replaced when it's renewed mid-run
+    pub(crate) token_path: PathBuf, // Where the login token is saved between runs
     pub(crate) credentials: Option<Credentials>,
     pub(crate) quiet_mode: bool, // For suppressing output when in JSON mode
-    subscription_manager: std::sync::Mutex<SubscriptionManager>,
+    pub(crate) download_jobs: usize, // Maximum number of concurrent downloads
+    pub(crate) force_download: bool, // Re-download files even if the manifest says they're unchanged
+    pub(crate) extract_archives: bool, // Unpack downloads that turn out to be ZIP archives
+    pub(crate) embed_metadata: bool, // Write the part number and description into STEP headers
+    pub(crate) dxf_postprocess: Option<DxfOptions>, // Convert, scale or strip downloaded DXF drawings
+    pub(crate) show_progress: bool, // Draw download progress bars
+    pub(crate) offline: bool, // Serve product data from the cache and refuse network calls
+    pub(crate) currency: String, // ISO 4217 code for prices the API doesn't label
+    pub(crate) download_dir: Option<PathBuf>, // Root for downloads instead of ~/Downloads/mmc
+    pub(crate) subscription_manager: std::sync::Mutex<SubscriptionManager>,
+    pub(crate) product_cache: std::sync::Mutex<ProductCache>,
+    pub(crate) warnings: std::sync::Mutex<Vec<Warning>>, // Collected until the caller takes them
+    pub(crate) certificate: Option<CertificateInfo>, // Details of the loaded client certificate, when readable
+    pub(crate) transcript: Option<Transcript>, // Record API traffic to, or replay it from, a directory
+    pub(crate) rate_limiter: RateLimiter, // Spaces out requests to stay within API limits
+    pub(crate) responses: std::sync::Mutex<HashMap<String, Arc<OnceCell<BufferedResponse>>>>, // Successful GETs by URL, shared for the session
+    pub(crate) metrics: Arc<Metrics>, // Request, cache and download counters for --metrics-file
+    pub(crate) pinned_spki: PinnedKeys, // Server or CA public keys to accept; empty accepts any valid certificate
+    pub(crate) auto_login: bool, // Log in with the stored credentials when there's no token
+    pub(crate) alert_command: Option<String>, // Shell command run when a price alert fires
+    pub(crate) renewing: tokio::sync::Mutex<()>, // Held while logging in, so concurrent requests share one login
+    pub(crate) pins_checked: OnceCell<()>, // Set once the server's key matched before any credentials were sent
+    pub(crate) cancellation: Cancellation, // Stops refresh, sync and bulk downloads partway through
 }
 
 impl McmasterClient {
     /// Create new client with optional credentials
     pub fn new_with_credentials(credentials: Option<Credentials>) -> Result<Self> {
-        Self::new_with_credentials_internal(credentials, false)
+        Self::new_with_network(credentials, &NetworkConfig::default(), false)
     }
 
     /// Create new client with optional credentials in quiet mode
     pub fn new_with_credentials_quiet(credentials: Option<Credentials>) -> Result<Self> {
-        Self::new_with_credentials_internal(credentials, true)
+        Self::new_with_network(credentials, &NetworkConfig::default(), true)
     }
 
-    /// Internal constructor for client
-    fn new_with_credentials_internal(credentials: Option<Credentials>, quiet: bool) -> Result<Self> {
-        let mut client_builder = Client::builder();
+    /// Create new client that connects through the proxy and CA bundle from `[network]`
+    pub fn new_with_network(credentials: Option<Credentials>, network: &NetworkConfig, quiet: bool) -> Result<Self> {
+        let mut client_builder = network_builder(network)?;
+        let mut certificate = None;
 
         // Try to find and load certificate
         if let Some(ref creds) = credentials {
@@ -50,398 +100,1042 @@ impl McmasterClient {
                     println!("Loading client certificate: {}", cert_path.display());
                 }
                 
-                // Read certificate file
-                let cert_data = fs::read(&cert_path)
-                    .map_err(|e| anyhow::anyhow!("Failed to read certificate file '{}': {}", cert_path.display(), e))?;
-
-                // Get certificate password
+                // Get certificate password and separate PEM key, if any
                 let cert_password = creds.certificate_password
                     .as_deref()
                     .unwrap_or("");
+                let key_path = creds.certificate_key_path.as_deref().map(expand_path);
 
-                // Create identity from certificate
-                let identity = Identity::from_pkcs12_der(&cert_data, cert_password)
-                    .map_err(|e| anyhow::anyhow!("Failed to create identity from PKCS12 certificate: {}. Try converting your certificate with: openssl pkcs12 -in cert.pfx -out cert.pem -nodes -legacy && openssl pkcs12 -export -in cert.pem -out cert_new.pfx", e))?;
+                // Create identity from certificate (PKCS#12 or PEM)
+                let identity = load_identity(&cert_path, key_path.as_deref(), cert_password)?;
 
                 client_builder = client_builder.identity(identity);
+                certificate = read_certificate_info(&cert_path, cert_password).ok();
                 
                 if !quiet {
                     println!("Client certificate loaded successfully");
                 }
             } else {
-                return Err(anyhow::anyhow!("No certificate found. Please specify certificate_path in credentials or place certificate at ~/.config/mmc/certificate.pfx"));
+                return Err(ClientError::Certificate("No certificate found. Please specify certificate_path in credentials or place certificate at ~/.config/mmc/certificate.pfx".to_string()));
             }
         }
 
-        // Build the HTTP client with certificate validation bypass (McMaster-Carr API specific)
+        let pinned_spki: Vec<SpkiHash> = network.pinned_spki.iter().filter_map(|pin| parse_pin(pin)).collect();
+        if network.insecure {
+            client_builder = client_builder.danger_accept_invalid_certs(true);
+        }
+        if !pinned_spki.is_empty() {
+            client_builder = client_builder.tls_info(true);
+        }
         let client = client_builder
-            .danger_accept_invalid_certs(true)
             .build()
-            .map_err(|e| anyhow::anyhow!("Failed to create HTTP client: {}", e))?;
+            .map_err(|e| ClientError::Config(format!("Failed to create HTTP client: {}", e)))?;
 
-        // Initialize subscription manager
+        // Initialize subscription manager and product cache
         let subscription_manager = SubscriptionManager::new(&credentials)?;
+        let product_cache = ProductCache::open(get_cache_path())?;
+
+        let mut warnings = Vec::new();
+        if let Some(info) = certificate.as_ref().filter(|info| info.expires_soon(Utc::now())) {
+            let message = if info.is_expired(Utc::now()) {
+                format!("Client certificate expired on {}", info.not_after.format("%Y-%m-%d"))
+            } else {
+                format!("Client certificate expires on {} ({} days left)", info.not_after.format("%Y-%m-%d"), info.days_until_expiry(Utc::now()))
+            };
+            warnings.push(Warning::new(WarningKind::CertificateExpiring, info.path.display().to_string(), message));
+        }
+
+        if network.insecure {
+            let message = if pinned_spki.is_empty() {
+                "Server certificates are not being validated (--insecure)"
+            } else {
+                "Server certificates are only checked against pinned_spki (--insecure)"
+            };
+            warnings.push(Warning::general(WarningKind::CertificateValidationDisabled, message));
+        }
 
-        Ok(McmasterClient {
+        let mut client = Self::from_parts(client, credentials, quiet, subscription_manager, product_cache);
+        client.pinned_spki = PinnedKeys::new(pinned_spki, network);
+        client.certificate = certificate;
+        client.warnings = std::sync::Mutex::new(warnings);
+        Ok(client)
+    }
+
+    /// Assemble a client from an HTTP client and already opened local stores
+    pub(crate) fn from_parts(
+        client: Client,
+        credentials: Option<Credentials>,
+        quiet: bool,
+        subscription_manager: SubscriptionManager,
+        product_cache: ProductCache,
+    ) -> Self {
+        McmasterClient {
             client,
-            token: None,
+            base_url: API_BASE_URL.to_string(),
+            session: std::sync::Mutex::new(None),
+            token_path: get_token_path(),
             credentials,
             quiet_mode: quiet,
+            download_jobs: DEFAULT_DOWNLOAD_JOBS,
+            force_download: false,
+            extract_archives: false,
+            embed_metadata: false,
+            dxf_postprocess: None,
+            show_progress: true,
+            offline: false,
+            currency: DEFAULT_CURRENCY.to_string(),
+            download_dir: None,
             subscription_manager: std::sync::Mutex::new(subscription_manager),
-        })
+            product_cache: std::sync::Mutex::new(product_cache),
+            warnings: std::sync::Mutex::new(Vec::new()),
+            certificate: None,
+            transcript: None,
+            rate_limiter: RateLimiter::default(),
+            responses: std::sync::Mutex::new(HashMap::new()),
+            metrics: Arc::new(Metrics::default()),
+            pinned_spki: PinnedKeys::default(),
+            auto_login: false,
+            alert_command: None,
+            renewing: tokio::sync::Mutex::new(()),
+            pins_checked: OnceCell::new(),
+            cancellation: Cancellation::default(),
+        }
+    }
+
+    /// Details of the loaded client certificate
+    pub fn certificate_status(&self) -> Result<&CertificateInfo> {
+        self.certificate.as_ref().ok_or_else(|| ClientError::Certificate(
+            "No readable client certificate is loaded. Set certificate_path in credentials or run mmc init-cert".to_string(),
+        ))
+    }
+
+    /// Send requests to another API root, such as a mock server (default: https://api.mcmaster.com)
+    pub fn set_base_url(&mut self, base_url: &str) {
+        self.base_url = base_url.trim_end_matches('/').to_string();
     }
 
-    /// Add product to subscription
-    pub async fn add_product(&self, product: &str) -> Result<()> {
-        let token = self.token.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("Not authenticated. Please login first with 'mmc login'")
-        })?;
+    /// Absolute URL for an API path like `/v1/products`
+    pub(crate) fn api_url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Set the root directory for downloads (default: ~/Downloads/mmc)
+    pub fn set_download_dir(&mut self, dir: Option<PathBuf>) {
+        self.download_dir = dir;
+    }
+
+    /// Set the maximum number of concurrent downloads
+    pub fn set_download_jobs(&mut self, jobs: usize) {
+        self.download_jobs = jobs.max(1);
+    }
+
+    /// Re-download files even when the download manifest says they're unchanged
+    pub fn set_force_download(&mut self, force: bool) {
+        self.force_download = force;
+    }
+
+    /// Unpack downloads that arrive as ZIP archives, naming their contents after the part
+    pub fn set_extract_archives(&mut self, extract: bool) {
+        self.extract_archives = extract;
+    }
+
+    /// Write the part number and description into the header of each downloaded STEP file
+    pub fn set_embed_metadata(&mut self, embed: bool) {
+        self.embed_metadata = embed;
+    }
+
+    /// Post-process downloaded DXF drawings with these options, from `[postprocess.dxf]`
+    pub fn set_dxf_postprocess(&mut self, options: Option<DxfOptions>) {
+        self.dxf_postprocess = options;
+    }
+
+    /// Log in with the stored credentials when a command needs a token and there isn't one,
+    /// and again when the token expires partway through a run
+    pub fn set_auto_login(&mut self, auto_login: bool) {
+        self.auto_login = auto_login;
+    }
+
+    /// Run this shell command when a price alert fires, with the details in `MMC_ALERT_*` variables
+    pub fn set_alert_command(&mut self, command: Option<String>) {
+        self.alert_command = command;
+    }
+
+    /// Get the current token or fail with `ClientError::NotAuthenticated`
+    ///
+    /// With auto-login on and credentials loaded, a missing token is fetched by logging
+    /// in, and one about to expire is renewed first, so long runs keep a working token.
+    pub(crate) async fn require_token(&self) -> Result<String> {
+        self.require_online()?;
+        match self.current_session() {
+            Some(session) if self.can_renew_token() && session.expires_within(TOKEN_RENEWAL_MARGIN) => {
+                self.renew_token(Some(&session.token)).await
+            }
+            Some(session) => Ok(session.token),
+            None if self.can_renew_token() => self.renew_token(None).await,
+            None => Err(ClientError::NotAuthenticated),
+        }
+    }
+
+    /// Fail if network calls are forbidden by offline mode
+    pub(crate) fn require_online(&self) -> Result<()> {
+        if self.offline {
+            return Err(ClientError::Offline("this command needs the McMaster-Carr API and can't run with --offline".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Set the ISO 4217 currency assumed for prices the API doesn't label (default: USD)
+    pub fn set_currency(&mut self, currency: &str) {
+        self.currency = currency.trim().to_uppercase();
+    }
+
+    /// Serve product data from the local cache only and refuse network calls
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// Save every API request/response pair, with credentials removed, into `dir`
+    pub fn set_record_dir(&mut self, dir: &Path) -> Result<()> {
+        self.transcript = Some(Transcript::record(dir)?);
+        Ok(())
+    }
+
+    /// Answer API requests from a directory recorded with `set_record_dir` instead of the network
+    pub fn set_replay_dir(&mut self, dir: &Path) -> Result<()> {
+        self.transcript = Some(Transcript::replay(dir)?);
+        // Recorded tokens are redacted, so any token will do
+        self.session.get_mut().unwrap_or_else(PoisonError::into_inner).get_or_insert_with(|| Session::new("replay".to_string(), None));
+        Ok(())
+    }
+
+    /// Limit API requests to `requests_per_second` after an initial `burst` (0 disables limiting)
+    pub fn set_rate_limit(&mut self, requests_per_second: f64, burst: u32) {
+        self.rate_limiter = RateLimiter::new(requests_per_second, burst);
+    }
+
+    /// Count requests, cache use and downloads in `metrics`, which the caller can render afterwards
+    pub fn set_metrics(&mut self, metrics: Arc<Metrics>) {
+        self.metrics = metrics;
+    }
+
+    /// Cancelling this stops a running refresh, sync or bulk download, which then reports what's left
+    pub fn cancellation(&self) -> &Cancellation {
+        &self.cancellation
+    }
+
+    /// Whether responses come from a recorded transcript rather than the API
+    pub(crate) fn is_replaying(&self) -> bool {
+        matches!(self.transcript, Some(Transcript::Replay { .. }))
+    }
+
+    /// Send an API request, through the transcript when recording or replaying
+    ///
+    /// A request whose token was rejected is sent once more with a renewed token when the
+    /// stored credentials allow it, so a token that expires during a long run doesn't fail
+    /// every request after it.
+    pub(crate) async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let request = request.build()?;
+        let retry = request.try_clone().filter(|_| self.can_renew_token());
+        let response = self.send_once(request).await?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+        let Some((mut retry, stale)) = retry.and_then(|retry| bearer_token(&retry).map(|token| (retry, token))) else {
+            return Ok(response);
+        };
+        let token = self.renew_token(Some(&stale)).await?;
+        let header = format!("Bearer {}", token).parse()
+            .map_err(|_| ClientError::Auth("Login returned a token that can't be sent in a header".to_string()))?;
+        retry.headers_mut().insert(AUTHORIZATION, header);
+        self.send_once(retry).await
+    }
+
+    /// Send an API request as is, without renewing a rejected token
+    pub(crate) async fn send_once(&self, request: Request) -> Result<Response> {
+        if !self.is_replaying() {
+            self.check_pins().await?;
+            self.rate_limiter.acquire().await;
+        }
+        let started = Instant::now();
+        let response = match &self.transcript {
+            Some(transcript) => transcript.send(&self.client, request).await,
+            None => match self.client.execute(request).await {
+                // Pooled connections may be replaced, so every new one is checked too
+                Ok(response) => self.pinned_spki.check(&response).await.map(|_| response),
+                Err(e) => Err(e.into()),
+            },
+        };
+        let status = response.as_ref().ok().map(Response::status);
+        self.metrics.record_request(status, started.elapsed());
+        response
+    }
+
+    /// With pinned keys, check the API server with a bare request before anything carries a token or password
+    async fn check_pins(&self) -> Result<()> {
+        if self.pinned_spki.is_empty() {
+            return Ok(());
+        }
+        self.pins_checked.get_or_try_init(|| async {
+            let response = self.client.get(&self.base_url).send().await?;
+            self.pinned_spki.check(&response).await
+        }).await?;
+        Ok(())
+    }
+
+    /// GET an API URL, sharing one request between all callers in this session
+    ///
+    /// Concurrent callers wait for the request already in flight, and later callers reuse
+    /// its successful response, so e.g. `cad` and `info` for the same part fetch the
+    /// product once. Failed requests aren't kept; the next caller tries again.
+    pub(crate) async fn get_shared(&self, url: &str, token: &str) -> Result<Response> {
+        let cell = self.responses.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(url.to_string())
+            .or_default()
+            .clone();
+
+        let mut fetched_now = false; // Whether this caller sent the request rather than reusing a response
+        let fetched = cell.get_or_try_init(|| {
+            fetched_now = true;
+            async {
+                let response = self.send(self.client.get(url)
+                    .header("Authorization", format!("Bearer {}", token)))
+                    .await
+                    .map_err(Uncached::Error)?;
+                let buffered = BufferedResponse::read(response).await.map_err(Uncached::Error)?;
+                if buffered.status.is_success() {
+                    Ok(buffered)
+                } else {
+                    Err(Uncached::Failed(buffered))
+                }
+            }
+        }).await;
+        self.metrics.record_cache(!fetched_now);
+
+        match fetched {
+            Ok(buffered) => buffered.to_response(),
+            Err(Uncached::Failed(buffered)) => buffered.to_response(),
+            Err(Uncached::Error(e)) => Err(e),
+        }
+    }
+
+    /// Show or hide download progress bars
+    pub fn set_show_progress(&mut self, show: bool) {
+        self.show_progress = show;
+    }
+
+    /// Add product to subscription, returning the product's details
+    ///
+    /// Parts that weren't tracked yet are journaled so `mmc undo` can unsubscribe them again.
+    pub async fn add_product(&self, product: &str) -> Result<ProductDetail> {
+        let was_tracked = self.subscriptions().has_part(product).unwrap_or(false);
+        let detail = self.subscribe(product).await?;
+        if !was_tracked {
+            self.journal_change(Operation::Add, vec![product.trim().to_uppercase()], Vec::new());
+        }
+        Ok(detail)
+    }
+
+    /// Subscribe to a product through the API and track it locally, without journaling
+    pub(crate) async fn subscribe(&self, product: &str) -> Result<ProductDetail> {
+        let token = self.require_token().await?;
 
         // Use correct API format from documentation
-        let response = self.client.put("https://api.mcmaster.com/v1/products")
+        let response = self.send(self.client.put(self.api_url("/v1/products"))
             .header("Authorization", format!("Bearer {}", token))
             .json(&serde_json::json!({
                 "URL": format!("https://mcmaster.com/{}", product)
-            }))
-            .send()
+            })))
             .await?;
 
         if response.status().is_success() {
             // Add to local tracking after successful API call
-            if let Ok(mut manager) = self.subscription_manager.lock() {
-                let _ = manager.add_part(product); // Ignore result as local tracking is supplementary
-            }
+            self.track_part(product);
 
-            // Always show confirmation for add operation, even in quiet mode
-            println!("✅ Added {} to subscription", product);
-            let product_detail: ProductDetail = response.json().await?;
-            println!("   {} - {}", product_detail.detail_description, product_detail.family_description);
+            let mut product_detail: ProductDetail = response.json().await?;
+            self.sanitize_product(&mut product_detail);
+            self.cache_product(&product_detail);
+            Ok(product_detail)
         } else {
-            let error_text = response.text().await?;
-            if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&error_text) {
-                return Err(anyhow::anyhow!(
-                    "Failed to add product: {}",
-                    error_response.error_message.unwrap_or("Unknown error".to_string())
-                ));
-            } else {
-                return Err(anyhow::anyhow!("Failed to add product: {}", error_text));
-            }
+            Err(ClientError::from_http(response, "Failed to add product").await)
         }
-
-        Ok(())
     }
 
     /// Remove product from subscription
+    ///
+    /// The part's tags, note and metadata are journaled so `mmc undo` can restore them.
     pub async fn remove_product(&self, product: &str) -> Result<()> {
-        let token = self.token.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("Not authenticated. Please login first with 'mmc login'")
-        })?;
+        let tracked = self.subscriptions().get_tracked_part(product).ok().flatten();
+        self.unsubscribe(product).await?;
+        let part_number = product.trim().to_uppercase();
+        self.journal_change(Operation::Remove, vec![part_number], tracked.into_iter().collect());
+        Ok(())
+    }
+
+    /// Unsubscribe from a product through the API and stop tracking it, without journaling
+    pub(crate) async fn unsubscribe(&self, product: &str) -> Result<()> {
+        let token = self.require_token().await?;
 
         // Use correct API format from documentation
-        let response = self.client.delete("https://api.mcmaster.com/v1/products")
+        let response = self.send(self.client.delete(self.api_url("/v1/products"))
             .header("Authorization", format!("Bearer {}", token))
             .json(&serde_json::json!({
                 "URL": format!("https://mcmaster.com/{}", product)
-            }))
-            .send()
+            })))
             .await?;
 
         if response.status().is_success() {
             // Remove from local tracking after successful API call
-            if let Ok(mut manager) = self.subscription_manager.lock() {
-                let _ = manager.remove_part(product); // Ignore result as local tracking is supplementary
-            }
-
-            // Always show confirmation for remove operation, even in quiet mode
-            println!("✅ Removed {} from subscription", product);
+            self.untrack_part(product);
         } else {
-            let error_text = response.text().await?;
-            if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&error_text) {
-                return Err(anyhow::anyhow!(
-                    "Failed to remove product: {}",
-                    error_response.error_message.unwrap_or("Unknown error".to_string())
-                ));
-            } else {
-                return Err(anyhow::anyhow!("Failed to remove product: {}", error_text));
-            }
+            return Err(ClientError::from_http(response, "Failed to remove product").await);
         }
 
         Ok(())
     }
 
-    /// Get detailed product information
-    pub async fn get_product(&self, product: &str, output_format: OutputFormat, fields_str: &str) -> Result<()> {
-        let token = self.token.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("Not authenticated. Please login first with 'mmc login'")
-        })?;
+    /// Get detailed product information (from the cache when offline)
+    pub async fn get_product(&self, product: &str) -> Result<ProductDetail> {
+        if self.offline {
+            let cached = self.cache().get_product(product)?;
+            self.metrics.record_cache(cached.is_some());
+            let mut detail = cached
+                .map(|cached| cached.detail)
+                .ok_or_else(|| not_cached("product details", product))?;
+            self.attach_item_number(&mut detail);
+            return Ok(detail);
+        }
 
-        let url = format!("https://api.mcmaster.com/v1/products/{}", product);
-        let response = self.client.get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
-            .await?;
+        let token = self.require_token().await?;
+
+        let url = self.api_url(&format!("/v1/products/{}", product));
+        let response = self.get_shared(&url, &token).await?;
 
         if response.status().is_success() {
-            let product_detail: ProductDetail = response.json().await?;
+            let mut product_detail: ProductDetail = response.json().await?;
+            self.sanitize_product(&mut product_detail);
             
             // Add to local tracking after successful API call (auto-discovery)
-            if let Ok(mut manager) = self.subscription_manager.lock() {
-                let _ = manager.add_part(product); // Ignore result as local tracking is supplementary
-            }
-            
-            match output_format {
-                OutputFormat::Json => {
-                    println!("{}", serde_json::to_string_pretty(&product_detail)?);
-                }
-                OutputFormat::Human => {
-                    self.display_product_human(&product_detail, fields_str)?;
-                }
-            }
+            self.track_part(product);
+            self.cache_product(&product_detail);
+            self.attach_item_number(&mut product_detail);
+
+            Ok(product_detail)
         } else {
-            let status = response.status();
-            let error_text = response.text().await?;
-            
-            if status.as_u16() == 404 {
-                return Err(anyhow::anyhow!(
-                    "Product {} is not in your subscription. Add it with 'mmc add {}'",
-                    product, product
-                ));
-            }
-            
-            if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&error_text) {
-                return Err(anyhow::anyhow!(
-                    "Failed to get product: {}",
-                    error_response.error_message.unwrap_or("Unknown error".to_string())
-                ));
-            } else {
-                return Err(anyhow::anyhow!("Failed to get product: {}", error_text));
+            if response.status().as_u16() == 404 {
+                return Err(ClientError::NotSubscribed(product.to_string()));
             }
+
+            Err(ClientError::from_http(response, "Failed to get product").await)
         }
+    }
 
-        Ok(())
+    /// Clean up malformed specification data, recording a warning for each fix
+    pub(crate) fn sanitize_product(&self, product: &mut ProductDetail) {
+        for warning in product.sanitize() {
+            self.warn(warning);
+        }
     }
 
-    /// Display product information in human-readable format
-    fn display_product_human(&self, product: &ProductDetail, fields_str: &str) -> Result<()> {
-        let fields = ProductField::parse_fields(fields_str);
-        
-        for field in fields {
-            match field {
-                ProductField::PartNumber => {
-                    println!("📦 Part Number: {}", product.part_number);
-                }
-                ProductField::DetailDescription => {
-                    println!("📝 Description: {}", product.detail_description);
-                }
-                ProductField::FamilyDescription => {
-                    println!("🏷️ Family: {}", product.family_description);
-                }
-                ProductField::Category => {
-                    println!("📂 Category: {}", product.product_category);
-                }
-                ProductField::Status => {
-                    println!("🔄 Status: {}", product.product_status);
-                }
-                ProductField::AllSpecs => {
-                    println!("🔧 Specifications:");
-                    for spec in &product.specifications {
-                        println!("  • {}: {}", spec.attribute, spec.values.join(", "));
-                    }
-                }
-                ProductField::Specification(spec_name) => {
-                    if let Some(spec) = product.specifications.iter()
-                        .find(|s| s.attribute.eq_ignore_ascii_case(&spec_name)) {
-                        println!("🔧 {}: {}", spec.attribute, spec.values.join(", "));
-                    }
-                }
-                ProductField::BasicInfo => {
-                    println!("📦 Part Number: {}", product.part_number);
-                    println!("📝 Description: {}", product.detail_description);
-                    println!("🏷️ Family: {}", product.family_description);
-                    println!("📂 Category: {}", product.product_category);
-                    println!("🔄 Status: {}", product.product_status);
-                }
-            }
+    /// Record a non-fatal warning for the caller to collect with `take_warnings`
+    pub(crate) fn warn(&self, warning: Warning) {
+        self.warnings.lock().unwrap_or_else(PoisonError::into_inner).push(warning);
+    }
+
+    /// Take the warnings collected since the last call
+    pub fn take_warnings(&self) -> Vec<Warning> {
+        std::mem::take(&mut *self.warnings.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Lock the subscription manager, recovering it if a previous holder panicked
+    ///
+    /// Every mutation is its own SQLite transaction, so a panic mid-change rolls back and
+    /// leaves the database consistent; a warning records the recovery so it isn't silent.
+    pub(crate) fn subscriptions(&self) -> MutexGuard<'_, SubscriptionManager> {
+        self.subscription_manager.lock().unwrap_or_else(|poisoned| {
+            self.subscription_manager.clear_poison();
+            self.warn(Warning::general(
+                WarningKind::TrackingRecovered,
+                "Recovered local subscription tracking after an earlier failure; run 'mmc sync' to verify it",
+            ));
+            poisoned.into_inner()
+        })
+    }
+
+    /// The product cache, recovered if an earlier panic poisoned its lock
+    ///
+    /// Every write is its own statement, so the cache is consistent after a panic.
+    pub(crate) fn cache(&self) -> MutexGuard<'_, ProductCache> {
+        self.product_cache.lock().unwrap_or_else(|poisoned| {
+            self.product_cache.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    /// Save product details to the local cache, warning if that fails
+    pub(crate) fn cache_product(&self, detail: &ProductDetail) {
+        if let Err(e) = self.cache().store_product(detail) {
+            self.warn(Warning::new(WarningKind::CacheNotSaved, &detail.part_number, format!("Could not update product cache: {}", e)));
         }
-        
-        Ok(())
     }
 
-    /// Get product pricing information
-    pub async fn get_price(&self, product: &str, output_format: OutputFormat) -> Result<()> {
-        let token = self.token.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("Not authenticated. Please login first with 'mmc login'")
-        })?;
+    /// Add a part to local tracking, warning if the subscription file can't be updated
+    pub(crate) fn track_part(&self, product: &str) {
+        if let Err(e) = self.subscriptions().add_part(product) {
+            self.warn(Warning::new(WarningKind::TrackingNotSaved, product, format!("Could not update local subscription list: {}", e)));
+        }
+    }
 
-        let url = format!("https://api.mcmaster.com/v1/products/{}/price", product);
-        let response = self.client.get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
-            .await?;
+    /// Remove a part from local tracking, warning if the subscription file can't be updated
+    pub(crate) fn untrack_part(&self, product: &str) {
+        if let Err(e) = self.subscriptions().remove_part(product) {
+            self.warn(Warning::new(WarningKind::TrackingNotSaved, product, format!("Could not update local subscription list: {}", e)));
+        }
+    }
+
+    /// Get product pricing information (price breaks ordered by quantity)
+    pub async fn get_price(&self, product: &str) -> Result<Vec<PriceInfo>> {
+        if self.offline {
+            let cached = self.cache().get_prices(product)?;
+            self.metrics.record_cache(cached.is_some());
+            let mut price_infos = cached.ok_or_else(|| not_cached("prices", product))?;
+            self.fill_currency(&mut price_infos);
+            return Ok(price_infos);
+        }
+
+        let token = self.require_token().await?;
+
+        let url = self.api_url(&format!("/v1/products/{}/price", product));
+        let response = self.get_shared(&url, &token).await?;
 
         if response.status().is_success() {
-            let price_infos: Vec<PriceInfo> = response.json().await?;
+            let mut price_infos: Vec<PriceInfo> = response.json().await?;
+            // Order price breaks by quantity so output is stable between runs
+            price_infos.sort_by(|a, b| a.minimum_quantity.total_cmp(&b.minimum_quantity));
+            self.fill_currency(&mut price_infos);
             
             if price_infos.is_empty() {
-                return Err(anyhow::anyhow!("No pricing information available"));
+                return Err(ClientError::NotFound(format!("No pricing information available for {}", product)));
             }
             
             // Add to local tracking after successful API call (auto-discovery)
-            if let Ok(mut manager) = self.subscription_manager.lock() {
-                let _ = manager.add_part(product); // Ignore result as local tracking is supplementary
-            }
-            
-            match output_format {
-                OutputFormat::Json => {
-                    println!("{}", serde_json::to_string_pretty(&price_infos)?);
-                }
-                OutputFormat::Human => {
-                    println!("💰 Pricing for {}", product);
-                    let unit = &price_infos[0].unit_of_measure;
-                    for price_info in &price_infos {
-                        let qty = price_info.minimum_quantity;
-                        let qty_str = if qty == qty.floor() {
-                            format!("{}+", qty as i64)
-                        } else {
-                            format!("{}+", qty)
-                        };
-                        println!("   {:<8} -> ${:.4} per {}", qty_str, price_info.amount, unit);
-                    }
-                }
+            self.track_part(product);
+            if let Err(e) = self.cache().store_prices(product, &price_infos) {
+                self.warn(Warning::new(WarningKind::CacheNotSaved, product, format!("Could not update price cache: {}", e)));
             }
+
+            Ok(price_infos)
         } else {
-            let error_text = response.text().await?;
-            if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&error_text) {
-                return Err(anyhow::anyhow!(
-                    "Failed to get price: {}",
-                    error_response.error_message.unwrap_or("Unknown error".to_string())
-                ));
-            } else {
-                return Err(anyhow::anyhow!("Failed to get price: {}", error_text));
-            }
+            Err(ClientError::from_http(response, "Failed to get price").await)
         }
-
-        Ok(())
     }
 
-    /// Get recent changes since specified date
-    pub async fn get_changes(&self, start_date: &str) -> Result<()> {
-        let token = self.token.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("Not authenticated. Please login first with 'mmc login'")
-        })?;
+    /// Get products changed between two dates (end defaults to now)
+    ///
+    /// With `subscribed_only`, changes are limited to locally tracked parts.
+    pub async fn get_changes(&self, start_date: &str, end_date: Option<&str>, subscribed_only: bool) -> Result<Vec<ChangeRecord>> {
+        let token = self.require_token().await?;
 
-        let encoded_date = urlencoding::encode(start_date);
-        let url = format!("https://api.mcmaster.com/v1/changes?start={}", encoded_date);
+        let mut url = self.api_url(&format!("/v1/changes?start={}", urlencoding::encode(start_date)));
+        if let Some(end_date) = end_date {
+            url.push_str(&format!("&end={}", urlencoding::encode(end_date)));
+        }
         
-        let response = self.client.get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+        let response = self.send(self.client.get(&url)
+            .header("Authorization", format!("Bearer {}", token)))
             .await?;
 
-        if response.status().is_success() {
-            let changes: serde_json::Value = response.json().await?;
-            println!("{}", serde_json::to_string_pretty(&changes)?);
-        } else {
-            let error_text = response.text().await?;
-            if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&error_text) {
-                return Err(anyhow::anyhow!(
-                    "Failed to get changes: {}",
-                    error_response.error_message.unwrap_or("Unknown error".to_string())
-                ));
-            } else {
-                return Err(anyhow::anyhow!("Failed to get changes: {}", error_text));
-            }
+        if !response.status().is_success() {
+            return Err(ClientError::from_http(response, "Failed to get changes").await);
         }
 
-        Ok(())
+        let body = response.text().await?;
+        let mut changes = serde_json::from_str::<ChangesResponse>(&body)
+            .map_err(|e| ClientError::Parse(format!("Unexpected changes response: {}", e)))?
+            .into_records();
+
+        if subscribed_only {
+            let tracked = self.subscriptions().get_all_parts()?;
+            changes.retain(|change| tracked.contains(&change.part_number));
+        }
+
+        // Order by part number so output is stable between runs
+        changes.sort_by(|a, b| a.part_number.cmp(&b.part_number));
+        Ok(changes)
     }
 
-    /// List all locally tracked subscriptions
-    pub fn list_subscriptions(&self) -> Result<()> {
-        if let Ok(manager) = self.subscription_manager.lock() {
-            let parts = manager.get_all_parts();
-            let file_path = manager.get_file_path();
-            
-            println!("📁 Subscription file: {}", file_path.display());
-            
-            if parts.is_empty() {
-                println!("📭 No subscribed parts tracked locally");
-                println!("💡 Parts will be automatically tracked as you use them");
-            } else {
-                println!("📦 Locally tracked subscriptions ({} parts):", parts.len());
-                for part in parts {
-                    println!("  • {}", part);
-                }
+    /// List all locally tracked subscriptions (sorted)
+    pub fn list_subscriptions(&self) -> Result<Vec<String>> {
+        self.subscriptions().get_all_parts()
+    }
+
+    /// List locally tracked parts with their tags, optionally only those with a given tag
+    pub fn list_tracked_parts(&self, tag: Option<&str>) -> Result<Vec<TrackedPart>> {
+        self.subscriptions().get_tracked_parts(tag)
+    }
+
+    /// Tracked parts, optionally only those with a tag, whose cached details meet `filter`
+    ///
+    /// Parts that were never cached can't be checked, so they're left out with a warning.
+    pub fn find_tracked_parts(&self, tag: Option<&str>, filter: &PartFilter) -> Result<Vec<TrackedPart>> {
+        let parts = self.list_tracked_parts(tag)?;
+        if filter.is_empty() {
+            return Ok(parts);
+        }
+
+        let mut matching = Vec::new();
+        let mut uncached = 0;
+        for part in parts {
+            match self.cache().get_product(&part.part_number)? {
+                Some(cached) if filter.matches(&cached.detail) => matching.push(part),
+                Some(_) => {}
+                None => uncached += 1,
             }
-        } else {
-            return Err(anyhow::anyhow!("Failed to access subscription manager"));
         }
-        Ok(())
+        if uncached > 0 {
+            self.warn(Warning::general(
+                WarningKind::NotCached,
+                format!("{} tracked parts have no cached details and were left out; run 'mmc refresh' to cache them", uncached),
+            ));
+        }
+        Ok(matching)
     }
 
-    /// Import parts from a file into local subscription tracking
-    pub fn import_subscriptions(&self, import_path: &str) -> Result<()> {
-        if let Ok(mut manager) = self.subscription_manager.lock() {
-            let imported_count = manager.import_from_file(import_path)?;
-            if !self.quiet_mode {
-                println!("📥 Imported {} new parts from {}", imported_count, import_path);
-            }
-        } else {
-            return Err(anyhow::anyhow!("Failed to access subscription manager"));
+    /// Tag a locally tracked part (tracking it if needed), returning its tags
+    pub fn tag_part(&self, product: &str, tags: &[String]) -> Result<Vec<String>> {
+        self.subscriptions().add_tags(product, tags)
+    }
+
+    /// Remove tags from a locally tracked part, returning its remaining tags
+    pub fn untag_part(&self, product: &str, tags: &[String]) -> Result<Vec<String>> {
+        self.subscriptions().remove_tags(product, tags)
+    }
+
+    /// Get a locally tracked part with its tags, note and metadata
+    pub fn tracked_part(&self, product: &str) -> Result<TrackedPart> {
+        self.subscriptions().get_tracked_part(product)?
+            .ok_or_else(|| ClientError::NotFound(format!("{} is not tracked locally", product)))
+    }
+
+    /// Set or clear the note on a locally tracked part (tracking it if needed)
+    pub fn set_part_note(&self, product: &str, note: Option<&str>) -> Result<()> {
+        self.subscriptions().set_note(product, note)
+    }
+
+    /// Set (or with `None`, remove) a metadata value on a locally tracked part (tracking it if needed)
+    pub fn set_part_metadata(&self, product: &str, key: &str, value: Option<&str>) -> Result<()> {
+        self.subscriptions().set_metadata(product, key, value)
+    }
+
+    /// Set the currency of prices the API doesn't label with one
+    fn fill_currency(&self, price_infos: &mut [PriceInfo]) {
+        for price_info in price_infos {
+            price_info.currency.get_or_insert_with(|| self.currency.clone());
         }
-        Ok(())
+    }
+
+    /// Price an order of `quantity` pieces, converting to packs with `pack_size` or the size in the product's specifications
+    pub async fn quote_price(&self, product: &str, quantity: f64, pack_size: Option<u32>) -> Result<PriceQuote> {
+        let prices = self.get_price(product).await?;
+
+        let sold_individually = prices.iter().all(|price| price.unit_of_measure.eq_ignore_ascii_case("each"));
+        let pack_size = match pack_size {
+            Some(size) => Some(size),
+            None if sold_individually => Some(1),
+            // The pack size is only in the specifications; without it, quantities are in packs
+            None => self.get_product(product).await.ok().and_then(|detail| detail.pack_size()),
+        };
+
+        PriceQuote::new(&product.trim().to_uppercase(), &prices, quantity, pack_size)
+            .ok_or_else(|| ClientError::NotFound(format!("No pricing information available for {}", product)))
+    }
+
+    /// Fetch two products and pair up their specifications
+    pub async fn compare_products(&self, first: &str, second: &str) -> Result<ProductDiff> {
+        let (first, second) = tokio::try_join!(self.get_product(first), self.get_product(second))?;
+        Ok(ProductDiff::new(&first, &second))
+    }
+
+    /// Get pricing for every locally tracked part, optionally only those with a given tag
+    ///
+    /// A failure for one part is recorded in its entry rather than stopping the run.
+    pub async fn get_price_all(&self, tag: Option<&str>) -> Result<Vec<PartPrices>> {
+        let parts: Vec<String> = self.subscriptions().get_tracked_parts(tag)?
+            .into_iter()
+            .map(|part| part.part_number)
+            .collect();
+        self.get_prices(&parts).await
+    }
+
+    /// Get prices for each of the given parts, recording failures per part
+    pub async fn get_prices(&self, parts: &[String]) -> Result<Vec<PartPrices>> {
+        self.require_token().await?;
+
+        let mut results = Vec::with_capacity(parts.len());
+        for part in parts {
+            let (prices, error) = match self.get_price(part).await {
+                Ok(prices) => (prices, None),
+                Err(e @ (ClientError::NotAuthenticated | ClientError::AuthExpired)) => return Err(e),
+                Err(e) => (Vec::new(), Some(e.to_string())),
+            };
+            results.push(PartPrices {
+                part_number: part.clone(),
+                prices,
+                error,
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// Get the path of the local subscription file
+    pub fn subscription_file_path(&self) -> Result<PathBuf> {
+        Ok(self.subscriptions().get_file_path().clone())
+    }
+
+    /// Import parts from a file into local subscription tracking, returning the number of new parts
+    pub fn import_subscriptions(&self, import_path: &str) -> Result<usize> {
+        let imported = self.subscriptions().import_new_parts(import_path)?;
+        let count = imported.len();
+        self.journal_change(Operation::Import, imported, Vec::new());
+        Ok(count)
+    }
+
+    /// Export locally tracked parts (with tags, notes and metadata) to a flat file, returning the number of parts
+    pub fn export_subscriptions(&self, export_path: &str) -> Result<usize> {
+        self.subscriptions().export_to_file(export_path)
     }
 
     /// Sync local subscription list with API (verify each part is actually subscribed)
-    pub async fn sync_subscriptions(&self) -> Result<()> {
-        if let Ok(manager) = self.subscription_manager.lock() {
-            let parts = manager.get_all_parts();
-            if parts.is_empty() {
-                println!("📭 No locally tracked parts to sync");
-                return Ok(());
-            }
+    ///
+    /// If cancelled, the parts already checked are still marked synced and the rest are
+    /// listed in `remaining`.
+    pub async fn sync_subscriptions(&self) -> Result<SyncReport> {
+        // Copy the part list so the lock isn't held across network calls
+        let parts = self.subscriptions().get_all_parts()?;
 
-            println!("🔄 Syncing {} locally tracked parts with API...", parts.len());
-            
-            let token = self.token.as_ref().ok_or_else(|| {
-                anyhow::anyhow!("Not authenticated. Please login first with 'mmc login'")
-            })?;
-
-            let mut verified = 0;
-            let mut not_found = Vec::new();
-
-            for part in parts {
-                let url = format!("https://api.mcmaster.com/v1/products/{}", part);
-                let response = self.client.get(&url)
-                    .header("Authorization", format!("Bearer {}", token))
-                    .send()
-                    .await?;
-
-                if response.status().is_success() {
-                    verified += 1;
-                    if !self.quiet_mode {
-                        print!("✅ {}", part);
-                        // Clear line and move cursor back
-                        print!("\r");
-                    }
-                } else if response.status().as_u16() == 404 {
-                    not_found.push(part);
+        let mut report = SyncReport::default();
+        if parts.is_empty() {
+            return Ok(report);
+        }
+
+        let token = self.require_token().await?;
+
+        for (index, part) in parts.iter().cloned().enumerate() {
+            let url = self.api_url(&format!("/v1/products/{}", part));
+            let Some(response) = self.cancellation.run(self.get_shared(&url, &token)).await else {
+                report.remaining = parts[index..].to_vec();
+                break;
+            };
+            let response = response?;
+
+            if response.status().is_success() {
+                let product: ProductDetail = response.json().await?;
+                self.cache_product(&product);
+                if !product.is_active() {
+                    report.discontinued.push(DiscontinuedPart {
+                        part_number: part.clone(),
+                        status: product.product_status.clone(),
+                        replacement: product.replacement_part().map(str::to_string),
+                    });
                 }
+                report.verified.push(part);
+            } else if response.status().as_u16() == 404 {
+                report.not_found.push(part);
             }
+        }
 
-            println!("✅ Verified {} parts are subscribed", verified);
-            
-            if !not_found.is_empty() {
-                println!("❌ {} parts not found in subscription:", not_found.len());
-                for part in not_found {
-                    println!("  • {}", part);
+        self.subscriptions().mark_synced(&report.verified)?;
+        report.discontinued_report = self.write_discontinued_report(&report.discontinued)?;
+
+        Ok(report)
+    }
+
+    /// Fetch and cache product details and prices for every tracked part
+    ///
+    /// With `max_age`, parts cached more recently than that are left alone. If cancelled,
+    /// what was already fetched stays cached and the rest is listed in `remaining`.
+    pub async fn refresh_cache(&self, max_age: Option<chrono::Duration>) -> Result<RefreshReport> {
+        let parts = self.subscriptions().get_all_parts()?;
+        let mut report = RefreshReport::default();
+
+        for (index, part) in parts.iter().cloned().enumerate() {
+            if self.cancellation.is_cancelled() {
+                report.remaining = parts[index..].to_vec();
+                break;
+            }
+            if let Some(max_age) = max_age {
+                if !self.cache().is_stale(&part, max_age)? {
+                    self.metrics.record_cache(true);
+                    report.fresh.push(part);
+                    continue;
                 }
             }
+
+            // get_product and get_price store what they fetch in the cache
+            let fetched = self.cancellation.run(async {
+                self.get_product(&part).await?;
+                self.get_price(&part).await.map(|_| ())
+            });
+            let Some(fetched) = fetched.await else {
+                report.remaining = parts[index..].to_vec();
+                break;
+            };
+            match fetched {
+                Ok(()) => report.refreshed.push(part),
+                Err(e @ (ClientError::NotAuthenticated | ClientError::AuthExpired)) => return Err(e),
+                Err(e) => report.failed.push((part, e.to_string())),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Write discontinued parts to the report file, removing a stale report when there are none
+    fn write_discontinued_report(&self, discontinued: &[DiscontinuedPart]) -> Result<Option<PathBuf>> {
+        let path = self.subscriptions().discontinued_report_path();
+
+        if discontinued.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            return Ok(None);
+        }
+
+        let mut content = String::from("# Discontinued parts found by 'mmc sync'\n# part\tstatus\treplacement\n");
+        for part in discontinued {
+            content.push_str(&format!(
+                "{}\t{}\t{}\n",
+                part.part_number,
+                part.status,
+                part.replacement.as_deref().unwrap_or("")
+            ));
+        }
+        fs::write(&path, content)?;
+
+        Ok(Some(path))
+    }
+}
+
+/// A response read into memory so it can be handed to several callers
+#[derive(Debug)]
+pub(crate) struct BufferedResponse {
+    status: reqwest::StatusCode,
+    headers: reqwest::header::HeaderMap,
+    body: Vec<u8>,
+}
+
+impl BufferedResponse {
+    async fn read(response: Response) -> Result<Self> {
+        Ok(BufferedResponse {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: response.bytes().await?.to_vec(),
+        })
+    }
+
+    fn to_response(&self) -> Result<Response> {
+        build_response(self.status.as_u16(), &self.headers, self.body.clone())
+    }
+}
+
+/// Why a shared GET left nothing to share
+enum Uncached {
+    Failed(BufferedResponse), // The API answered with an error status
+    Error(ClientError),
+}
+
+/// Result of verifying locally tracked parts against the API
+#[derive(Debug, Default, Serialize)]
+pub struct SyncReport {
+    pub verified: Vec<String>,
+    pub not_found: Vec<String>,
+    pub discontinued: Vec<DiscontinuedPart>, // Subscribed parts whose status is no longer Active
+    pub discontinued_report: Option<PathBuf>, // Where the discontinued parts were written, if any
+    pub remaining: Vec<String>, // Not checked before the sync was cancelled
+}
+
+/// Error for data that offline mode can't serve
+fn not_cached(what: &str, product: &str) -> ClientError {
+    ClientError::Offline(format!(
+        "no cached {} for {}; run 'mmc refresh' while online to cache tracked parts",
+        what,
+        product.trim().to_uppercase()
+    ))
+}
+
+/// HTTP client builder with the proxy and extra trusted roots from `[network]`
+///
+/// Without a configured proxy, reqwest picks up `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY`
+/// from the environment; a configured one replaces them.
+fn network_builder(network: &NetworkConfig) -> Result<reqwest::ClientBuilder> {
+    let mut builder = Client::builder();
+    if let Some(timeout) = network.connect_timeout() {
+        builder = builder.connect_timeout(timeout);
+    }
+    if let Some(timeout) = network.timeout() {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(proxy) = &network.proxy {
+        let proxy = reqwest::Proxy::all(proxy.as_str())
+            .map_err(|e| ClientError::Config(format!("Invalid proxy '{}': {}", proxy, e)))?;
+        builder = builder.proxy(proxy);
+    }
+    if let Some(path) = network.ca_bundle() {
+        let pem = fs::read(&path)
+            .map_err(|e| ClientError::Certificate(format!("Could not read CA bundle '{}': {}", path.display(), e)))?;
+        let roots = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| ClientError::Certificate(format!("Invalid CA bundle '{}': {}", path.display(), e)))?;
+        if roots.is_empty() {
+            return Err(ClientError::Certificate(format!("CA bundle '{}' has no PEM certificates", path.display())));
+        }
+        for root in roots {
+            builder = builder.add_root_certificate(root);
+        }
+    }
+    Ok(builder)
+}
+
+/// Result of refreshing the product cache
+#[derive(Debug, Default, Serialize)]
+pub struct RefreshReport {
+    pub refreshed: Vec<String>,
+    pub fresh: Vec<String>, // Cached recently enough to skip
+    pub failed: Vec<(String, String)>, // (part, error)
+    pub remaining: Vec<String>, // Not reached before the refresh was cancelled
+}
+
+/// Pricing for one part in a bulk price lookup
+#[derive(Debug, Serialize)]
+pub struct PartPrices {
+    pub part_number: String,
+    pub prices: Vec<PriceInfo>,
+    pub error: Option<String>,
+}
+
+/// A tracked part that McMaster-Carr no longer lists as active
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscontinuedPart {
+    pub part_number: String,
+    pub status: String,
+    pub replacement: Option<String>,
+}
+
+/// Default sustained request rate, kept under McMaster-Carr's documented limits
+pub const DEFAULT_REQUESTS_PER_SECOND: f64 = 5.0;
+
+/// Default number of requests that may be sent at once before the rate applies
+pub const DEFAULT_BURST: u32 = 10;
+
+/// Default seconds to wait for a connection, including the TLS handshake
+pub const DEFAULT_CONNECT_TIMEOUT: u64 = 30;
+
+/// Default seconds a request may take from sending to the end of the response body
+pub const DEFAULT_REQUEST_TIMEOUT: u64 = 300;
+
+/// Token bucket shared by every API request from one client
+///
+/// The bucket holds up to `burst` tokens and refills at `requests_per_second`.
+/// Each request takes a token, waiting for the refill when the bucket is empty.
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests_per_second: f64, // 0 disables limiting
+    burst: f64,
+    state: std::sync::Mutex<(f64, Instant)>, // (tokens available, last refill)
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        RateLimiter {
+            requests_per_second: requests_per_second.max(0.0),
+            burst,
+            state: std::sync::Mutex::new((burst, Instant::now())),
+        }
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take a token at `now`, returning how long to wait before it's available
+    fn reserve(&self, now: Instant) -> Duration {
+        if self.requests_per_second == 0.0 {
+            return Duration::ZERO;
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let (tokens, last) = *state;
+        let elapsed = now.saturating_duration_since(last).as_secs_f64();
+        // Tokens go negative when requests queue up, so waiters are served in order
+        let tokens = (tokens + elapsed * self.requests_per_second).min(self.burst) - 1.0;
+        *state = (tokens, now.max(last));
+
+        if tokens >= 0.0 {
+            Duration::ZERO
         } else {
-            return Err(anyhow::anyhow!("Failed to access subscription manager"));
+            Duration::from_secs_f64(-tokens / self.requests_per_second)
         }
-        
-        Ok(())
     }
-    
-}
\ No newline at end of file
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter::new(DEFAULT_REQUESTS_PER_SECOND, DEFAULT_BURST)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_bucket() {
+        let limiter = RateLimiter::new(10.0, 2);
+        let start = Instant::now();
+
+        // The burst goes out immediately, then requests are spaced at the rate
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::from_millis(100));
+        assert_eq!(limiter.reserve(start), Duration::from_millis(200));
+
+        // After a quiet second the bucket is full again, but never above the burst
+        let later = start + Duration::from_secs(1);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert!(!limiter.reserve(later).is_zero());
+
+        let unlimited = RateLimiter::new(0.0, 1);
+        for _ in 0..100 {
+            assert_eq!(unlimited.reserve(start), Duration::ZERO);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_product_fetched_once_per_session() {
+        use crate::test_support::{MockApi, PART_NUMBER};
+
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+
+        let (first, second) = futures::join!(client.get_product(PART_NUMBER), client.get_product(PART_NUMBER));
+        assert_eq!(first.unwrap().part_number, second.unwrap().part_number);
+        let links = client.get_product_links(PART_NUMBER, crate::test_support::TOKEN).await.unwrap();
+        assert_eq!(links.cad.len(), 1);
+
+        let product_path = format!("/v1/products/{}", PART_NUMBER);
+        let requests = api.server().received_requests().await.unwrap();
+        assert_eq!(requests.iter().filter(|request| request.url.path() == product_path).count(), 1);
+
+        // Errors aren't shared, so a failed lookup is retried
+        assert!(client.get_product("92141A008").await.is_err());
+        assert!(client.get_product("92141A008").await.is_err());
+        let requests = api.server().received_requests().await.unwrap();
+        assert_eq!(requests.iter().filter(|request| request.url.path().ends_with("92141A008")).count(), 2);
+    }
+}
@@ -1,131 +1,267 @@
 //! Authentication functionality for McMaster-Carr API
 
-use anyhow::Result;
-use std::path::PathBuf;
+use crate::utils::error::{ClientError, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use reqwest::header::AUTHORIZATION;
+use reqwest::Request;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::PoisonError;
 use tokio::fs as async_fs;
 
-use crate::config::paths::{get_config_dir, get_token_path, find_certificate_path, expand_path};
-use crate::models::auth::{LoginRequest, LoginResponse, ErrorResponse};
+use crate::client::certificate::CertificateInfo;
+use crate::config::paths::{find_certificate_path, expand_path};
+use crate::models::auth::{parse_expiration, Credentials, LoginRequest, LoginResponse};
+use crate::models::warning::{Warning, WarningKind};
+use crate::utils::display::{decorate, Icon};
+use crate::utils::lock::{write_atomic, FileLock};
+
+/// Outcome of a logout request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogoutStatus {
+    /// The API invalidated the token
+    LoggedOut,
+    /// The API rejected the logout request; the local token was cleared anyway
+    RequestFailed,
+    /// There was no token to logout with
+    NoSession,
+}
+
+/// How long before its expiry a token is renewed, when the API said when it expires
+pub const TOKEN_RENEWAL_MARGIN: TimeDelta = TimeDelta::minutes(10);
+
+/// A login token and its expiry time, when known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Session {
+    pub token: String,
+    pub expires: Option<DateTime<Utc>>,
+}
+
+impl Session {
+    pub fn new(token: String, expires: Option<DateTime<Utc>>) -> Self {
+        Session { token, expires }
+    }
+
+    /// Whether the token expires within `margin` from now; unknown expiry never does
+    pub fn expires_within(&self, margin: TimeDelta) -> bool {
+        self.expires.is_some_and(|expires| expires - Utc::now() <= margin)
+    }
+}
+
+/// The token a request was sent with
+pub(crate) fn bearer_token(request: &Request) -> Option<String> {
+    let header = request.headers().get(AUTHORIZATION)?.to_str().ok()?;
+    header.strip_prefix("Bearer ").map(str::to_string)
+}
+
+/// Everything `mmc auth status` reports about the login state
+#[derive(Debug, Serialize)]
+pub struct AuthStatus {
+    pub token_file: Option<PathBuf>, // Saved token, if there is one
+    pub expiration: Option<String>, // ExpirationTS as the API sent it at login
+    pub expires: Option<DateTime<Utc>>,
+    pub certificate: Option<CertificateInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate_error: Option<String>, // Why the credentials' certificate couldn't be loaded
+    pub credentials_file: Option<PathBuf>,
+    pub username: Option<String>,
+    pub auto_login: bool,
+}
+
+impl AuthStatus {
+    /// Whether the saved token's expiry time has passed; unknown expiry counts as valid
+    pub fn token_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// Whether API commands can run without logging in first
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        self.certificate_error.is_none()
+            && ((self.token_file.is_some() && !self.token_expired(now)) || (self.auto_login && self.username.is_some()))
+    }
+}
 
 /// Authentication-related methods for McmasterClient
 impl super::api::McmasterClient {
     /// Authenticate with username and password
     pub async fn login(&mut self, username: String, password: String) -> Result<()> {
+        let session = self.request_token(username, password).await?;
+        self.set_session(Some(session));
+        Ok(())
+    }
+
+    /// The current token, if logged in
+    pub(crate) fn current_session(&self) -> Option<Session> {
+        self.session.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    fn set_session(&self, session: Option<Session>) {
+        *self.session.lock().unwrap_or_else(PoisonError::into_inner) = session;
+    }
+
+    /// Whether a missing, expiring or rejected token can be replaced by logging in again
+    pub(crate) fn can_renew_token(&self) -> bool {
+        self.auto_login && self.credentials.is_some() && !self.is_replaying()
+    }
+
+    /// Log in with the stored credentials to replace `stale`, or to get a first token with `None`
+    ///
+    /// Concurrent callers wait for a single login; those that find the token already
+    /// replaced by the time it's their turn just use the new one.
+    pub(crate) async fn renew_token(&self, stale: Option<&str>) -> Result<String> {
+        let _renewing = self.renewing.lock().await;
+        if let Some(session) = self.current_session().filter(|session| Some(session.token.as_str()) != stale) {
+            return Ok(session.token);
+        }
+        let credentials = self.credentials.as_ref().ok_or(ClientError::NotAuthenticated)?;
+        let session = self.request_token(credentials.username.clone(), credentials.password.clone()).await?;
+        // Reported with the warnings, on stderr, so machine-readable output stays clean
+        if !self.quiet_mode {
+            let message = if stale.is_some() { "Renewed the authentication token" } else { "Logged in with stored credentials" };
+            self.warn(Warning::general(WarningKind::TokenRenewed, message));
+        }
+        let token = session.token.clone();
+        self.set_session(Some(session));
+        Ok(token)
+    }
+
+    /// Log in and save the new token, without replacing the client's current one
+    async fn request_token(&self, username: String, password: String) -> Result<Session> {
+        self.require_online()?;
         let login_request = LoginRequest {
             user_name: username,
             password,
         };
 
-        let response = self.client.post("https://api.mcmaster.com/v1/login")
+        let response = self.send_once(self.client.post(self.api_url("/v1/login"))
             .json(&login_request)
-            .send()
+            .build()?)
             .await?;
 
         if response.status().is_success() {
             let login_response: LoginResponse = response.json().await?;
-            self.token = Some(login_response.token.clone());
 
-            // Save token to file for future use
-            if let Err(e) = self.save_token().await {
-                if !self.quiet_mode {
-                    eprintln!("⚠️  Warning: Could not save token: {}", e);
+            // Save token to file for future use (a replayed token is only a placeholder)
+            if !self.is_replaying() {
+                if let Err(e) = self.save_token(&login_response.token, login_response.expiration.as_deref()).await {
+                    self.warn(Warning::general(WarningKind::TokenNotSaved, format!("Could not save token: {}", e)));
                 }
             }
-
-            // Always show login success (critical information)
-            println!("✅ Login successful");
+            let expires = login_response.expiration.as_deref().and_then(parse_expiration);
+            Ok(Session::new(login_response.token, expires))
         } else {
-            // Try to parse as error response
-            let error_text = response.text().await?;
-            if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&error_text) {
-                return Err(anyhow::anyhow!(
-                    "Login failed: {}",
-                    error_response.error_message.unwrap_or("Unknown error".to_string())
-                ));
-            } else {
-                return Err(anyhow::anyhow!("Login failed: {}", error_text));
+            // A rejected login is an authentication failure, not an expired token
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            if status == 429 {
+                return Err(ClientError::RateLimited { retry_after: None });
             }
+            Err(ClientError::Auth(format!("Login failed: {}", ClientError::response_message(&body))))
         }
-
-        Ok(())
     }
 
     /// Logout and invalidate current token
-    pub async fn logout(&mut self) -> Result<()> {
-        if let Some(token) = &self.token {
-            let response = self.client.delete("https://api.mcmaster.com/v1/logout")
-                .header("Authorization", format!("Bearer {}", token))
-                .send()
-                .await?;
-
-            if response.status().is_success() {
-                self.token = None;
-                // Remove token file
-                let token_path = get_token_path();
-                if token_path.exists() {
-                    let _ = async_fs::remove_file(token_path).await;
-                }
-                // Always show logout success (critical information)
-                println!("✅ Logout successful");
-            } else {
-                // Always show logout warnings (critical information)
-                eprintln!("⚠️  Warning: Logout request failed, but clearing local token");
-                self.token = None;
-                let token_path = get_token_path();
-                if token_path.exists() {
-                    let _ = async_fs::remove_file(token_path).await;
-                }
-            }
-        } else {
-            // Always show logout info (critical information)
-            println!("ℹ️  No active session to logout from");
+    pub async fn logout(&mut self) -> Result<LogoutStatus> {
+        self.require_online()?;
+        let Some(session) = self.current_session() else {
+            return Ok(LogoutStatus::NoSession);
+        };
+
+        // A rejected token is the one being thrown away, so it isn't renewed first
+        let response = self.send_once(self.client.delete(self.api_url("/v1/logout"))
+            .header("Authorization", format!("Bearer {}", session.token))
+            .build()?)
+            .await?;
+
+        // Clear the local token either way
+        self.set_session(None);
+        let token_path = &self.token_path;
+        if token_path.exists() && !self.is_replaying() {
+            let _lock = FileLock::exclusive_async(token_path).await?;
+            let _ = async_fs::remove_file(token_path).await;
+            let _ = async_fs::remove_file(self.token_expiry_path()).await;
         }
 
-        Ok(())
+        if response.status().is_success() {
+            Ok(LogoutStatus::LoggedOut)
+        } else {
+            Ok(LogoutStatus::RequestFailed)
+        }
     }
 
     /// Load stored token from file
     pub async fn load_token(&mut self) -> Result<()> {
-        let token_path = get_token_path();
+        let token_path = self.token_path.clone();
         
         if token_path.exists() {
-            let token = async_fs::read_to_string(token_path).await?;
-            self.token = Some(token.trim().to_string());
+            let (token, expiration) = {
+                let _lock = FileLock::shared_async(&token_path).await?;
+                (async_fs::read_to_string(&token_path).await?, async_fs::read_to_string(self.token_expiry_path()).await.ok())
+            };
+            self.set_session(Some(Session::new(token.trim().to_string(), expiration.as_deref().and_then(parse_expiration))));
             if !self.quiet_mode {
-                println!("🔑 Loaded existing authentication token");
+                println!("{}", decorate(Icon::Key, "Loaded existing authentication token"));
             }
         } else {
             if !self.quiet_mode {
-                println!("ℹ️  No existing token found");
+                println!("{}", decorate(Icon::Info, "No existing token found"));
             }
         }
 
         Ok(())
     }
 
-    /// Save current token to file
-    async fn save_token(&self) -> Result<()> {
-        if let Some(token) = &self.token {
-            let config_dir = get_config_dir();
-            
-            // Create config directory if it doesn't exist
-            if !config_dir.exists() {
-                async_fs::create_dir_all(&config_dir).await?;
-            }
+    /// Save a token to file, with its expiry time next to it when the API gave one
+    async fn save_token(&self, token: &str, expiration: Option<&str>) -> Result<()> {
+        // Create config directory if it doesn't exist
+        if let Some(config_dir) = self.token_path.parent().filter(|dir| !dir.exists()) {
+            async_fs::create_dir_all(config_dir).await?;
+        }
 
-            let token_path = get_token_path();
-            async_fs::write(token_path, token).await?;
+        // Another mmc process may be reading or saving the token at the same time
+        let _lock = FileLock::exclusive_async(&self.token_path).await?;
+        write_atomic(&self.token_path, token.as_bytes())?;
+        match expiration {
+            Some(expiration) => write_atomic(&self.token_expiry_path(), expiration.as_bytes())?,
+            None => {
+                let _ = async_fs::remove_file(self.token_expiry_path()).await;
+            }
         }
-        
         Ok(())
     }
 
+    /// File holding the saved token's ExpirationTS
+    fn token_expiry_path(&self) -> PathBuf {
+        self.token_path.with_extension("expires")
+    }
+
+    /// Report the saved token, its expiry, the loaded certificate and the credentials in use
+    ///
+    /// The credentials are passed in so a client built without them, because their
+    /// certificate wouldn't load, can still report them.
+    pub fn auth_status(&self, credentials: Option<&Credentials>, credentials_file: Option<&Path>) -> AuthStatus {
+        let token_file = self.token_path.is_file().then(|| self.token_path.clone());
+        let expiration = token_file.as_ref()
+            .and_then(|_| std::fs::read_to_string(self.token_expiry_path()).ok())
+            .map(|expiration| expiration.trim().to_string());
+        AuthStatus {
+            token_file,
+            expires: expiration.as_deref().and_then(parse_expiration),
+            expiration,
+            certificate: self.certificate.clone(),
+            certificate_error: None,
+            credentials_file: credentials.and(credentials_file).map(Path::to_path_buf),
+            username: credentials.map(|credentials| credentials.username.clone()),
+            auto_login: self.auto_login,
+        }
+    }
+
     /// Login with stored credentials if available
     pub async fn login_with_stored_credentials(&mut self) -> Result<()> {
         if let Some(ref credentials) = self.credentials.clone() {
             self.login(credentials.username.clone(), credentials.password.clone()).await
         } else {
-            Err(anyhow::anyhow!("No credentials available"))
+            Err(ClientError::Auth("No credentials available".to_string()))
         }
     }
 
@@ -152,8 +288,8 @@ impl super::api::McmasterClient {
         self.quiet_mode = quiet;
     }
 
-    /// Save credentials template to file
-    pub async fn save_credentials_template(&self, path: &str) -> Result<()> {
+    /// Save credentials template to file, returning the path written
+    pub async fn save_credentials_template(&self, path: &str) -> Result<PathBuf> {
         let credentials_path = expand_path(path);
         
         // Create parent directories if they don't exist
@@ -180,16 +316,77 @@ password = "your_password"
 # Default locations checked:
 #   ~/.config/mmc/certificate.pfx
 #   ~/.config/mmc/certificate.p12  
+#   ~/.config/mmc/certificate.pem (certificate and key, or key in certificate.key)
 #   ~/.mmcli/certificate.pfx (legacy)
 #   ~/.mmcli/certificate.p12 (legacy)
 certificate_path = "~/.config/mmc/certificate.pfx"
 certificate_password = "certificate_password"
+# For a PEM certificate whose private key is in a separate file:
+# certificate_key_path = "~/.config/mmc/certificate.key"
 "#.to_string()
         };
 
         async_fs::write(&credentials_path, template).await?;
-        println!("✅ Credentials template saved to: {}", credentials_path.display());
-        
-        Ok(())
+
+        Ok(credentials_path)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockApi, PART_NUMBER, PASSWORD, TOKEN, USERNAME};
+    use wiremock::matchers::header_exists;
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_auto_login_and_renewal() {
+        let api = MockApi::start().await;
+        let mut client = api.client().unwrap();
+        client.credentials = Some(Credentials {
+            username: USERNAME.to_string(),
+            password: PASSWORD.to_string(),
+            certificate_path: None,
+            certificate_password: None,
+            certificate_key_path: None,
+            subscriptions_file: None,
+        });
+        assert!(matches!(client.get_product(PART_NUMBER).await, Err(ClientError::NotAuthenticated)));
+
+        // Concurrent requests share a single login
+        client.set_auto_login(true);
+        let (first, second) = tokio::join!(client.get_product(PART_NUMBER), client.get_price(PART_NUMBER));
+        assert!(first.is_ok() && second.is_ok());
+        let logins = || async { api.server().received_requests().await.unwrap().iter().filter(|request| request.url.path() == "/v1/login").count() };
+        assert_eq!(logins().await, 1);
+        assert_eq!(std::fs::read_to_string(api.data_dir().join("token")).unwrap(), TOKEN);
+
+        let status = client.auth_status(client.credentials.as_ref(), None);
+        assert_eq!(status.token_file, Some(api.data_dir().join("token")));
+        assert_eq!(status.expires.map(|expires| expires.to_rfc3339()).as_deref(), Some("2099-01-01T00:00:00+00:00"));
+        assert!(status.is_usable(chrono::Utc::now()));
+
+        // A rejected token is renewed and the request sent again
+        Mock::given(header_exists("Authorization"))
+            .respond_with(ResponseTemplate::new(401))
+            .with_priority(20)
+            .mount(api.server())
+            .await;
+        client.quiet_mode = false;
+        client.set_session(Some(Session::new("expired-token".to_string(), None)));
+        assert!(client.add_product(PART_NUMBER).await.is_ok());
+        assert_eq!(logins().await, 2);
+        let renewed = Warning::general(WarningKind::TokenRenewed, "Renewed the authentication token");
+        assert_eq!(client.take_warnings(), vec![renewed]);
+
+        // A token about to expire is renewed before it's used
+        client.set_session(Some(Session::new(TOKEN.to_string(), Some(Utc::now() + TimeDelta::minutes(1)))));
+        assert_eq!(client.require_token().await.unwrap(), TOKEN);
+        assert_eq!(logins().await, 3);
+        assert!(!client.current_session().unwrap().expires_within(TOKEN_RENEWAL_MARGIN));
+
+        // Without auto-login the rejection stands
+        client.set_auto_login(false);
+        client.set_session(Some(Session::new("expired-token".to_string(), None)));
+        assert!(matches!(client.remove_product(PART_NUMBER).await, Err(ClientError::AuthExpired)));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,304 @@
+//! Bill of materials commands: enrichment, checks and purchase lists
+
+use crate::models::bom::Bom;
+use crate::models::product::{PriceInfo, PriceQuote, ProductDetail};
+use crate::utils::error::{ClientError, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+/// Columns `mmc bom enrich` appends to every row
+pub const ENRICHED_COLUMNS: &[&str] = &["Item Number", "Description", "Category", "Unit Price", "Currency", "Pack Size", "Datasheet URL"];
+
+/// A BOM with catalog columns appended, and the parts that couldn't be looked up
+#[derive(Debug)]
+pub struct BomEnrichment {
+    pub bom: Bom,
+    pub enriched: usize, // Distinct parts found
+    pub failed: Vec<(String, String)>, // (part, error); their rows get blank columns
+}
+
+/// Result of `mmc bom check` for one BOM row
+#[derive(Debug, Clone, Serialize)]
+pub struct BomCheckLine {
+    pub line: u64, // Line in the BOM file
+    pub part_number: Option<String>,
+    pub quantity: Option<f64>,
+    pub problems: Vec<String>, // Empty when the row passes
+}
+
+/// Result of `mmc bom check`
+#[derive(Debug, Clone, Serialize)]
+pub struct BomCheckReport {
+    pub lines: Vec<BomCheckLine>,
+    pub quantities_checked: bool, // False when the BOM has no quantity column
+}
+
+impl BomCheckReport {
+    /// Whether every row passed
+    pub fn passed(&self) -> bool {
+        self.lines.iter().all(|line| line.problems.is_empty())
+    }
+
+    /// Rows with at least one problem
+    pub fn failures(&self) -> impl Iterator<Item = &BomCheckLine> {
+        self.lines.iter().filter(|line| !line.problems.is_empty())
+    }
+}
+
+/// Parts to buy for one or more BOMs, rounded to what can be ordered
+#[derive(Debug, Clone, Serialize)]
+pub struct PurchaseOrder {
+    pub lines: Vec<PriceQuote>, // One per distinct part, in BOM order
+    pub totals: BTreeMap<String, f64>, // Order total by currency
+    pub failed: Vec<(String, String)>, // (part or line, error); left out of the order
+}
+
+impl PurchaseOrder {
+    /// Write `Part Number,Quantity` rows, quantities in units of measure, for the bulk order page
+    pub fn write_order_csv<W: Write>(&self, writer: W) -> csv::Result<()> {
+        let mut csv = csv::Writer::from_writer(writer);
+        csv.write_record(["Part Number", "Quantity"])?;
+        for line in &self.lines {
+            csv.write_record([line.part_number.clone(), line.units.to_string()])?;
+        }
+        csv.flush()?;
+        Ok(())
+    }
+}
+
+/// BOM methods for McmasterClient
+impl super::api::McmasterClient {
+    /// Append description, category, price, pack size and datasheet columns to each row
+    ///
+    /// Each distinct part is looked up once. Rows without a part number, or whose part
+    /// can't be found, keep their original cells and get blank catalog columns.
+    pub async fn enrich_bom(&self, bom: &Bom) -> Result<BomEnrichment> {
+        let mut columns: HashMap<String, Vec<String>> = HashMap::new();
+        let mut failed = Vec::new();
+        for part in bom.part_numbers() {
+            match self.enrichment_columns(&part).await {
+                Ok(values) => {
+                    columns.insert(part, values);
+                }
+                Err(e @ (ClientError::NotAuthenticated | ClientError::AuthExpired)) => return Err(e),
+                Err(e) => failed.push((part, e.to_string())),
+            }
+        }
+
+        let mut enriched = bom.clone();
+        let width = enriched.rows.iter().map(Vec::len).max().unwrap_or(0).max(enriched.headers.len());
+        enriched.headers.resize(width, String::new());
+        enriched.headers.extend(ENRICHED_COLUMNS.iter().map(|column| column.to_string()));
+        for row in &mut enriched.rows {
+            let part = bom.part_number(row);
+            let values = part.as_ref().and_then(|part| columns.get(part)).cloned();
+            // Item numbers are local, so parts that couldn't be looked up still get theirs
+            let item_number = match &part {
+                Some(part) => self.item_number(part)?,
+                None => None,
+            };
+            row.resize(width, String::new());
+            row.push(item_number.unwrap_or_default());
+            row.extend(values.unwrap_or_else(|| vec![String::new(); ENRICHED_COLUMNS.len() - 1]));
+        }
+
+        Ok(BomEnrichment { bom: enriched, enriched: columns.len(), failed })
+    }
+
+    /// Check that every row's part exists, is active and subscribed, and meets the minimum order
+    ///
+    /// Each distinct part is looked up once; the minimum order check needs a quantity column.
+    pub async fn check_bom(&self, bom: &Bom) -> Result<BomCheckReport> {
+        let mut lookups: HashMap<String, std::result::Result<(ProductDetail, Vec<PriceInfo>), String>> = HashMap::new();
+        for part in bom.part_numbers() {
+            let lookup = match self.get_product(&part).await {
+                Ok(detail) => self.get_price(&part).await.map(|prices| (detail, prices)),
+                Err(e) => Err(e),
+            };
+            let lookup = match lookup {
+                Ok(found) => Ok(found),
+                Err(e @ (ClientError::NotAuthenticated | ClientError::AuthExpired)) => return Err(e),
+                Err(ClientError::NotFound(_)) => Err("Part not found in the catalog".to_string()),
+                Err(ClientError::NotSubscribed(_)) => Err("Part is not in the account's subscription; add it with mmc add".to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            lookups.insert(part, lookup);
+        }
+
+        let lines = bom.rows.iter().zip(&bom.line_numbers)
+            .map(|(row, &line)| {
+                let part_number = bom.part_number(row);
+                let quantity = bom.quantity(row);
+                let mut problems = Vec::new();
+                match quantity.clone() {
+                    Some(Err(cell)) if cell.is_empty() => problems.push("Missing quantity".to_string()),
+                    Some(Err(cell)) => problems.push(format!("Quantity '{}' is not a number", cell)),
+                    Some(Ok(quantity)) if quantity <= 0.0 => problems.push(format!("Quantity {} is not positive", quantity)),
+                    _ => {}
+                }
+                let quantity = quantity.and_then(|quantity| quantity.ok()).filter(|quantity| *quantity > 0.0);
+
+                match part_number.as_ref().and_then(|part| lookups.get(part)) {
+                    None => problems.push("Missing part number".to_string()),
+                    Some(Err(problem)) => problems.push(problem.clone()),
+                    Some(Ok((detail, prices))) => {
+                        if !detail.is_active() {
+                            problems.push(match detail.replacement_part() {
+                                Some(replacement) => format!("Part is {} (replacement: {})", detail.product_status.to_lowercase(), replacement),
+                                None => format!("Part is {}", detail.product_status.to_lowercase()),
+                            });
+                        }
+                        if let Some(quantity) = quantity {
+                            problems.extend(minimum_order_problem(detail, prices, quantity));
+                        }
+                    }
+                }
+                BomCheckLine { line, part_number, quantity, problems }
+            })
+            .collect();
+
+        Ok(BomCheckReport { lines, quantities_checked: bom.quantity_column.is_some() })
+    }
+
+    /// Add up each part's quantity across BOMs and price it in whole packs
+    ///
+    /// Rows without a part number (other vendors' items) are skipped. Rows whose quantity
+    /// isn't a positive number, and parts that can't be priced, are reported and left out.
+    pub async fn order_bom(&self, boms: &[Bom]) -> Result<PurchaseOrder> {
+        let mut pieces: Vec<(String, f64)> = Vec::new();
+        let mut failed = Vec::new();
+        for bom in boms {
+            if bom.quantity_column.is_none() {
+                return Err(ClientError::Parse("BOM has no quantity column (Qty or Quantity)".to_string()));
+            }
+            for (row, line) in bom.rows.iter().zip(&bom.line_numbers) {
+                let Some(part) = bom.part_number(row) else { continue };
+                match bom.quantity(row) {
+                    Some(Ok(quantity)) if quantity > 0.0 => match pieces.iter_mut().find(|(existing, _)| *existing == part) {
+                        Some((_, total)) => *total += quantity,
+                        None => pieces.push((part, quantity)),
+                    },
+                    Some(Ok(quantity)) => failed.push((part, format!("Line {}: quantity {} is not positive", line, quantity))),
+                    _ => failed.push((part, format!("Line {}: missing or invalid quantity", line))),
+                }
+            }
+        }
+
+        let mut lines = Vec::new();
+        let mut totals = BTreeMap::new();
+        for (part, quantity) in pieces {
+            match self.quote_price(&part, quantity, None).await {
+                Ok(quote) => {
+                    *totals.entry(quote.currency.clone()).or_insert(0.0) += quote.total;
+                    lines.push(quote);
+                }
+                Err(e @ (ClientError::NotAuthenticated | ClientError::AuthExpired)) => return Err(e),
+                Err(e) => failed.push((part, e.to_string())),
+            }
+        }
+
+        Ok(PurchaseOrder { lines, totals, failed })
+    }
+
+    /// Catalog values for one part, in ENRICHED_COLUMNS order after the item number
+    async fn enrichment_columns(&self, part: &str) -> Result<Vec<String>> {
+        let detail = self.get_product(part).await?;
+        let prices = self.get_price(part).await?;
+        let price = prices.iter().min_by(|a, b| a.minimum_quantity.total_cmp(&b.minimum_quantity));
+        let pack_size = if prices.iter().all(|price| price.unit_of_measure.eq_ignore_ascii_case("each")) {
+            Some(1)
+        } else {
+            detail.pack_size()
+        };
+
+        // Links aren't cached, so offline runs leave the datasheet column blank
+        let datasheet = match self.require_token().await {
+            Ok(token) => self.get_product_links(part, &token).await?.datasheets.into_iter().next(),
+            Err(_) => None,
+        };
+        let datasheet = datasheet.map(|url| if url.starts_with('/') { self.api_url(&url) } else { url });
+
+        Ok(vec![
+            detail.detail_description,
+            detail.product_category,
+            price.map(|price| format!("{:.2}", price.amount)).unwrap_or_default(),
+            price.map(|price| price.currency().to_string()).unwrap_or_default(),
+            pack_size.map(|size| size.to_string()).unwrap_or_default(),
+            datasheet.unwrap_or_default(),
+        ])
+    }
+}
+
+/// Describe why `quantity` pieces fall short of the smallest price break's minimum, if they do
+fn minimum_order_problem(detail: &ProductDetail, prices: &[PriceInfo], quantity: f64) -> Option<String> {
+    let minimum = prices.iter().min_by(|a, b| a.minimum_quantity.total_cmp(&b.minimum_quantity))?;
+    let sold_individually = minimum.unit_of_measure.eq_ignore_ascii_case("each");
+    // Pieces are rounded up to whole packs; without a pack size the quantity counts units of measure
+    let units = match detail.pack_size().filter(|_| !sold_individually) {
+        Some(size) => (quantity / f64::from(size.max(1))).ceil(),
+        None => quantity,
+    };
+    (units < minimum.minimum_quantity).then(|| format!(
+        "Quantity {} is below the minimum order of {} {}",
+        quantity, minimum.minimum_quantity, minimum.unit_of_measure.to_lowercase(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockApi, PART_NUMBER};
+
+    #[tokio::test]
+    async fn test_enrich_bom() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        client.set_item_number(PART_NUMBER, Some("HW-00412")).unwrap();
+        let csv = format!("Ref,Qty,McMaster PN\nS1,4,{}\nX1,1,\n", PART_NUMBER.to_lowercase());
+        let bom = Bom::from_reader(csv.as_bytes(), None).unwrap();
+
+        let enrichment = client.enrich_bom(&bom).await.unwrap();
+        assert_eq!(enrichment.enriched, 1);
+        assert!(enrichment.failed.is_empty());
+        assert_eq!(enrichment.bom.headers.len(), 3 + ENRICHED_COLUMNS.len());
+        assert_eq!(enrichment.bom.rows[0][3..], ["HW-00412", "M3 x 0.5 mm Thread, 10 mm Long", "Screws", "9.50", "USD", "", ""]);
+        assert!(enrichment.bom.rows[1][3..].iter().all(String::is_empty));
+    }
+
+    #[tokio::test]
+    async fn test_check_bom() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        let csv = format!("Part,Qty\n{0},4\n92141A008,1\n\n{0},\n", PART_NUMBER);
+        let bom = Bom::from_reader(csv.as_bytes(), None).unwrap();
+
+        let report = client.check_bom(&bom).await.unwrap();
+        assert!(!report.passed());
+        assert!(report.quantities_checked);
+        assert!(report.lines[0].problems.is_empty());
+        let failures: Vec<u64> = report.failures().map(|line| line.line).collect();
+        assert_eq!(failures, vec![3, 5]);
+        assert_eq!(report.lines[2].problems, vec!["Missing quantity"]);
+    }
+
+    #[tokio::test]
+    async fn test_order_bom() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        let frame = Bom::from_reader(format!("Part,Qty\n{0},4\n{0},6\nBOLT-X,2\n", PART_NUMBER).as_bytes(), None).unwrap();
+        let arm = Bom::from_reader(format!("Ref,Part,Qty\nS1,{},2\nS2,,9\n", PART_NUMBER).as_bytes(), None).unwrap();
+
+        let order = client.order_bom(&[frame, arm]).await.unwrap();
+        assert_eq!(order.lines.len(), 1);
+        // No pack size in the fixture, so 12 is counted in packs and gets the 10+ price break
+        assert_eq!(order.lines[0].quantity, 12.0);
+        assert_eq!(order.lines[0].total, 12.0 * 8.75);
+        assert_eq!(order.totals["USD"], 12.0 * 8.75);
+        assert_eq!(order.failed.len(), 1);
+
+        let mut csv = Vec::new();
+        order.write_order_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "Part Number,Quantity\n91290A115,12\n");
+    }
+}
@@ -0,0 +1,321 @@
+//! Local cache of product data for offline use, and the datasheet text index
+
+use crate::models::product::{PriceInfo, ProductDetail};
+use crate::utils::error::{ClientError, Result};
+use crate::utils::lock::FileLock;
+use chrono::{DateTime, Duration as Age, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Schema changes, applied in order; `PRAGMA user_version` records how many have run
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE products (
+        part_number TEXT PRIMARY KEY,
+        detail TEXT NOT NULL,
+        fetched_at TEXT NOT NULL
+    );",
+    "CREATE TABLE prices (
+        part_number TEXT PRIMARY KEY,
+        prices TEXT NOT NULL,
+        fetched_at TEXT NOT NULL
+    );",
+    "CREATE VIRTUAL TABLE datasheet_text USING fts5(
+        part_number UNINDEXED,
+        file UNINDEXED,
+        content
+    );",
+];
+
+/// How long cached data stays fresh, from the `[cache]` table in config.toml
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Hours before `mmc refresh` re-fetches a cached part (default: refresh every part)
+    pub ttl_hours: Option<u64>,
+}
+
+impl CacheConfig {
+    /// `ttl_hours` as an age, or None when unset or too large for a duration
+    pub fn ttl(&self) -> Option<Age> {
+        self.ttl_hours.and_then(|hours| i64::try_from(hours).ok()).and_then(Age::try_hours)
+    }
+}
+
+/// Product details as last fetched from the API
+#[derive(Debug)]
+pub struct CachedProduct {
+    pub detail: ProductDetail,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A datasheet whose text matched a search
+#[derive(Debug, Serialize)]
+pub struct DatasheetMatch {
+    pub part_number: String,
+    pub file: PathBuf,
+    pub snippet: String, // Matching text with the search terms in [brackets]
+}
+
+/// SQLite cache of API responses, keyed by part number
+pub struct ProductCache {
+    file_path: PathBuf,
+    conn: Connection,
+}
+
+impl ProductCache {
+    /// Open (creating if needed) the cache database
+    pub fn open(file_path: PathBuf) -> Result<Self> {
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Only one process creates or migrates the database at a time
+        let _lock = FileLock::exclusive(&file_path)?;
+
+        let conn = Connection::open(&file_path)?;
+        conn.busy_timeout(Duration::from_secs(5))?; // Wait for other mmc processes instead of failing
+        conn.pragma_update(None, "journal_mode", "WAL")?; // Readers don't block the writer
+
+        let mut cache = ProductCache { file_path, conn };
+        cache.migrate()?;
+        Ok(cache)
+    }
+
+    /// Bring the schema up to date
+    fn migrate(&mut self) -> Result<()> {
+        let version: usize = self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version >= MIGRATIONS.len() {
+            return Ok(());
+        }
+
+        let tx = self.conn.transaction()?;
+        for migration in &MIGRATIONS[version..] {
+            tx.execute_batch(migration)?;
+        }
+        tx.pragma_update(None, "user_version", MIGRATIONS.len())?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Store product details fetched just now
+    pub fn store_product(&self, detail: &ProductDetail) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO products (part_number, detail, fetched_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(part_number) DO UPDATE SET detail = excluded.detail, fetched_at = excluded.fetched_at",
+            params![normalize_part(&detail.part_number), serde_json::to_string(detail)?, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Cached product details, if the part has been fetched before
+    pub fn get_product(&self, part_number: &str) -> Result<Option<CachedProduct>> {
+        let row: Option<(String, String)> = self.conn
+            .query_row(
+                "SELECT detail, fetched_at FROM products WHERE part_number = ?1",
+                params![normalize_part(part_number)],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        row.map(|(detail, fetched_at)| {
+            Ok(CachedProduct {
+                detail: serde_json::from_str(&detail)?,
+                fetched_at: parse_time(&fetched_at)?,
+            })
+        })
+        .transpose()
+    }
+
+    /// Cached product details of every part in a category (case-insensitive)
+    pub fn products_in_category(&self, category: &str) -> Result<Vec<ProductDetail>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT detail FROM products
+             WHERE json_extract(detail, '$.ProductCategory') = ?1 COLLATE NOCASE
+             ORDER BY part_number",
+        )?;
+        let details = stmt
+            .query_map(params![category.trim()], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        details.iter()
+            .map(|detail| Ok(serde_json::from_str(detail)?))
+            .collect()
+    }
+
+    /// Every cached product, sorted by part number
+    pub fn all_products(&self) -> Result<Vec<CachedProduct>> {
+        let mut stmt = self.conn.prepare("SELECT detail, fetched_at FROM products ORDER BY part_number")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.iter()
+            .map(|(detail, fetched_at)| Ok(CachedProduct { detail: serde_json::from_str(detail)?, fetched_at: parse_time(fetched_at)? }))
+            .collect()
+    }
+
+    /// Store price breaks fetched just now
+    pub fn store_prices(&self, part_number: &str, prices: &[PriceInfo]) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO prices (part_number, prices, fetched_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(part_number) DO UPDATE SET prices = excluded.prices, fetched_at = excluded.fetched_at",
+            params![normalize_part(part_number), serde_json::to_string(prices)?, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Cached price breaks, if the part has been priced before
+    pub fn get_prices(&self, part_number: &str) -> Result<Option<Vec<PriceInfo>>> {
+        let prices: Option<String> = self.conn
+            .query_row(
+                "SELECT prices FROM prices WHERE part_number = ?1",
+                params![normalize_part(part_number)],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(prices.map(|prices| serde_json::from_str(&prices)).transpose()?)
+    }
+
+    /// Whether a part is missing from the cache or was fetched longer ago than `max_age`
+    pub fn is_stale(&self, part_number: &str, max_age: Age) -> Result<bool> {
+        let fetched_at: Option<String> = self.conn
+            .query_row(
+                "SELECT fetched_at FROM products WHERE part_number = ?1",
+                params![normalize_part(part_number)],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(match fetched_at {
+            Some(fetched_at) => Utc::now() - parse_time(&fetched_at)? > max_age,
+            None => true,
+        })
+    }
+
+    /// Replace the indexed text of a datasheet file
+    pub fn store_datasheet_text(&mut self, part_number: &str, file: &Path, text: &str) -> Result<()> {
+        let file = file.to_string_lossy();
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM datasheet_text WHERE file = ?1", params![file])?;
+        tx.execute(
+            "INSERT INTO datasheet_text (part_number, file, content) VALUES (?1, ?2, ?3)",
+            params![normalize_part(part_number), file, text],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Indexed datasheets containing every word of `query`, best matches first
+    pub fn search_datasheets(&self, query: &str, limit: usize) -> Result<Vec<DatasheetMatch>> {
+        let Some(query) = match_expression(query) else {
+            return Ok(Vec::new());
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT part_number, file, snippet(datasheet_text, 2, '[', ']', '...', 12)
+             FROM datasheet_text WHERE datasheet_text MATCH ?1 ORDER BY rank LIMIT ?2",
+        )?;
+        let matches = stmt
+            .query_map(params![query, limit], |row| {
+                Ok(DatasheetMatch {
+                    part_number: row.get(0)?,
+                    file: PathBuf::from(row.get::<_, String>(1)?),
+                    snippet: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(matches)
+    }
+
+    /// Number of indexed datasheet files
+    pub fn datasheet_count(&self) -> Result<usize> {
+        Ok(self.conn.query_row("SELECT COUNT(*) FROM datasheet_text", [], |row| row.get(0))?)
+    }
+
+    /// Number of cached products
+    pub fn count(&self) -> Result<usize> {
+        Ok(self.conn.query_row("SELECT COUNT(*) FROM products", [], |row| row.get(0))?)
+    }
+
+    /// Get the cache database path
+    pub fn get_file_path(&self) -> &Path {
+        &self.file_path
+    }
+}
+
+fn normalize_part(part_number: &str) -> String {
+    part_number.trim().to_uppercase()
+}
+
+/// FTS5 query matching every word, with each word quoted so punctuation is taken literally
+fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query.split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| ClientError::Parse(format!("Invalid cache timestamp '{}': {}", value, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_store_and_read_products() {
+        let temp_dir = tempdir().unwrap();
+        let cache = ProductCache::open(temp_dir.path().join("cache.db")).unwrap();
+
+        let detail: ProductDetail = serde_json::from_str(
+            r#"{"PartNumber": "91290A115", "DetailDescription": "M3 x 0.5 mm Thread, 10 mm Long",
+                "FamilyDescription": "Socket Head Screws", "ProductCategory": "Screws", "ProductStatus": "Active",
+                "Specifications": [{"Attribute": "Thread Size", "Values": ["M3"]}]}"#,
+        ).unwrap();
+
+        assert!(cache.get_product("91290A115").unwrap().is_none());
+        assert!(cache.is_stale("91290A115", Age::days(1)).unwrap());
+
+        cache.store_product(&detail).unwrap();
+        let cached = cache.get_product("91290a115").unwrap().unwrap();
+        assert_eq!(cached.detail.specifications[0].values, vec!["M3"]);
+        assert!(!cache.is_stale("91290A115", Age::days(1)).unwrap());
+        assert!(cache.is_stale("91290A115", Age::zero() - Age::seconds(1)).unwrap());
+        assert_eq!(cache.count().unwrap(), 1);
+        assert_eq!(cache.products_in_category("screws").unwrap().len(), 1);
+        assert!(cache.products_in_category("Washers").unwrap().is_empty());
+
+        let prices: Vec<PriceInfo> = serde_json::from_str(r#"[{"Amount": 9.5, "MinimumQuantity": 1, "UnitOfMeasure": "Pack"}]"#).unwrap();
+        assert!(cache.get_prices("91290A115").unwrap().is_none());
+        cache.store_prices("91290a115", &prices).unwrap();
+        assert_eq!(cache.get_prices("91290A115").unwrap().unwrap()[0].unit_of_measure, "Pack");
+    }
+
+    #[test]
+    fn test_datasheet_search() {
+        let temp_dir = tempdir().unwrap();
+        let mut cache = ProductCache::open(temp_dir.path().join("cache.db")).unwrap();
+
+        let file = Path::new("/tmp/91290A115.pdf");
+        cache.store_datasheet_text("91290a115", file, "Old text").unwrap();
+        cache.store_datasheet_text("91290a115", file, "Operating temperature: -40 to 250 F").unwrap();
+        cache.store_datasheet_text("92141A008", Path::new("/tmp/92141A008.pdf"), "Washer for M3 screws").unwrap();
+        assert_eq!(cache.datasheet_count().unwrap(), 2);
+
+        let matches = cache.search_datasheets("operating TEMPERATURE", 10).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].part_number, "91290A115");
+        assert_eq!(matches[0].file, file);
+        assert!(matches[0].snippet.contains("[Operating] [temperature]"));
+
+        assert!(cache.search_datasheets("old", 10).unwrap().is_empty());
+        assert_eq!(cache.search_datasheets("-40 \"F", 10).unwrap().len(), 1);
+        assert!(cache.search_datasheets("   ", 10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+//! Cancelling bulk operations partway through
+//!
+//! `refresh`, `sync` and `cad-all` check the client's [`Cancellation`] between parts and
+//! race each request against it, so cancelling drops whatever is in flight while keeping
+//! (and saving) the work that already finished. The CLI cancels on Ctrl-C.
+
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// A flag that stops a bulk operation once set; clones share the flag
+#[derive(Debug, Clone)]
+pub struct Cancellation {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Cancellation {
+    fn default() -> Self {
+        Cancellation { sender: Arc::new(watch::channel(false).0) }
+    }
+}
+
+impl Cancellation {
+    /// Stop the running operation; there's no way back
+    pub fn cancel(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Wait until cancelled
+    pub async fn cancelled(&self) {
+        let mut receiver = self.sender.subscribe();
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+
+    /// Run `future` to completion, or drop it and return `None` if cancelled first
+    pub async fn run<F: Future>(&self, future: F) -> Option<F::Output> {
+        tokio::select! {
+            biased;
+            _ = self.cancelled() => None,
+            output = future => Some(output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancellation() {
+        let cancellation = Cancellation::default();
+        assert_eq!(cancellation.run(async { 1 }).await, Some(1));
+
+        let shared = cancellation.clone();
+        let stalled = tokio::spawn(async move { shared.run(std::future::pending::<()>()).await });
+        cancellation.cancel();
+        assert_eq!(stalled.await.unwrap(), None);
+        assert!(cancellation.is_cancelled());
+        assert_eq!(cancellation.run(async { 1 }).await, None);
+    }
+}
@@ -0,0 +1,102 @@
+//! Crosswalk from McMaster-Carr part numbers to internal ERP/PLM item numbers
+//!
+//! Mappings live in the subscription database and are independent of tracking, so a
+//! part keeps its item number after `mmc remove`. `info`, `export`, `list` and
+//! `bom enrich` include the item number wherever a part has one.
+
+use crate::models::bom::{find_column, Bom};
+use crate::models::product::ProductDetail;
+use crate::models::warning::{Warning, WarningKind};
+use crate::utils::error::{ClientError, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// Header names recognized as the internal item number column, most specific first
+const ITEM_COLUMN_NAMES: &[&str] = &[
+    "item number", "item no", "item #", "item", "erp item number", "erp item", "erp number", "erp",
+    "plm number", "plm", "internal part number", "internal pn",
+];
+
+/// A part's internal item number
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CrosswalkEntry {
+    pub part_number: String,
+    pub item_number: String,
+}
+
+/// Crosswalk methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// The internal item number mapped to a part, if any
+    pub fn item_number(&self, part: &str) -> Result<Option<String>> {
+        self.subscriptions().get_item_number(part)
+    }
+
+    /// Map a part to an item number, or with `None` remove its mapping; returns whether it changed
+    pub fn set_item_number(&self, part: &str, item_number: Option<&str>) -> Result<bool> {
+        self.subscriptions().set_item_number(part, item_number)
+    }
+
+    /// Every mapping, sorted by part number
+    pub fn crosswalk(&self) -> Result<Vec<CrosswalkEntry>> {
+        Ok(self.subscriptions().get_crosswalk()?
+            .into_iter()
+            .map(|(part_number, item_number)| CrosswalkEntry { part_number, item_number })
+            .collect())
+    }
+
+    /// Load mappings from a CSV with part number and item number columns; returns how many changed
+    ///
+    /// Columns are found by header as in BOMs (e.g. "McMaster PN" and "Item Number");
+    /// rows missing either value are skipped.
+    pub fn import_crosswalk(&self, path: &Path) -> Result<usize> {
+        let csv = Bom::read(path, None)?;
+        let item_column = find_column(&csv.headers, ITEM_COLUMN_NAMES)
+            .filter(|column| *column != csv.part_column)
+            .ok_or_else(|| ClientError::Parse("No item number column found; name it 'Item Number'".to_string()))?;
+
+        let mut subscriptions = self.subscriptions();
+        let mut changed = 0;
+        for row in &csv.rows {
+            let (Some(part), Some(item_number)) = (csv.part_number(row), row.get(item_column).filter(|item| !item.is_empty())) else {
+                continue;
+            };
+            if subscriptions.set_item_number(&part, Some(item_number))? {
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Fill in a product's item number from the crosswalk, warning rather than failing if it can't be read
+    pub(crate) fn attach_item_number(&self, product: &mut ProductDetail) {
+        match self.item_number(&product.part_number) {
+            Ok(item_number) => product.item_number = item_number,
+            Err(e) => self.warn(Warning::new(WarningKind::CrosswalkUnavailable, &product.part_number, format!("Could not read the item number crosswalk: {}", e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{MockApi, PART_NUMBER};
+
+    #[tokio::test]
+    async fn test_crosswalk() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+
+        let file = api.data_dir().join("crosswalk.csv");
+        std::fs::write(&file, format!("Item Number,Description,McMaster PN\nHW-00412,Screw,{}\nHW-00413,Nut,\n", PART_NUMBER.to_lowercase())).unwrap();
+        assert_eq!(client.import_crosswalk(&file).unwrap(), 1);
+        assert_eq!(client.import_crosswalk(&file).unwrap(), 0);
+        assert_eq!(client.get_product(PART_NUMBER).await.unwrap().item_number.as_deref(), Some("HW-00412"));
+
+        // Mappings outlive tracking
+        client.subscriptions().remove_part(PART_NUMBER).unwrap();
+        assert!(client.set_item_number("92141a008", Some("HW-00500")).unwrap());
+        let parts: Vec<_> = client.crosswalk().unwrap().into_iter().map(|entry| entry.part_number).collect();
+        assert_eq!(parts, vec!["91290A115", "92141A008"]);
+        assert!(client.set_item_number(PART_NUMBER, None).unwrap());
+        assert_eq!(client.item_number(PART_NUMBER).unwrap(), None);
+    }
+}
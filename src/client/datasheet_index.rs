@@ -0,0 +1,88 @@
+//! Full-text index of downloaded datasheets
+//!
+//! Text is extracted from datasheet PDFs and stored in the product cache database,
+//! so `mmc search` can find parts by what their datasheets say.
+
+use crate::client::cache::DatasheetMatch;
+use crate::client::downloads::DownloadResult;
+use crate::models::warning::{Warning, WarningKind};
+use crate::utils::error::{ClientError, Result};
+use std::path::PathBuf;
+
+/// Datasheet indexing methods for McmasterClient
+impl super::api::McmasterClient {
+    /// Extract the text of downloaded datasheets into the search index, returning how many were indexed
+    ///
+    /// Files whose text can't be extracted (scanned images, damaged PDFs) are skipped with a warning.
+    pub async fn index_datasheets(&self, product: &str, results: &[DownloadResult]) -> usize {
+        let mut indexed = 0;
+        for result in results.iter().filter(|result| result.is_success()) {
+            let stored = extract_text(result.file_path.clone()).await
+                .and_then(|text| self.cache().store_datasheet_text(product, &result.file_path, &text));
+            match stored {
+                Ok(()) => indexed += 1,
+                Err(e) => self.warn(Warning::new(
+                    WarningKind::DatasheetNotIndexed,
+                    result.file_path.display().to_string(),
+                    format!("Could not index datasheet: {}", e),
+                )),
+            }
+        }
+        indexed
+    }
+
+    /// Search indexed datasheet text; works offline
+    pub fn search_datasheets(&self, query: &str, limit: usize) -> Result<Vec<DatasheetMatch>> {
+        self.cache().search_datasheets(query, limit)
+    }
+}
+
+/// Extract a PDF's text off the async runtime; the extractor can panic on malformed files
+async fn extract_text(path: PathBuf) -> Result<String> {
+    let text = tokio::task::spawn_blocking(move || pdf_extract::extract_text(&path))
+        .await
+        .map_err(|_| ClientError::Parse("PDF text extraction failed".to_string()))?
+        .map_err(|e| ClientError::Parse(format!("Unreadable PDF: {}", e)))?;
+
+    if text.trim().is_empty() {
+        return Err(ClientError::Parse("no text found (the PDF may be scanned images)".to_string()));
+    }
+    Ok(normalize_whitespace(&text))
+}
+
+/// Collapse the layout whitespace PDF extraction produces
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockApi;
+
+    #[tokio::test]
+    async fn test_unreadable_datasheet_is_skipped() {
+        let api = MockApi::start().await;
+        let client = api.client().unwrap();
+
+        let file_path = api.data_dir().join("91290A115.pdf");
+        std::fs::write(&file_path, "not a pdf").unwrap();
+        let result = DownloadResult {
+            filename: "91290A115.pdf".to_string(),
+            file_path: file_path.clone(),
+            url: String::new(),
+            label: None,
+            bytes: 9,
+            skipped: false,
+            error: None,
+            sha256: None,
+            extracted: Vec::new(),
+        };
+
+        assert_eq!(client.index_datasheets("91290A115", &[result]).await, 0);
+        let warnings = client.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::DatasheetNotIndexed);
+        assert_eq!(normalize_whitespace("Operating\n  temperature\t-40 F"), "Operating temperature -40 F");
+    }
+}
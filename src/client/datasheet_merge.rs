@@ -0,0 +1,218 @@
+//! Merging downloaded datasheets into one bookmarked PDF, for `mmc datasheet merge`
+//!
+//! Each part's datasheets are appended in part order under a bookmark naming the part,
+//! with a child bookmark per file when a part has several. Only datasheets already
+//! downloaded are merged; parts without one are reported rather than fetched.
+
+use crate::utils::error::{ClientError, Result};
+use crate::utils::lock::write_atomic;
+use lopdf::{dictionary, Bookmark, Document, Object, ObjectId};
+use std::path::{Path, PathBuf};
+
+/// Page attributes a page can inherit from its parent `Pages` nodes
+const INHERITED_PAGE_KEYS: &[&[u8]] = &[b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// What `mmc datasheet merge` wrote
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    pub path: PathBuf,
+    pub pages: usize,
+    pub merged: Vec<String>, // Parts with at least one datasheet in the PDF
+    pub missing: Vec<String>, // Parts with no downloaded datasheet
+    pub failed: Vec<(String, String)>, // (datasheet file, error) for PDFs that couldn't be read
+}
+
+/// Pages and objects taken from one source PDF, renumbered to fit the merged document
+struct SourcePages {
+    pages: Vec<(ObjectId, lopdf::Dictionary)>, // In page order, with inherited attributes filled in
+    objects: Vec<(ObjectId, Object)>,
+    max_id: u32, // Highest object number used
+}
+
+/// Renumber a PDF's objects from `first_id` and pull out its pages
+fn take_pages(mut doc: Document, first_id: u32) -> SourcePages {
+    doc.renumber_objects_with(first_id);
+    let max_id = doc.max_id;
+    let mut pages = Vec::new();
+    for id in doc.get_pages().into_values() {
+        let Ok(page) = doc.get_dictionary(id) else {
+            continue;
+        };
+        let mut page = page.clone();
+        for key in INHERITED_PAGE_KEYS {
+            if page.has(key) {
+                continue;
+            }
+            // Walk up the page tree for the nearest ancestor that sets it
+            let mut parent = page.get(b"Parent").and_then(Object::as_reference).ok();
+            while let Some(parent_id) = parent {
+                let Ok(node) = doc.get_dictionary(parent_id) else {
+                    break;
+                };
+                if let Ok(value) = node.get(key) {
+                    page.set(key.to_vec(), value.clone());
+                    break;
+                }
+                parent = node.get(b"Parent").and_then(Object::as_reference).ok();
+            }
+        }
+        pages.push((id, page));
+    }
+
+    // The page tree, catalog and outline are rebuilt for the merged document
+    let objects = doc.objects.into_iter()
+        .filter(|(_, object)| !matches!(object.type_name().unwrap_or_default(), b"Page" | b"Pages" | b"Catalog" | b"Outlines"))
+        .collect();
+    SourcePages { pages, objects, max_id }
+}
+
+/// Merge PDFs grouped under titles into one document
+///
+/// `sources` holds (bookmark title, [(file name, PDF)]); a group with several files gets
+/// a child bookmark per file.
+fn merge_pdfs(sources: Vec<(String, Vec<(String, Document)>)>) -> Result<(Vec<u8>, usize)> {
+    let mut merged = Document::with_version("1.5");
+    let mut next_id = 1;
+    let mut kids = Vec::new();
+    let mut pages = Vec::new();
+    for (title, files) in sources {
+        let mut group = None;
+        let several = files.len() > 1;
+        for (name, doc) in files {
+            let source = take_pages(doc, next_id);
+            next_id = source.max_id + 1;
+            let Some(&(first_page, _)) = source.pages.first() else {
+                continue;
+            };
+            let parent = *group.get_or_insert_with(|| merged.add_bookmark(Bookmark::new(title.clone(), [0.0; 3], 0, first_page), None));
+            if several {
+                merged.add_bookmark(Bookmark::new(name, [0.0; 3], 0, first_page), Some(parent));
+            }
+            kids.extend(source.pages.iter().map(|(id, _)| Object::Reference(*id)));
+            pages.extend(source.pages);
+            merged.objects.extend(source.objects);
+        }
+    }
+    if pages.is_empty() {
+        return Err(ClientError::NotFound("No datasheet pages to merge".to_string()));
+    }
+
+    let pages_id = (next_id, 0);
+    let catalog_id = (next_id + 1, 0);
+    merged.max_id = next_id + 1;
+    let page_count = pages.len();
+    for (id, mut page) in pages {
+        page.set("Parent", pages_id);
+        merged.objects.insert(id, Object::Dictionary(page));
+    }
+    merged.objects.insert(pages_id, Object::Dictionary(dictionary! {
+        "Type" => "Pages",
+        "Kids" => kids,
+        "Count" => page_count as i64,
+    }));
+    let mut catalog = dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+        "PageMode" => "UseOutlines",
+    };
+    if let Some(outline_id) = merged.build_outline() {
+        catalog.set("Outlines", outline_id);
+    }
+    merged.objects.insert(catalog_id, Object::Dictionary(catalog));
+    merged.trailer.set("Root", catalog_id);
+
+    let mut pdf = Vec::new();
+    merged.save_to(&mut pdf).map_err(|e| ClientError::Generic(format!("Could not write PDF: {}", e)))?;
+    Ok((pdf, page_count))
+}
+
+/// Datasheet merging methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Merge the downloaded datasheets of `parts`, in order, into one bookmarked PDF at `output`
+    ///
+    /// Bookmarks read "{part} - {description}" when the part's details are cached.
+    pub fn merge_datasheets(&self, parts: &[String], output: &Path) -> Result<MergeReport> {
+        let mut report = MergeReport { path: output.to_path_buf(), ..Default::default() };
+        let mut sources = Vec::new();
+        for part in parts {
+            let mut files = Vec::new();
+            for path in self.downloaded_files(part, "datasheets")? {
+                if !path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("pdf")) {
+                    continue;
+                }
+                let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                match Document::load(&path) {
+                    Ok(doc) => files.push((name, doc)),
+                    Err(e) => report.failed.push((path.display().to_string(), e.to_string())),
+                }
+            }
+            if files.is_empty() {
+                report.missing.push(part.clone());
+                continue;
+            }
+
+            let title = match self.cache().get_product(part)? {
+                Some(cached) if !cached.detail.detail_description.is_empty() => format!("{} - {}", part, cached.detail.detail_description),
+                _ => part.clone(),
+            };
+            report.merged.push(part.clone());
+            sources.push((title, files));
+        }
+
+        if sources.is_empty() {
+            return Err(ClientError::NotFound(format!(
+                "No downloaded datasheets for {}; download them with `mmc datasheet <part>`",
+                report.missing.join(", ")
+            )));
+        }
+        let (pdf, pages) = merge_pdfs(sources)?;
+        if let Some(parent) = output.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        write_atomic(output, &pdf)?;
+        report.pages = pages;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::label::{Label, LabelFormat};
+    use crate::models::product::ProductDetail;
+    use crate::test_support::{product_fixture, MockApi, PART_NUMBER};
+
+    #[tokio::test]
+    async fn test_merge_datasheets() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        client.get_product(PART_NUMBER).await.unwrap();
+
+        // Label PDFs keep their page size and fonts on the page tree, so pages must inherit them
+        let detail: ProductDetail = serde_json::from_value(product_fixture()).unwrap();
+        let pdf = Label::from_product(&detail).render(LabelFormat::Pdf).unwrap();
+        let dir = client.get_output_path(None, PART_NUMBER, "datasheets");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("91290A115_1.pdf"), &pdf).unwrap();
+        std::fs::write(dir.join("91290A115_2.pdf"), &pdf).unwrap();
+
+        let output = api.data_dir().join("merged.pdf");
+        let parts = vec![PART_NUMBER.to_string(), "90591A111".to_string()];
+        let report = client.merge_datasheets(&parts, &output).unwrap();
+        assert_eq!((report.pages, report.merged, report.missing), (2, vec![PART_NUMBER.to_string()], vec!["90591A111".to_string()]));
+
+        let merged = Document::load(&output).unwrap();
+        let pages = merged.get_pages();
+        assert_eq!(pages.len(), 2);
+        for id in pages.values() {
+            assert!(merged.get_dictionary(*id).unwrap().has(b"MediaBox"));
+        }
+        let catalog = merged.catalog().unwrap();
+        let outlines = merged.get_dictionary(catalog.get(b"Outlines").unwrap().as_reference().unwrap()).unwrap();
+        let top = merged.get_dictionary(outlines.get(b"First").unwrap().as_reference().unwrap()).unwrap();
+        assert_eq!(top.get(b"Title").unwrap().as_str().unwrap(), b"91290A115 - M3 x 0.5 mm Thread, 10 mm Long");
+        assert!(top.has(b"First")); // A child bookmark per file
+
+        assert!(client.merge_datasheets(&["90591A111".to_string()], &output).is_err());
+    }
+}
@@ -1,65 +1,206 @@
 //! Download functionality for images, CAD files, and datasheets
 
-use anyhow::Result;
-use std::path::PathBuf;
+use crate::utils::archive::{extract_flat, is_zip};
+use crate::utils::error::{ClientError, Result};
+use futures::stream::{self, StreamExt};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
-use crate::models::auth::ErrorResponse;
 use crate::models::api::{ProductResponse, ProductLinks, CadFile, CadFormat};
+use crate::client::manifest::{DownloadManifest, MANIFEST_FILENAME};
+use crate::integrations::dxf::{is_dxf_file, postprocess_file};
+use crate::integrations::step::{embed_metadata_in_file, is_step_file, StepMetadata};
+use crate::models::warning::{Warning, WarningKind};
+
+/// Default number of concurrent downloads
+pub const DEFAULT_DOWNLOAD_JOBS: usize = 4;
+
+/// A single file queued for download
+#[derive(Debug, Clone)]
+pub struct DownloadTask {
+    pub url: String,
+    pub file_path: PathBuf,
+    pub filename: String,
+    pub label: Option<String>, // Extra context shown in the summary, e.g. "3-D STEP"
+    pub step_metadata: Option<StepMetadata>, // Written into the header of a downloaded STEP file
+}
+
+/// Outcome of a single file download
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadResult {
+    pub filename: String,
+    pub file_path: PathBuf,
+    pub url: String,
+    pub label: Option<String>,
+    pub bytes: u64,
+    pub skipped: bool, // File was unchanged according to the manifest
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>, // Hex checksum recorded in the manifest
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extracted: Vec<PathBuf>, // Further files unpacked from a ZIP download, besides `file_path`
+}
+
+/// Summary of a bulk download across many parts
+#[derive(Debug, Default)]
+pub struct BulkDownloadReport {
+    pub total_parts: usize,
+    pub results: Vec<DownloadResult>,
+    pub no_files: Vec<String>, // Parts without any matching files
+    pub failures: Vec<(String, String)>, // (part, error) for link lookups and failed downloads
+    pub remaining: Vec<String>, // Parts not finished before the download was cancelled
+}
+
+impl BulkDownloadReport {
+    /// Number of parts that had files and downloaded without errors
+    pub fn succeeded_parts(&self) -> usize {
+        let unfinished: std::collections::BTreeSet<&String> = self.failures.iter()
+            .map(|(part, _)| part)
+            .chain(&self.remaining)
+            .collect();
+        self.total_parts - unfinished.len() - self.no_files.len()
+    }
+}
+
+impl DownloadResult {
+    /// Whether the download completed successfully
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
 
 /// Download-related methods for McmasterClient
 impl super::api::McmasterClient {
     /// Download product images
-    pub async fn download_images(&self, product: &str, output_dir: Option<&str>) -> Result<()> {
-        let token = self.token.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("Not authenticated. Please login first with 'mmc login'")
-        })?;
+    pub async fn download_images(&self, product: &str, output_dir: Option<&str>) -> Result<Vec<DownloadResult>> {
+        let token = self.require_token().await?;
 
         // Get product links
-        let links = self.get_product_links(product, token).await?;
+        let links = self.get_product_links(product, &token).await?;
 
         if links.images.is_empty() {
-            println!("ℹ️  No images available for product {}", product);
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         // Determine output directory
         let output_path = self.get_output_path(output_dir, product, "images");
         fs::create_dir_all(&output_path).await?;
 
-        println!("📥 Downloading {} images to {}", links.images.len(), output_path.display());
+        let tasks: Vec<DownloadTask> = links.images.iter().enumerate()
+            .map(|(i, image_url)| {
+                let filename = if links.images.len() == 1 {
+                    format!("{}.jpg", product)
+                } else {
+                    format!("{}_{}.jpg", product, i + 1)
+                };
+                DownloadTask {
+                    url: image_url.clone(),
+                    file_path: output_path.join(&filename),
+                    filename,
+                    label: None,
+                    step_metadata: None,
+                }
+            })
+            .collect();
+
+        Ok(self.run_downloads(tasks).await)
+    }
 
-        for (i, image_url) in links.images.iter().enumerate() {
-            let filename = if links.images.len() == 1 {
-                format!("{}.jpg", product)
-            } else {
-                format!("{}_{}.jpg", product, i + 1)
+    /// Download CAD files
+    pub async fn download_cad(&self, product: &str, output_dir: Option<&str>, formats: &[&str], download_all: bool) -> Result<Vec<DownloadResult>> {
+        let output_path = self.get_output_path(output_dir, product, "cad");
+        let tasks = self.cad_tasks(product, &output_path, formats, download_all).await?;
+
+        if tasks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        fs::create_dir_all(&output_path).await?;
+
+        Ok(self.run_downloads(tasks).await)
+    }
+
+    /// Download CAD files for every locally tracked part (optionally only those with a tag) into {root}/{part}/cad/
+    pub async fn download_cad_all(&self, output_root: Option<&str>, formats: &[&str], download_all: bool, tag: Option<&str>) -> Result<BulkDownloadReport> {
+        let parts: Vec<String> = self.subscriptions().get_tracked_parts(tag)?
+            .into_iter()
+            .map(|part| part.part_number)
+            .collect();
+        self.download_cad_parts(&parts, output_root, formats, download_all).await
+    }
+
+    /// Download CAD files for the given parts into {output_root}/{part}/cad/, all in one download queue
+    ///
+    /// If cancelled, finished files stay in their manifests and the parts with files still
+    /// missing are listed in `remaining`.
+    pub async fn download_cad_parts(&self, parts: &[String], output_root: Option<&str>, formats: &[&str], download_all: bool) -> Result<BulkDownloadReport> {
+        let mut report = BulkDownloadReport {
+            total_parts: parts.len(),
+            ..Default::default()
+        };
+
+        let mut tasks = Vec::new();
+        let mut task_parts = Vec::new(); // Part number for each queued task
+
+        for (index, part) in parts.iter().enumerate() {
+            let output_path = match output_root {
+                Some(root) => PathBuf::from(root).join(part).join("cad"),
+                None => self.get_output_path(None, part, "cad"),
             };
-            let file_path = output_path.join(&filename);
 
-            match self.download_file(image_url, &file_path).await {
-                Ok(_) => println!("  ✅ Downloaded {}", filename),
-                Err(e) => eprintln!("  ❌ Failed to download {}: {}", filename, e),
+            let Some(part_tasks) = self.cancellation.run(self.cad_tasks(part, &output_path, formats, download_all)).await else {
+                report.remaining = parts[index..].to_vec();
+                break;
+            };
+            match part_tasks {
+                Ok(part_tasks) if part_tasks.is_empty() => report.no_files.push(part.clone()),
+                Ok(part_tasks) => {
+                    if let Err(e) = fs::create_dir_all(&output_path).await {
+                        report.failures.push((part.clone(), e.to_string()));
+                        continue;
+                    }
+                    task_parts.extend(std::iter::repeat_n(part.clone(), part_tasks.len()));
+                    tasks.extend(part_tasks);
+                }
+                Err(e) => report.failures.push((part.clone(), e.to_string())),
             }
         }
 
-        println!("✅ Image download complete");
-        Ok(())
+        if !tasks.is_empty() {
+            let queued: Vec<PathBuf> = tasks.iter().map(|task| task.file_path.clone()).collect();
+            let results = self.run_downloads(tasks).await;
+            let finished: HashMap<&Path, &DownloadResult> = results.iter().map(|result| (result.file_path.as_path(), result)).collect();
+            let mut unfinished = Vec::new();
+            for (part, file_path) in task_parts.into_iter().zip(&queued) {
+                match finished.get(file_path.as_path()) {
+                    Some(DownloadResult { filename, error: Some(e), .. }) => report.failures.push((part, format!("{}: {}", filename, e))),
+                    Some(_) => {}
+                    None if !unfinished.contains(&part) => unfinished.push(part),
+                    None => {}
+                }
+            }
+            // Parts cut short mid-download come before the ones never looked up
+            report.remaining.splice(0..0, unfinished);
+            report.results = results;
+        }
+
+        Ok(report)
     }
 
-    /// Download CAD files
-    pub async fn download_cad(&self, product: &str, output_dir: Option<&str>, formats: &[&str], download_all: bool) -> Result<()> {
-        let token = self.token.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("Not authenticated. Please login first with 'mmc login'")
-        })?;
+    /// Build download tasks for a product's CAD files matching the requested formats
+    async fn cad_tasks(&self, product: &str, output_path: &Path, formats: &[&str], download_all: bool) -> Result<Vec<DownloadTask>> {
+        let token = self.require_token().await?;
 
         // Get product links
-        let links = self.get_product_links(product, token).await?;
+        let links = self.get_product_links(product, &token).await?;
 
         if links.cad.is_empty() {
-            println!("ℹ️  No CAD files available for product {}", product);
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         // Filter CAD files by requested formats
@@ -76,88 +217,89 @@ impl super::api::McmasterClient {
                 .collect()
         };
 
-        if filtered_cad.is_empty() {
-            println!("ℹ️  No CAD files match the requested formats");
-            return Ok(());
-        }
-
-        // Determine output directory
-        let output_path = self.get_output_path(output_dir, product, "cad");
-        fs::create_dir_all(&output_path).await?;
-
-        println!("📥 Downloading {} CAD files to {}", filtered_cad.len(), output_path.display());
-
-        for cad_file in filtered_cad {
-            let extension = self.get_cad_extension(&cad_file.format);
-            let filename = format!("{}.{}", product, extension);
-            let file_path = output_path.join(&filename);
-
-            match self.download_file(&cad_file.url, &file_path).await {
-                Ok(_) => println!("  ✅ Downloaded {} ({})", filename, cad_file.key),
-                Err(e) => eprintln!("  ❌ Failed to download {}: {}", filename, e),
-            }
-        }
-
-        println!("✅ CAD download complete");
-        Ok(())
+        // Several links can share a format (2-D and 3-D DWG, SLDPRT and SLDDRW); the first
+        // keeps the plain name and the others add their link key, so no two tasks share a file
+        let mut taken = HashSet::new();
+        Ok(filtered_cad.iter()
+            .map(|cad_file| {
+                let extension = self.get_cad_extension(&cad_file.format);
+                let mut filename = format!("{}.{}", product, extension);
+                if taken.contains(&filename) {
+                    filename = format!("{}_{}.{}", product, key_slug(&cad_file.key), extension);
+                }
+                let mut number = 2;
+                while taken.contains(&filename) {
+                    filename = format!("{}_{}_{}.{}", product, key_slug(&cad_file.key), number, extension);
+                    number += 1;
+                }
+                taken.insert(filename.clone());
+                let step_metadata = (self.embed_metadata && cad_file.format == CadFormat::Step).then(|| StepMetadata {
+                    part_number: product.to_string(),
+                    description: links.description.clone(),
+                });
+                DownloadTask {
+                    url: cad_file.url.clone(),
+                    file_path: output_path.join(&filename),
+                    filename,
+                    label: Some(cad_file.key.clone()),
+                    step_metadata,
+                }
+            })
+            .collect())
     }
 
     /// Download datasheets
-    pub async fn download_datasheets(&self, product: &str, output_dir: Option<&str>) -> Result<()> {
-        let token = self.token.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("Not authenticated. Please login first with 'mmc login'")
-        })?;
+    pub async fn download_datasheets(&self, product: &str, output_dir: Option<&str>) -> Result<Vec<DownloadResult>> {
+        let token = self.require_token().await?;
 
         // Get product links
-        let links = self.get_product_links(product, token).await?;
+        let links = self.get_product_links(product, &token).await?;
 
         if links.datasheets.is_empty() {
-            println!("ℹ️  No datasheets available for product {}", product);
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         // Determine output directory
         let output_path = self.get_output_path(output_dir, product, "datasheets");
         fs::create_dir_all(&output_path).await?;
 
-        println!("📥 Downloading {} datasheets to {}", links.datasheets.len(), output_path.display());
-
-        for (i, datasheet_url) in links.datasheets.iter().enumerate() {
-            let filename = if links.datasheets.len() == 1 {
-                format!("{}.pdf", product)
-            } else {
-                format!("{}_{}.pdf", product, i + 1)
-            };
-            let file_path = output_path.join(&filename);
-
-            match self.download_file(datasheet_url, &file_path).await {
-                Ok(_) => println!("  ✅ Downloaded {}", filename),
-                Err(e) => eprintln!("  ❌ Failed to download {}: {}", filename, e),
-            }
-        }
+        let tasks: Vec<DownloadTask> = links.datasheets.iter().enumerate()
+            .map(|(i, datasheet_url)| {
+                let filename = if links.datasheets.len() == 1 {
+                    format!("{}.pdf", product)
+                } else {
+                    format!("{}_{}.pdf", product, i + 1)
+                };
+                DownloadTask {
+                    url: datasheet_url.clone(),
+                    file_path: output_path.join(&filename),
+                    filename,
+                    label: None,
+                    step_metadata: None,
+                }
+            })
+            .collect();
+
+        Ok(self.run_downloads(tasks).await)
+    }
 
-        println!("✅ Datasheet download complete");
-        Ok(())
+    /// The first datasheet downloaded for a product, as named by `download_datasheets`
+    pub fn downloaded_datasheet(&self, product: &str, output_dir: Option<&str>) -> Result<PathBuf> {
+        let dir = self.get_output_path(output_dir, product, "datasheets");
+        [format!("{}.pdf", product), format!("{}_1.pdf", product)]
+            .into_iter()
+            .map(|filename| dir.join(filename))
+            .find(|path| path.is_file())
+            .ok_or_else(|| ClientError::NotFound(format!("No datasheet for {} in '{}'; download it with `mmc datasheet {}`", product, dir.display(), product)))
     }
 
     /// Get product links from API
-    async fn get_product_links(&self, product: &str, token: &str) -> Result<ProductLinks> {
-        let url = format!("https://api.mcmaster.com/v1/products/{}", product);
-        let response = self.client.get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
-            .await?;
+    pub(crate) async fn get_product_links(&self, product: &str, token: &str) -> Result<ProductLinks> {
+        let url = self.api_url(&format!("/v1/products/{}", product));
+        let response = self.get_shared(&url, token).await?;
 
         if !response.status().is_success() {
-            let error_text = response.text().await?;
-            if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&error_text) {
-                return Err(anyhow::anyhow!(
-                    "Failed to get product links: {}",
-                    error_response.error_message.unwrap_or("Unknown error".to_string())
-                ));
-            } else {
-                return Err(anyhow::anyhow!("Failed to get product links: {}", error_text));
-            }
+            return Err(ClientError::from_http(response, "Failed to get product links").await);
         }
 
         let product_response: ProductResponse = response.json().await?;
@@ -179,59 +321,353 @@ impl super::api::McmasterClient {
                 });
             } else if link.key.contains("Data Sheet") || link.key.contains("Datasheet") {
                 datasheets.push(link.value);
+            } else {
+                self.warn(Warning::new(
+                    WarningKind::UnknownLinkKey,
+                    product,
+                    format!("Ignored link with unrecognized key '{}'", link.key),
+                ));
             }
         }
 
         Ok(ProductLinks {
+            description: product_response.detail_description,
             images,
             cad: cad_files,
             datasheets,
         })
     }
 
-    /// Download a file from URL to local path
-    async fn download_file(&self, url: &str, file_path: &PathBuf) -> Result<()> {
+    /// Run download tasks concurrently (bounded by the configured job count) with progress bars
+    ///
+    /// Files already recorded in the directory's manifest with a matching hash are skipped
+    /// unless forced downloads are enabled. If the client is cancelled, unfinished tasks are
+    /// left out of the results; their `.part` files stay behind to resume from next time.
+    pub async fn run_downloads(&self, tasks: Vec<DownloadTask>) -> Vec<DownloadResult> {
+        // Load the manifest for every target directory
+        let mut manifests: HashMap<PathBuf, DownloadManifest> = HashMap::new();
+        for task in &tasks {
+            let dir = manifest_dir(&task.file_path);
+            if let Entry::Vacant(slot) = manifests.entry(dir) {
+                let manifest = DownloadManifest::load(slot.key()).await;
+                slot.insert(manifest);
+            }
+        }
+
+        let mut results: Vec<(usize, DownloadResult)> = Vec::new();
+        let mut pending = Vec::new();
+        for (index, task) in tasks.into_iter().enumerate() {
+            let manifest = &manifests[&manifest_dir(&task.file_path)];
+            if !self.force_download && manifest.is_unchanged(&task.filename, &task.url, &task.file_path).await {
+                let entry = manifest.files.get(&task.filename);
+                results.push((index, DownloadResult {
+                    bytes: entry.map(|entry| entry.size).unwrap_or(0),
+                    sha256: entry.map(|entry| entry.sha256.clone()),
+                    filename: task.filename,
+                    file_path: task.file_path,
+                    url: task.url,
+                    label: task.label,
+                    skipped: true,
+                    error: None,
+                    extracted: Vec::new(),
+                }));
+            } else {
+                pending.push((index, task));
+            }
+        }
+
+        let progress = MultiProgress::new();
+        if !self.show_progress {
+            progress.set_draw_target(indicatif::ProgressDrawTarget::hidden());
+        }
+
+        let jobs = self.download_jobs.max(1);
+        let mut downloaded: Vec<(usize, DownloadResult)> = stream::iter(pending)
+            .map(|(index, task)| {
+                let progress = &progress;
+                async move {
+                    let bar = progress.add(ProgressBar::new(0));
+                    bar.set_style(Self::progress_style());
+                    bar.set_message(task.filename.clone());
+
+                    let outcome = self.cancellation.run(self.download_asset(&task.url, &task.file_path, &bar)).await;
+                    bar.finish_and_clear();
+
+                    let (bytes, error) = match outcome? {
+                        Ok(bytes) => (bytes, None),
+                        Err(e) => (0, Some(e.to_string())),
+                    };
+                    let step_metadata = task.step_metadata;
+                    let mut result = DownloadResult {
+                        filename: task.filename,
+                        file_path: task.file_path,
+                        url: task.url,
+                        label: task.label,
+                        bytes,
+                        skipped: false,
+                        error,
+                        sha256: None,
+                        extracted: Vec::new(),
+                    };
+                    if result.is_success() {
+                        if let Err(e) = self.unpack_archive(&mut result) {
+                            result.error = Some(e.to_string());
+                        }
+                    }
+                    // Before the manifest hashes the file, so the rewritten file counts as unchanged next time
+                    if let Some(metadata) = step_metadata.filter(|_| result.is_success() && is_step_file(&result.file_path)) {
+                        if let Err(e) = embed_metadata_in_file(&result.file_path, &metadata) {
+                            self.warn(Warning::new(WarningKind::MetadataNotEmbedded, &result.filename, e.to_string()));
+                        }
+                    }
+                    if let Some(options) = self.dxf_postprocess.as_ref().filter(|_| result.is_success()) {
+                        for path in std::iter::once(&result.file_path).chain(&result.extracted).filter(|path| is_dxf_file(path)) {
+                            if let Err(e) = postprocess_file(path, options) {
+                                self.warn(Warning::new(WarningKind::NotPostprocessed, &result.filename, e.to_string()));
+                            }
+                        }
+                    }
+                    Some((index, result))
+                }
+            })
+            .buffer_unordered(jobs)
+            .filter_map(std::future::ready)
+            .collect()
+            .await;
+
+        // Record fresh downloads in their manifests
+        for (_, result) in &mut downloaded {
+            if !result.is_success() {
+                continue;
+            }
+            if let Some(manifest) = manifests.get_mut(&manifest_dir(&result.file_path)) {
+                match manifest.record(&result.filename, &result.url, &result.file_path).await {
+                    Ok(entry) => result.sha256 = Some(entry.sha256),
+                    Err(e) => self.warn(Warning::new(
+                        WarningKind::ManifestNotSaved,
+                        &result.filename,
+                        format!("Could not hash file: {}", e),
+                    )),
+                }
+                // Unpacked siblings get entries too, so `mmc verify` covers them
+                for path in &result.extracted {
+                    let filename = path.file_name().unwrap_or_default().to_string_lossy();
+                    if let Err(e) = manifest.record(&filename, &result.url, path).await {
+                        self.warn(Warning::new(WarningKind::ManifestNotSaved, filename, format!("Could not hash file: {}", e)));
+                    }
+                }
+            }
+        }
+        for manifest in manifests.values() {
+            if let Err(e) = manifest.save().await {
+                self.warn(Warning::new(
+                    WarningKind::ManifestNotSaved,
+                    manifest.path().display().to_string(),
+                    format!("Could not write download manifest: {}", e),
+                ));
+            }
+        }
+
+        // Report results in the order the tasks were queued
+        results.extend(downloaded);
+        for (_, result) in &results {
+            self.metrics.record_download(result.skipped, result.error.is_some(), result.bytes);
+        }
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Check whether a finished download is really a ZIP archive and, if extraction is on, unpack it
+    ///
+    /// The first unpacked file in the download's format (a `.stp` counts as `.step`) replaces
+    /// the archive under the queued name, so the result and its manifest entry keep that name;
+    /// the rest are listed in `extracted`. If no file is in that format, the archive stays as
+    /// downloaded beside them. Without extraction, the archive is kept with a warning.
+    fn unpack_archive(&self, result: &mut DownloadResult) -> Result<()> {
+        let named_zip = result.file_path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("zip"));
+        if named_zip || !is_zip(&result.file_path) {
+            return Ok(());
+        }
+        if !self.extract_archives {
+            self.warn(Warning::new(
+                WarningKind::ArchiveNotExtracted,
+                &result.filename,
+                format!("{} is a ZIP archive; download it again with --extract --force to unpack it", result.file_path.display()),
+            ));
+            return Ok(());
+        }
+
+        let stem = result.file_path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        let mut paths = extract_flat(&result.file_path, &manifest_dir(&result.file_path), &stem)?;
+        if paths.is_empty() {
+            return Err(ClientError::Parse(format!("{} is an empty ZIP archive", result.filename)));
+        }
+        let queued_format = format_extension(&result.file_path);
+        let primary = paths.iter().position(|path| *path == result.file_path)
+            .or_else(|| paths.iter().position(|path| format_extension(path) == queued_format));
+        if let Some(position) = primary {
+            let primary = paths.remove(position);
+            if primary != result.file_path {
+                std::fs::rename(&primary, &result.file_path)?;
+            }
+        }
+        result.extracted = paths;
+        Ok(())
+    }
+
+    /// Download a single asset from URL to local path, reporting progress on the given bar
+    ///
+    /// Data is streamed into a `{file}.part` temp file. If a partial file is left over
+    /// from an interrupted run of the same URL, the download resumes with an HTTP range
+    /// request, sent with `If-Range` so a changed file is fetched whole instead of stitched
+    /// together. The temp file is only renamed into place once its size matches the
+    /// expected length.
+    pub(crate) async fn download_asset(&self, url: &str, file_path: &Path, bar: &ProgressBar) -> Result<u64> {
         // Convert relative URLs to absolute URLs
         let full_url = if url.starts_with('/') {
-            format!("https://api.mcmaster.com{}", url)
+            self.api_url(url)
         } else {
             url.to_string()
         };
-        
+
         // Add authentication token for download requests
-        let token = self.token.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("Not authenticated. Please login first with 'mmc login'")
-        })?;
-        
-        let response = self.client.get(&full_url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send().await?;
-        
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to download file: HTTP {}", response.status()));
+        let token = self.require_token().await?;
+
+        let part_path = partial_path(file_path);
+        let resume_path = resume_info_path(file_path);
+        let mut existing = match fs::metadata(&part_path).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        };
+        // A partial file from another URL, or without a record of where it came from, can't be resumed
+        let resume = match existing {
+            0 => None,
+            _ => ResumeInfo::read(&resume_path).await.filter(|info| info.url == full_url),
+        };
+        if resume.is_none() {
+            existing = 0;
         }
 
-        let mut file = fs::File::create(file_path).await?;
-        let content = response.bytes().await?;
-        file.write_all(&content).await?;
-        
-        Ok(())
+        let request = |from: u64| {
+            let mut request = self.client.get(&full_url)
+                .header("Authorization", format!("Bearer {}", token));
+            if let Some(info) = resume.as_ref().filter(|_| from > 0) {
+                request = request.header("Range", format!("bytes={}-", from));
+                if let Some(validator) = &info.validator {
+                    request = request.header("If-Range", validator);
+                }
+            }
+            request
+        };
+        let mut response = self.send(request(existing)).await?;
+
+        if existing > 0 && response.status().as_u16() == 416 {
+            let total = response.headers()
+                .get("Content-Range")
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_content_range_total);
+            // The partial file already holds the complete content
+            if total == Some(existing) {
+                fs::rename(&part_path, file_path).await?;
+                let _ = fs::remove_file(&resume_path).await;
+                return Ok(existing);
+            }
+            // Otherwise it's stale or corrupt; start over
+            let _ = fs::remove_file(&part_path).await;
+            existing = 0;
+            response = self.send(request(0)).await?;
+        }
+
+        // Only append when the server honoured the range request; otherwise start over
+        let resuming = existing > 0 && response.status().as_u16() == 206;
+        let expected_total = if resuming {
+            response.headers()
+                .get("Content-Range")
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_content_range_total)
+                .or_else(|| response.content_length().map(|length| existing + length))
+        } else {
+            response.content_length()
+        };
+
+        let mut file = if resuming {
+            fs::OpenOptions::new().append(true).open(&part_path).await?
+        } else {
+            ResumeInfo::from_response(&full_url, &response).write(&resume_path).await?;
+            fs::File::create(&part_path).await?
+        };
+
+        let mut written: u64 = if resuming { existing } else { 0 };
+        if let Some(total) = expected_total {
+            bar.set_length(total);
+        }
+        bar.set_position(written);
+
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+            bar.set_position(written);
+        }
+        file.flush().await?;
+        drop(file);
+
+        // Integrity check: keep the .part file for a later resume if we came up short
+        if let Some(total) = expected_total {
+            if written != total {
+                return Err(ClientError::Generic(format!(
+                    "Incomplete download: received {} of {} bytes (re-run to resume)",
+                    written, total
+                )));
+            }
+        }
+
+        fs::rename(&part_path, file_path).await?;
+        let _ = fs::remove_file(&resume_path).await;
+        Ok(written)
+    }
+
+    /// Progress bar style used for individual downloads
+    fn progress_style() -> ProgressStyle {
+        ProgressStyle::with_template("  {spinner} {msg:<30} [{bar:25}] {bytes:>10}/{total_bytes:<10}")
+            .unwrap_or_else(|_| ProgressStyle::default_bar())
+            .progress_chars("=> ")
+    }
+
+    /// Root directory for downloads: the configured download_dir, or ~/Downloads/mmc
+    pub(crate) fn download_root(&self) -> PathBuf {
+        self.download_dir.clone()
+            .unwrap_or_else(|| dirs::download_dir().unwrap_or_else(|| PathBuf::from(".")).join("mmc"))
     }
 
     /// Get output path for downloads
-    fn get_output_path(&self, output_dir: Option<&str>, product: &str, category: &str) -> PathBuf {
+    pub(crate) fn get_output_path(&self, output_dir: Option<&str>, product: &str, category: &str) -> PathBuf {
         if let Some(dir) = output_dir {
             PathBuf::from(dir)
         } else {
-            // Default to ~/Downloads/mmc/{product}/{category}/
-            dirs::download_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join("mmc")
-                .join(product)
-                .join(category)
+            // Default to {download_root}/{product}/{category}/
+            self.download_root().join(product).join(category)
         }
     }
 
+    /// Files already downloaded to a part's `{category}` folder, skipping the manifest, partial downloads and dotfiles
+    pub(crate) fn downloaded_files(&self, product: &str, category: &str) -> Result<Vec<PathBuf>> {
+        let entries = match std::fs::read_dir(self.get_output_path(None, product, category)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut files = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            if path.is_file() && name != MANIFEST_FILENAME && !name.starts_with('.') && !name.ends_with(".part") {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
     /// Get file extension for CAD format
     fn get_cad_extension(&self, format: &CadFormat) -> &'static str {
         match format {
@@ -245,4 +681,237 @@ impl super::api::McmasterClient {
             CadFormat::Pdf => "pdf",
         }
     }
-}
\ No newline at end of file
+}
+
+/// Directory whose manifest tracks the given file
+fn manifest_dir(file_path: &Path) -> PathBuf {
+    file_path.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// A file's lowercase extension, with alternate spellings of a CAD format folded together
+fn format_extension(path: &Path) -> String {
+    let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+    match extension.as_str() {
+        "stp" => "step".to_string(),
+        "igs" => "iges".to_string(),
+        _ => extension,
+    }
+}
+
+/// A link key as part of a file name, e.g. "2-D DWG" -> "2-D_DWG"
+fn key_slug(key: &str) -> String {
+    key.trim().chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' }).collect()
+}
+
+/// Path of the temp file used while a download is in progress
+pub fn partial_path(file_path: &Path) -> PathBuf {
+    let mut name = file_path.as_os_str().to_os_string();
+    name.push(".part");
+    PathBuf::from(name)
+}
+
+/// Where a `.part` file's download came from, kept beside it so a resume asks for the same content
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ResumeInfo {
+    url: String,
+    validator: Option<String>, // Strong ETag, else Last-Modified, sent back as If-Range
+}
+
+impl ResumeInfo {
+    fn from_response(url: &str, response: &reqwest::Response) -> Self {
+        let header = |name| response.headers().get(name).and_then(|value| value.to_str().ok());
+        // Weak ETags aren't allowed in If-Range
+        let validator = header("ETag").filter(|etag| !etag.starts_with("W/")).or_else(|| header("Last-Modified"));
+        ResumeInfo { url: url.to_string(), validator: validator.map(str::to_string) }
+    }
+
+    async fn read(path: &Path) -> Option<Self> {
+        serde_json::from_slice(&fs::read(path).await.ok()?).ok()
+    }
+
+    async fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_vec(self)?).await?;
+        Ok(())
+    }
+}
+
+/// Hidden file recording where `{file}.part` was downloaded from
+fn resume_info_path(file_path: &Path) -> PathBuf {
+    let name = file_path.file_name().unwrap_or_default().to_string_lossy();
+    file_path.with_file_name(format!(".{}.resume", name))
+}
+
+/// Parse the total size out of a `Content-Range` header (e.g. "bytes 100-999/1000")
+fn parse_content_range_total(value: &str) -> Option<u64> {
+    let (_, total) = value.rsplit_once('/')?;
+    total.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{product_fixture, MockApi, PART_NUMBER, STEP_FILE};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    /// Serve `links` as the product's download links, in place of the fixture's one STEP link
+    async fn mount_links(api: &MockApi, links: serde_json::Value) {
+        let mut product = product_fixture();
+        product["Links"] = links;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/products/{}", PART_NUMBER)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(product))
+            .with_priority(1)
+            .mount(api.server())
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_cad_links_sharing_a_format_get_their_own_files() {
+        let api = MockApi::start().await;
+        mount_links(&api, serde_json::json!([
+            {"Key": "2-D DWG", "Value": "/v1/cad/2d.dwg"},
+            {"Key": "3-D DWG", "Value": "/v1/cad/3d.dwg"},
+            {"Key": "3-D STEP", "Value": format!("/v1/cad/{}.step", PART_NUMBER)}
+        ])).await;
+        for (file, body) in [("2d.dwg", "two"), ("3d.dwg", "three")] {
+            Mock::given(method("GET"))
+                .and(path(format!("/v1/cad/{}", file)))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(api.server())
+                .await;
+        }
+        let client = api.logged_in_client().unwrap();
+
+        let results = client.download_cad(PART_NUMBER, None, &[], true).await.unwrap();
+        let names: Vec<&str> = results.iter().map(|result| result.filename.as_str()).collect();
+        assert_eq!(names, vec!["91290A115.dwg", "91290A115_3-D_DWG.dwg", "91290A115.step"]);
+        assert!(results.iter().all(DownloadResult::is_success));
+        assert_eq!(std::fs::read_to_string(&results[0].file_path).unwrap(), "two");
+        assert_eq!(std::fs::read_to_string(&results[1].file_path).unwrap(), "three");
+        assert_eq!(std::fs::read(&results[2].file_path).unwrap(), STEP_FILE);
+
+        // Each file has its own manifest entry, so a second run skips all three
+        let again = client.download_cad(PART_NUMBER, None, &[], true).await.unwrap();
+        assert!(again.iter().all(|result| result.skipped));
+    }
+
+    #[tokio::test]
+    async fn test_unpacked_archive_keeps_the_queued_name() {
+        use std::io::Write;
+
+        // A STEP link that serves a ZIP whose entries are named nothing like the part
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in [("model/a.stp", "first"), ("model/b.stp", "second"), ("drawing.dxf", "dxf")] {
+            zip.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        let archive = zip.finish().unwrap().into_inner();
+        let api = MockApi::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/cad/{}.step", PART_NUMBER)))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(archive))
+            .with_priority(1)
+            .mount(api.server())
+            .await;
+        let mut client = api.logged_in_client().unwrap();
+        client.set_extract_archives(true);
+
+        let report = client.download_cad_parts(&[PART_NUMBER.to_string()], None, &["step"], false).await.unwrap();
+        assert!(report.remaining.is_empty() && report.failures.is_empty());
+        assert_eq!(report.succeeded_parts(), 1);
+        let result = &report.results[0];
+        assert_eq!(result.filename, "91290A115.step");
+        assert_eq!(std::fs::read_to_string(&result.file_path).unwrap(), "first");
+        let dir = result.file_path.parent().unwrap();
+        assert_eq!(result.extracted, vec![dir.join("91290A115_2.stp"), dir.join("91290A115.dxf")]);
+        assert!(!dir.join("91290A115_1.stp").exists());
+
+        // The manifest entry under the queued name lets the next run skip it
+        let again = client.download_cad_parts(&[PART_NUMBER.to_string()], None, &["step"], false).await.unwrap();
+        assert!(again.results[0].skipped);
+    }
+
+    #[tokio::test]
+    async fn test_resume_checks_partial_file() {
+        use wiremock::matchers::header;
+
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        let url = format!("/v1/cad/{}.step", PART_NUMBER);
+        let file_path = api.data_dir().join("91290A115.step");
+        let total = STEP_FILE.len();
+        let start = |partial: &[u8], validator: Option<&str>| {
+            std::fs::write(partial_path(&file_path), partial).unwrap();
+            let info = ResumeInfo { url: format!("{}{}", api.uri(), url), validator: validator.map(str::to_string) };
+            std::fs::write(resume_info_path(&file_path), serde_json::to_vec(&info).unwrap()).unwrap();
+        };
+        let bar = ProgressBar::hidden();
+        let download = || client.download_asset(&url, &file_path, &bar);
+
+        // Any range past the end is refused, as for a partial file that's already complete
+        Mock::given(method("GET"))
+            .and(path(&url))
+            .and(header("Range", format!("bytes={}-", total).as_str()))
+            .respond_with(ResponseTemplate::new(416).insert_header("Content-Range", format!("bytes */{}", total).as_str()))
+            .with_priority(1)
+            .expect(1)
+            .mount(api.server())
+            .await;
+        Mock::given(method("GET"))
+            .and(path(&url))
+            .and(header("Range", "bytes=100-"))
+            .respond_with(ResponseTemplate::new(416).insert_header("Content-Range", format!("bytes */{}", total).as_str()))
+            .with_priority(1)
+            .expect(1)
+            .mount(api.server())
+            .await;
+        Mock::given(method("GET"))
+            .and(path(&url))
+            .and(header("Range", "bytes=10-"))
+            .and(header("If-Range", "\"v1\""))
+            .respond_with(ResponseTemplate::new(206)
+                .insert_header("Content-Range", format!("bytes 10-{}/{}", total - 1, total).as_str())
+                .set_body_bytes(&STEP_FILE[10..]))
+            .with_priority(1)
+            .expect(1)
+            .mount(api.server())
+            .await;
+
+        // A complete partial file is accepted as is
+        start(STEP_FILE, None);
+        assert_eq!(download().await.unwrap(), total as u64);
+        assert_eq!(std::fs::read(&file_path).unwrap(), STEP_FILE);
+        assert!(!resume_info_path(&file_path).exists());
+
+        // A stale one longer than the file is thrown away and the file fetched whole
+        start(&[b'x'; 100], None);
+        assert_eq!(download().await.unwrap(), total as u64);
+        assert_eq!(std::fs::read(&file_path).unwrap(), STEP_FILE);
+
+        // A resume sends the stored validator and appends the rest
+        start(&STEP_FILE[..10], Some("\"v1\""));
+        assert_eq!(download().await.unwrap(), total as u64);
+        assert_eq!(std::fs::read(&file_path).unwrap(), STEP_FILE);
+
+        // A partial file from another URL is never resumed
+        std::fs::write(partial_path(&file_path), b"other").unwrap();
+        std::fs::write(resume_info_path(&file_path), br#"{"url":"https://elsewhere.example/a.step","validator":null}"#).unwrap();
+        assert_eq!(download().await.unwrap(), total as u64);
+        assert_eq!(std::fs::read(&file_path).unwrap(), STEP_FILE);
+    }
+
+    #[test]
+    fn test_partial_path_appends_suffix() {
+        let path = PathBuf::from("/tmp/mmc/91831A030.step");
+        assert_eq!(partial_path(&path), PathBuf::from("/tmp/mmc/91831A030.step.part"));
+    }
+
+    #[test]
+    fn test_parse_content_range_total() {
+        assert_eq!(parse_content_range_total("bytes 100-999/1000"), Some(1000));
+        assert_eq!(parse_content_range_total("bytes */1000"), Some(1000));
+        assert_eq!(parse_content_range_total("bytes 0-99/*"), None);
+        assert_eq!(parse_content_range_total("garbage"), None);
+    }
+}
@@ -0,0 +1,206 @@
+//! Append-only journal of subscription changes, for `mmc undo`
+//!
+//! Each add, remove and import is appended to `journal.jsonl` next to the subscription
+//! database as one JSON line. Undoing a change appends an `undo` entry naming it rather
+//! than editing the file, so the journal is a complete history and `mmc undo` run again
+//! steps further back.
+
+use crate::client::subscriptions::TrackedPart;
+use crate::models::warning::{Warning, WarningKind};
+use crate::utils::error::{ClientError, Result};
+use crate::utils::lock::FileLock;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A kind of change to the subscription list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// Subscribed through the API and started tracking
+    Add,
+    /// Unsubscribed through the API and stopped tracking
+    Remove,
+    /// Started tracking parts from a file (local only)
+    Import,
+    /// Reverted an earlier entry
+    Undo,
+}
+
+impl Operation {
+    /// Locale key for the operation's name
+    pub fn message_key(self) -> &'static str {
+        match self {
+            Operation::Add => "journal.add",
+            Operation::Remove => "journal.remove",
+            Operation::Import => "journal.import",
+            Operation::Undo => "journal.undo",
+        }
+    }
+}
+
+/// One line of the journal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub at: DateTime<Utc>,
+    pub operation: Operation,
+    pub parts: Vec<String>, // Parts the change applied to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<TrackedPart>, // Tags, notes and metadata of removed parts, restored by undo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undoes: Option<u64>, // For undo entries, the entry that was reverted
+}
+
+/// What `mmc undo` reverted
+#[derive(Debug, Serialize)]
+pub struct UndoReport {
+    pub entry: JournalEntry, // The change that was undone
+    pub reverted: Vec<String>,
+    pub failed: Vec<(String, String)>, // (part, error); the change stays undoable if any part failed
+}
+
+/// The journal file
+#[derive(Debug, Clone)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    pub fn new(path: PathBuf) -> Self {
+        Journal { path }
+    }
+
+    /// Every entry, oldest first
+    pub fn entries(&self) -> Result<Vec<JournalEntry>> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    ClientError::Parse(format!("Invalid journal entry on line {} of '{}': {}", index + 1, self.path.display(), e))
+                })
+            })
+            .collect()
+    }
+
+    /// Append an entry, numbering it after the last one
+    pub fn record(&self, operation: Operation, parts: Vec<String>, removed: Vec<TrackedPart>, undoes: Option<u64>) -> Result<JournalEntry> {
+        let _lock = FileLock::exclusive(&self.path)?;
+        let id = self.entries()?.last().map_or(1, |entry| entry.id + 1);
+        let entry = JournalEntry { id, at: Utc::now(), operation, parts, removed, undoes };
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(entry)
+    }
+
+    /// The most recent change that hasn't been undone
+    pub fn last_undoable(&self) -> Result<Option<JournalEntry>> {
+        let entries = self.entries()?;
+        let undone: BTreeSet<u64> = entries.iter().filter_map(|entry| entry.undoes).collect();
+        Ok(entries.into_iter().rev().find(|entry| entry.operation != Operation::Undo && !undone.contains(&entry.id)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Journal methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// The journal kept next to the subscription database
+    pub fn journal(&self) -> Journal {
+        Journal::new(self.subscriptions().get_file_path().with_file_name("journal.jsonl"))
+    }
+
+    /// Record a change for undo, warning rather than failing if the journal can't be written
+    pub(crate) fn journal_change(&self, operation: Operation, parts: Vec<String>, removed: Vec<TrackedPart>) {
+        if parts.is_empty() {
+            return;
+        }
+        if let Err(e) = self.journal().record(operation, parts, removed, None) {
+            self.warn(Warning::general(WarningKind::TrackingNotSaved, format!("Could not record the change for undo: {}", e)));
+        }
+    }
+
+    /// Revert the most recent add, remove or import that hasn't been undone yet
+    ///
+    /// Adds are unsubscribed and removes re-subscribed through the API, with their tags,
+    /// notes and metadata restored; imports stop tracking the parts they added. Returns
+    /// `None` when there's nothing left to undo.
+    pub async fn undo_last(&self) -> Result<Option<UndoReport>> {
+        let journal = self.journal();
+        let Some(entry) = journal.last_undoable()? else {
+            return Ok(None);
+        };
+
+        let mut report = UndoReport { entry: entry.clone(), reverted: Vec::new(), failed: Vec::new() };
+        for part in &entry.parts {
+            let reverted = match entry.operation {
+                Operation::Add => self.unsubscribe(part).await,
+                Operation::Remove => match self.subscribe(part).await {
+                    Ok(_) => match entry.removed.iter().find(|removed| &removed.part_number == part) {
+                        Some(removed) => self.subscriptions().restore_part(removed),
+                        None => Ok(()),
+                    },
+                    Err(e) => Err(e),
+                },
+                Operation::Import => self.subscriptions().remove_part(part).map(|_| ()),
+                Operation::Undo => Ok(()),
+            };
+            match reverted {
+                Ok(()) => report.reverted.push(part.clone()),
+                Err(e @ (ClientError::NotAuthenticated | ClientError::AuthExpired)) => return Err(e),
+                Err(e) => report.failed.push((part.clone(), e.to_string())),
+            }
+        }
+
+        if report.failed.is_empty() {
+            journal.record(Operation::Undo, report.reverted.clone(), Vec::new(), Some(entry.id))?;
+        }
+        Ok(Some(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockApi, PART_NUMBER};
+
+    #[tokio::test]
+    async fn test_undo_import_and_add() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+
+        client.subscriptions().add_part("90591A111").unwrap();
+        let file = api.data_dir().join("parts.txt");
+        fs::write(&file, "90591A111\n92141A008\tfasteners\n").unwrap();
+        assert_eq!(client.import_subscriptions(file.to_str().unwrap()).unwrap(), 1);
+        client.add_product(PART_NUMBER).await.unwrap();
+
+        let entries = client.journal().entries().unwrap();
+        assert_eq!(entries.iter().map(|entry| entry.operation).collect::<Vec<_>>(), vec![Operation::Import, Operation::Add]);
+        assert_eq!(entries[0].parts, vec!["92141A008"]);
+
+        // Undo the add, then the import, which only drops the part it added
+        let report = client.undo_last().await.unwrap().unwrap();
+        assert_eq!((report.entry.operation, report.reverted), (Operation::Add, vec![PART_NUMBER.to_string()]));
+        let report = client.undo_last().await.unwrap().unwrap();
+        assert_eq!(report.entry.operation, Operation::Import);
+        assert_eq!(client.subscriptions().get_all_parts().unwrap(), vec!["90591A111"]);
+        assert!(client.undo_last().await.unwrap().is_none());
+        assert_eq!(client.journal().entries().unwrap().last().unwrap().undoes, Some(1));
+    }
+}
@@ -0,0 +1,210 @@
+//! Download manifest tracking for skip-unchanged logic and `mmc verify`
+
+use crate::utils::error::{ClientError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Name of the manifest file written alongside downloads
+pub const MANIFEST_FILENAME: &str = "mmc-manifest.json";
+
+/// A single downloaded file recorded in the manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub filename: String,
+    pub url: String,
+    pub size: u64,
+    pub sha256: String,
+    pub timestamp: String, // RFC 3339 time the file was downloaded
+}
+
+/// How a recorded file compares with its manifest entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    Ok,
+    Modified, // The contents no longer hash to the recorded checksum
+    Missing,
+}
+
+/// A downloaded file re-hashed against its manifest
+#[derive(Debug, Clone, Serialize)]
+pub struct FileCheck {
+    pub file_path: PathBuf,
+    pub status: FileStatus,
+    pub expected_sha256: String,
+    pub actual_sha256: Option<String>, // None when the file is missing or unreadable
+}
+
+/// Manifest of files downloaded into a single directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DownloadManifest {
+    #[serde(skip)]
+    path: PathBuf,
+    pub files: BTreeMap<String, ManifestEntry>, // Keyed by filename for stable output
+}
+
+impl DownloadManifest {
+    /// Load the manifest for a download directory (empty if none exists or it can't be read)
+    pub async fn load(dir: &Path) -> Self {
+        let path = dir.join(MANIFEST_FILENAME);
+        let mut manifest: DownloadManifest = match fs::read_to_string(&path).await {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+            Err(_) => DownloadManifest::default(),
+        };
+        manifest.path = path;
+        manifest
+    }
+
+    /// Read a manifest file, failing if it's unreadable or corrupt
+    pub async fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).await?;
+        let mut manifest: DownloadManifest = serde_json::from_str(&content)
+            .map_err(|e| ClientError::Parse(format!("Invalid download manifest '{}': {}", path.display(), e)))?;
+        manifest.path = path.to_path_buf();
+        Ok(manifest)
+    }
+
+    /// Path of the manifest file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the manifest back to its directory
+    pub async fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(self)?).await?;
+        Ok(())
+    }
+
+    /// Check whether a local file is unchanged since it was recorded for the given URL
+    pub async fn is_unchanged(&self, filename: &str, url: &str, file_path: &Path) -> bool {
+        let Some(entry) = self.files.get(filename) else {
+            return false;
+        };
+        if entry.url != url || !file_path.exists() {
+            return false;
+        }
+        match sha256_file(file_path).await {
+            Ok(hash) => hash == entry.sha256,
+            Err(_) => false,
+        }
+    }
+
+    /// Record a freshly downloaded file
+    pub async fn record(&mut self, filename: &str, url: &str, file_path: &Path) -> Result<ManifestEntry> {
+        let sha256 = sha256_file(file_path).await?;
+        let size = fs::metadata(file_path).await?.len();
+        let entry = ManifestEntry {
+            filename: filename.to_string(),
+            url: url.to_string(),
+            size,
+            sha256,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+        self.files.insert(filename.to_string(), entry.clone());
+        Ok(entry)
+    }
+
+    /// Re-hash every recorded file and compare it with its recorded checksum
+    pub async fn verify(&self) -> Vec<FileCheck> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let mut checks = Vec::new();
+        for entry in self.files.values() {
+            let file_path = dir.join(&entry.filename);
+            let actual_sha256 = sha256_file(&file_path).await.ok();
+            let status = match &actual_sha256 {
+                Some(hash) if *hash == entry.sha256 => FileStatus::Ok,
+                Some(_) => FileStatus::Modified,
+                None => FileStatus::Missing,
+            };
+            checks.push(FileCheck { file_path, status, expected_sha256: entry.sha256.clone(), actual_sha256 });
+        }
+        checks
+    }
+}
+
+/// Manifest methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Re-hash a part's downloads against every manifest under `{root}/{product}/`
+    ///
+    /// `root` defaults to the download directory, matching where `cad`, `images` and
+    /// `cad-all` save files.
+    pub async fn verify_downloads(&self, product: &str, root: Option<&Path>) -> Result<Vec<FileCheck>> {
+        let dir = root.map(Path::to_path_buf).unwrap_or_else(|| self.download_root()).join(product);
+        let mut manifests = Vec::new();
+        find_manifests(&dir, &mut manifests)?;
+        if manifests.is_empty() {
+            return Err(ClientError::NotFound(format!("no download manifests for {} under {}", product, dir.display())));
+        }
+
+        let mut checks = Vec::new();
+        for path in manifests {
+            checks.extend(DownloadManifest::read(&path).await?.verify().await);
+        }
+        Ok(checks)
+    }
+}
+
+/// Collect manifest files in `dir` and its subdirectories, in path order
+fn find_manifests(dir: &Path, found: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries: Vec<PathBuf> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries.map(|entry| entry.map(|entry| entry.path())).collect::<std::io::Result<_>>()?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            find_manifests(&path, found)?;
+        } else if path.file_name().is_some_and(|name| name == MANIFEST_FILENAME) {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Compute the hex-encoded SHA-256 of a file
+pub async fn sha256_file(path: &Path) -> Result<String> {
+    let content = fs::read(path).await?;
+    Ok(hex::encode(Sha256::digest(&content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockApi;
+
+    #[tokio::test]
+    async fn test_verify_downloads() {
+        let api = MockApi::start().await;
+        let client = api.client().unwrap();
+
+        let root = api.data_dir().join("downloads");
+        let cad = root.join("91290A115").join("cad");
+        std::fs::create_dir_all(&cad).unwrap();
+        let mut manifest = DownloadManifest::load(&cad).await;
+        for name in ["91290A115.step", "91290A115.dxf", "91290A115.stl"] {
+            std::fs::write(cad.join(name), name).unwrap();
+            manifest.record(name, "/v1/cad", &cad.join(name)).await.unwrap();
+        }
+        manifest.save().await.unwrap();
+        std::fs::write(cad.join("91290A115.dxf"), "tampered").unwrap();
+        std::fs::remove_file(cad.join("91290A115.stl")).unwrap();
+
+        let checks = client.verify_downloads("91290A115", Some(&root)).await.unwrap();
+        let statuses: Vec<(String, FileStatus)> = checks.iter()
+            .map(|check| (check.file_path.file_name().unwrap().to_string_lossy().into_owned(), check.status))
+            .collect();
+        assert_eq!(statuses, vec![
+            ("91290A115.dxf".to_string(), FileStatus::Modified),
+            ("91290A115.step".to_string(), FileStatus::Ok),
+            ("91290A115.stl".to_string(), FileStatus::Missing),
+        ]);
+        assert!(matches!(client.verify_downloads("92141A008", Some(&root)).await, Err(ClientError::NotFound(_))));
+    }
+}
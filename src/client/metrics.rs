@@ -0,0 +1,155 @@
+//! Counters for API traffic, cache use and downloads during a run
+//!
+//! `mmc --metrics-file PATH` writes them in the Prometheus text format when the command
+//! finishes, for node_exporter's textfile collector or any scraper that reads the format.
+
+use reqwest::StatusCode;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Label values for `mmc_api_requests_total`; requests that got no response count as "error"
+const STATUS_CLASSES: [&str; 6] = ["1xx", "2xx", "3xx", "4xx", "5xx", "error"];
+
+/// Counters shared by every request a client makes; safe to update from concurrent tasks
+#[derive(Debug)]
+pub struct Metrics {
+    started: Instant,
+    requests: [AtomicU64; 6], // Indexed like STATUS_CLASSES
+    request_micros: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    downloaded_files: AtomicU64,
+    skipped_files: AtomicU64,
+    failed_files: AtomicU64,
+    downloaded_bytes: AtomicU64,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics {
+            started: Instant::now(),
+            requests: Default::default(),
+            request_micros: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+            downloaded_files: AtomicU64::new(0),
+            skipped_files: AtomicU64::new(0),
+            failed_files: AtomicU64::new(0),
+            downloaded_bytes: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Metrics {
+    /// Count an API request and how long it took; `None` when it failed without a response
+    pub(crate) fn record_request(&self, status: Option<StatusCode>, elapsed: Duration) {
+        let class = match status {
+            Some(status) => usize::from(status.as_u16() / 100).clamp(1, 5) - 1,
+            None => STATUS_CLASSES.len() - 1,
+        };
+        self.requests[class].fetch_add(1, Ordering::Relaxed);
+        self.request_micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Count a lookup answered without the network (`hit`) or one that had to fetch
+    pub(crate) fn record_cache(&self, hit: bool) {
+        let counter = if hit { &self.cache_hits } else { &self.cache_misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a finished download task
+    pub(crate) fn record_download(&self, skipped: bool, failed: bool, bytes: u64) {
+        let counter = match (skipped, failed) {
+            (true, _) => &self.skipped_files,
+            (false, true) => &self.failed_files,
+            (false, false) => &self.downloaded_files,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        if !skipped {
+            self.downloaded_bytes.fetch_add(bytes, Ordering::Relaxed);
+        }
+    }
+
+    /// Total API requests sent so far
+    pub fn request_count(&self) -> u64 {
+        self.requests.iter().map(|count| count.load(Ordering::Relaxed)).sum()
+    }
+
+    /// The counters in the Prometheus text exposition format
+    pub fn render(&self, succeeded: bool) -> String {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed).to_string();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let mut out = String::new();
+
+        let requests: Vec<(String, String)> = STATUS_CLASSES.iter().zip(&self.requests)
+            .map(|(class, count)| (format!("{{status=\"{}\"}}", class), load(count)))
+            .collect();
+        write_metric(&mut out, "mmc_api_requests_total", "counter", "API requests sent, by response status class", &requests);
+        write_metric(&mut out, "mmc_api_request_duration_seconds", "summary", "Time spent waiting for API responses", &[
+            ("_sum".to_string(), (self.request_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0).to_string()),
+            ("_count".to_string(), self.request_count().to_string()),
+        ]);
+        write_metric(&mut out, "mmc_cache_hits_total", "counter", "Product and price lookups answered without the network", &[
+            (String::new(), load(&self.cache_hits)),
+        ]);
+        write_metric(&mut out, "mmc_cache_misses_total", "counter", "Product and price lookups that had to fetch", &[
+            (String::new(), load(&self.cache_misses)),
+        ]);
+        write_metric(&mut out, "mmc_downloads_total", "counter", "Download tasks, by outcome", &[
+            ("{result=\"downloaded\"}".to_string(), load(&self.downloaded_files)),
+            ("{result=\"skipped\"}".to_string(), load(&self.skipped_files)),
+            ("{result=\"failed\"}".to_string(), load(&self.failed_files)),
+        ]);
+        write_metric(&mut out, "mmc_download_bytes_total", "counter", "Bytes downloaded", &[
+            (String::new(), load(&self.downloaded_bytes)),
+        ]);
+        write_metric(&mut out, "mmc_run_duration_seconds", "gauge", "How long the command ran", &[
+            (String::new(), self.started.elapsed().as_secs_f64().to_string()),
+        ]);
+        write_metric(&mut out, "mmc_run_success", "gauge", "1 if the command finished without an error, otherwise 0", &[
+            (String::new(), u8::from(succeeded).to_string()),
+        ]);
+        write_metric(&mut out, "mmc_last_run_timestamp_seconds", "gauge", "When the command finished, in Unix time", &[
+            (String::new(), now.to_string()),
+        ]);
+        out
+    }
+}
+
+/// Append one metric family; each sample is (name suffix or labels, value)
+fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(String, String)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for (suffix, value) in samples {
+        let _ = writeln!(out, "{}{} {}", name, suffix, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters() {
+        let metrics = Metrics::default();
+        metrics.record_request(Some(StatusCode::OK), Duration::from_millis(250));
+        metrics.record_request(Some(StatusCode::NOT_FOUND), Duration::from_millis(250));
+        metrics.record_request(None, Duration::ZERO);
+        metrics.record_cache(true);
+        metrics.record_download(false, false, 1024);
+        metrics.record_download(true, false, 2048);
+
+        let text = metrics.render(true);
+        assert!(text.contains("# TYPE mmc_api_requests_total counter\n"));
+        assert!(text.contains("mmc_api_requests_total{status=\"2xx\"} 1\n"));
+        assert!(text.contains("mmc_api_requests_total{status=\"4xx\"} 1\n"));
+        assert!(text.contains("mmc_api_requests_total{status=\"error\"} 1\n"));
+        assert!(text.contains("mmc_api_request_duration_seconds_sum 0.5\n"));
+        assert!(text.contains("mmc_api_request_duration_seconds_count 3\n"));
+        assert!(text.contains("mmc_cache_hits_total 1\n"));
+        assert!(text.contains("mmc_downloads_total{result=\"skipped\"} 1\n"));
+        assert!(text.contains("mmc_download_bytes_total 1024\n"));
+        assert!(text.contains("mmc_run_success 1\n"));
+    }
+}
@@ -4,9 +4,26 @@
 //! McMaster-Carr's Product Information API, including authentication,
 //! product management, and file downloads.
 
+pub mod alerts;
+pub mod alternates;
 pub mod api;
 pub mod auth;
+pub mod bom;
+pub mod cache;
+pub mod cancel;
+pub mod certificate;
+pub mod crosswalk;
+pub mod datasheet_index;
+pub mod datasheet_merge;
 pub mod downloads;
+pub mod journal;
+pub mod manifest;
+pub mod metrics;
+pub mod pinning;
+pub mod selftest;
+pub mod snapshot;
 pub mod subscriptions;
+pub mod team;
+pub mod transcript;
 
 pub use api::McmasterClient;
\ No newline at end of file
@@ -0,0 +1,324 @@
+//! Public key pinning for the API server's TLS certificate
+//!
+//! Pins are base64 SHA-256 hashes of a certificate's SubjectPublicKeyInfo, the same
+//! values HPKP and `curl --pinnedpubkey sha256//...` use, so an existing pin can be
+//! copied in as is. A pin can name the server's own key (a leaf pin), which changes
+//! whenever the certificate is replaced, or the key of a CA certificate the server sends
+//! with it (a chain pin, usually the issuing intermediate), which lasts as long as the
+//! server keeps buying certificates from that CA.
+//!
+//! The HTTP client only reports the server's own certificate, so when its key isn't
+//! pinned, the chain is fetched with a second handshake that stops as soon as the server
+//! has sent its certificates. The server's certificate is accepted once it verifies up to
+//! a pinned CA certificate from that chain; the result is remembered for the session.
+//! Get the pins for every certificate a server sends with:
+//!
+//! ```text
+//! openssl s_client -connect api.mcmaster.com:443 -showcerts </dev/null 2>/dev/null \
+//!   | awk '/BEGIN CERT/,/END CERT/' | csplit -s -z -f cert- - '/BEGIN CERT/' '{*}'
+//! for cert in cert-*; do openssl x509 -in "$cert" -pubkey -noout \
+//!   | openssl pkey -pubin -outform der | openssl dgst -sha256 -binary | base64; done
+//! ```
+
+use crate::config::settings::NetworkConfig;
+use crate::utils::error::{ClientError, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::tls::TlsInfo;
+use reqwest::{Response, Url};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::{ring, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// SHA-256 of a SubjectPublicKeyInfo
+pub type SpkiHash = [u8; 32];
+
+/// Parse a pin written as base64, optionally prefixed with `sha256//`
+pub fn parse_pin(pin: &str) -> Option<SpkiHash> {
+    let pin = pin.trim();
+    let encoded = pin.strip_prefix("sha256//").unwrap_or(pin);
+    STANDARD.decode(encoded).ok()?.try_into().ok()
+}
+
+/// Write a hash the way pins are written in config.toml
+pub fn format_pin(hash: &SpkiHash) -> String {
+    format!("sha256//{}", STANDARD.encode(hash))
+}
+
+/// Hash the public key of a DER certificate
+pub fn spki_hash(certificate: &[u8]) -> Result<SpkiHash> {
+    let (_, certificate) = X509Certificate::from_der(certificate)
+        .map_err(|e| ClientError::Certificate(format!("Could not parse server certificate: {}", e)))?;
+    Ok(Sha256::digest(certificate.public_key().raw).into())
+}
+
+/// The keys from `pinned_spki`, and the server certificates already found to chain to one
+#[derive(Debug, Default)]
+pub struct PinnedKeys {
+    pins: Vec<SpkiHash>,
+    proxy: Option<Url>, // Proxy the chain is fetched through, as for API requests
+    connect_timeout: Option<Duration>,
+    matched: Mutex<HashSet<[u8; 32]>>, // SHA-256 of server certificates verified up to a pinned CA key
+}
+
+impl PinnedKeys {
+    /// Pins from `[network]`; an empty list turns pinning off
+    pub fn new(pins: Vec<SpkiHash>, network: &NetworkConfig) -> Self {
+        let proxy = network.proxy.clone()
+            .or_else(|| ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"].iter().find_map(|name| std::env::var(name).ok()))
+            .and_then(|proxy| Url::parse(&proxy).ok());
+        PinnedKeys { pins, proxy, connect_timeout: network.connect_timeout(), matched: Mutex::default() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    /// Check that the server behind `response` presented a certificate with a pinned key,
+    /// or one that verifies up to a CA certificate with a pinned key
+    ///
+    /// Responses without TLS details (plain HTTP, such as a local mock server) aren't checked.
+    pub async fn check(&self, response: &Response) -> Result<()> {
+        if self.pins.is_empty() {
+            return Ok(());
+        }
+        let Some(info) = response.extensions().get::<TlsInfo>() else {
+            return Ok(());
+        };
+        let host = response.url().host_str().unwrap_or_default().to_string();
+        let certificate = info.peer_certificate()
+            .ok_or_else(|| ClientError::Certificate(format!("{} presented no certificate to check against pinned_spki", host)))?;
+        let hash = spki_hash(certificate)?;
+        if self.pins.contains(&hash) {
+            return Ok(());
+        }
+        let fingerprint: [u8; 32] = Sha256::digest(certificate).into();
+        if self.matched.lock().unwrap_or_else(PoisonError::into_inner).contains(&fingerprint) {
+            return Ok(());
+        }
+
+        // Not a leaf pin; look for a pinned CA key in the chain the server sends
+        let port = response.url().port_or_known_default().unwrap_or(443);
+        let fetch = presented_chain(&host, port, self.proxy.as_ref());
+        let chain = match self.connect_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, fetch).await
+                .map_err(|_| ClientError::Certificate(format!("Timed out fetching the certificate chain of {} to check pinned_spki", host)))??,
+            None => fetch.await?,
+        };
+        if verifies_to_pin(certificate, &chain, &host, &self.pins)? {
+            self.matched.lock().unwrap_or_else(PoisonError::into_inner).insert(fingerprint);
+            return Ok(());
+        }
+
+        let mut presented = vec![format_pin(&hash)];
+        for ca in chain.iter().skip(1) {
+            presented.push(format_pin(&spki_hash(ca)?));
+        }
+        Err(ClientError::Certificate(format!(
+            "The certificate chain for {} has public keys {}, none of which is in pinned_spki; refusing the connection",
+            host,
+            presented.join(", ")
+        )))
+    }
+}
+
+/// Whether `leaf` verifies for `host` up to a CA certificate in `chain` whose key is pinned
+///
+/// `chain` is as the server sent it, its own certificate first; the CA certificates between
+/// the leaf and the pinned one must all verify too.
+fn verifies_to_pin(leaf: &[u8], chain: &[CertificateDer<'static>], host: &str, pins: &[SpkiHash]) -> Result<bool> {
+    let leaf = CertificateDer::from(leaf.to_vec());
+    let server_name = ServerName::try_from(host.to_string())
+        .map_err(|e| ClientError::Certificate(format!("Invalid server name '{}': {}", host, e)))?;
+    let provider = Arc::new(ring::default_provider());
+    for (index, ca) in chain.iter().enumerate().skip(1) {
+        if !pins.contains(&spki_hash(ca)?) {
+            continue;
+        }
+        let mut roots = RootCertStore::empty();
+        if roots.add(ca.clone()).is_err() {
+            continue;
+        }
+        let verifier = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+            .build()
+            .map_err(|e| ClientError::Certificate(format!("Could not check the chain against pinned_spki: {}", e)))?;
+        if verifier.verify_server_cert(&leaf, &chain[1..index], &server_name, &[], UnixTime::now()).is_ok() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// The certificates a server sends in a TLS handshake, its own first
+///
+/// Nothing is trusted here: the handshake is abandoned once the certificates arrive, and
+/// the caller verifies them against the certificate the real connection presented.
+async fn presented_chain(host: &str, port: u16, proxy: Option<&Url>) -> Result<Vec<CertificateDer<'static>>> {
+    let stream = connect(host, port, proxy).await?;
+    let recorder = Arc::new(ChainRecorder::default());
+    let config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| ClientError::Certificate(format!("Could not set up TLS: {}", e)))?
+        .dangerous()
+        .with_custom_certificate_verifier(recorder.clone())
+        .with_no_client_auth();
+    let server_name = ServerName::try_from(host.to_string())
+        .map_err(|e| ClientError::Certificate(format!("Invalid server name '{}': {}", host, e)))?;
+    // Always fails, since the recorder stops the handshake
+    let _ = TlsConnector::from(Arc::new(config)).connect(server_name, stream).await;
+
+    let chain = std::mem::take(&mut *recorder.chain.lock().unwrap_or_else(PoisonError::into_inner));
+    if chain.is_empty() {
+        return Err(ClientError::Certificate(format!("{} sent no certificates to check against pinned_spki", host)));
+    }
+    Ok(chain)
+}
+
+/// Open a connection to `host:port`, through an HTTP proxy's CONNECT tunnel if one is set
+async fn connect(host: &str, port: u16, proxy: Option<&Url>) -> Result<TcpStream> {
+    let Some(proxy) = proxy.filter(|_| !no_proxy(host)) else {
+        return Ok(TcpStream::connect((host, port)).await?);
+    };
+    if proxy.scheme() != "http" {
+        return Err(ClientError::Config(format!(
+            "Checking pinned_spki against a certificate chain needs an http:// proxy, not '{}'; pin the server's own key instead",
+            proxy.scheme()
+        )));
+    }
+    let proxy_host = proxy.host_str().unwrap_or_default();
+    let mut stream = TcpStream::connect((proxy_host, proxy.port_or_known_default().unwrap_or(80))).await?;
+
+    let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+    if !proxy.username().is_empty() {
+        let decode = |value: &str| urlencoding::decode(value).map(|value| value.into_owned()).unwrap_or_else(|_| value.to_string());
+        let credentials = format!("{}:{}", decode(proxy.username()), decode(proxy.password().unwrap_or_default()));
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", STANDARD.encode(credentials)));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read the proxy's reply up to the blank line; the tunnel starts right after it
+    let mut reply = Vec::new();
+    while !reply.ends_with(b"\r\n\r\n") && reply.len() < 8192 {
+        let byte = stream.read_u8().await?;
+        reply.push(byte);
+    }
+    let status = String::from_utf8_lossy(&reply).lines().next().unwrap_or_default().to_string();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(ClientError::Certificate(format!("Proxy {} refused a tunnel to {} for checking pinned_spki: {}", proxy_host, host, status)));
+    }
+    Ok(stream)
+}
+
+/// Whether `NO_PROXY` says to reach `host` directly
+fn no_proxy(host: &str) -> bool {
+    let list = std::env::var("NO_PROXY").or_else(|_| std::env::var("no_proxy")).unwrap_or_default();
+    list.split(',').map(str::trim).filter(|entry| !entry.is_empty()).any(|entry| {
+        let domain = entry.trim_start_matches('.');
+        entry == "*" || host == domain || host.ends_with(&format!(".{}", domain))
+    })
+}
+
+/// Records the certificates a server sends and then stops the handshake
+#[derive(Debug, Default)]
+struct ChainRecorder {
+    chain: Mutex<Vec<CertificateDer<'static>>>,
+}
+
+impl ServerCertVerifier for ChainRecorder {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        let chain = std::iter::once(end_entity).chain(intermediates).map(|certificate| certificate.clone().into_owned()).collect();
+        *self.chain.lock().unwrap_or_else(PoisonError::into_inner) = chain;
+        Err(rustls::Error::General("certificate chain recorded".to_string()))
+    }
+
+    fn verify_tls12_signature(&self, _: &[u8], _: &CertificateDer<'_>, _: &DigitallySignedStruct) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        Err(rustls::Error::General("certificate chain recorded".to_string()))
+    }
+
+    fn verify_tls13_signature(&self, _: &[u8], _: &CertificateDer<'_>, _: &DigitallySignedStruct) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        Err(rustls::Error::General("certificate chain recorded".to_string()))
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        let provider: CryptoProvider = ring::default_provider();
+        provider.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pin() {
+        let hash = [7u8; 32];
+        let pin = format_pin(&hash);
+        assert!(pin.starts_with("sha256//"));
+        assert_eq!(parse_pin(&pin), Some(hash));
+        assert_eq!(parse_pin(pin.trim_start_matches("sha256//")), Some(hash));
+        assert_eq!(parse_pin("sha256//c2hvcnQ="), None);
+        assert_eq!(parse_pin("not base64!"), None);
+    }
+
+    /// DER certificates from a PEM fixture in `testdata/pinning`
+    fn certificates(pem: &str) -> Vec<CertificateDer<'static>> {
+        use rustls::pki_types::pem::PemObject;
+        CertificateDer::pem_slice_iter(pem.as_bytes()).collect::<std::result::Result<_, _>>().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_chain_pins() {
+        use rustls::pki_types::pem::PemObject;
+        use rustls::pki_types::PrivateKeyDer;
+
+        // A server for localhost sending its certificate and the intermediate that issued it
+        let leaf = certificates(include_str!("testdata/pinning/leaf.pem")).remove(0);
+        let intermediate = certificates(include_str!("testdata/pinning/intermediate.pem")).remove(0);
+        let root = certificates(include_str!("testdata/pinning/root.pem")).remove(0);
+        let key = PrivateKeyDer::from_pem_slice(include_bytes!("testdata/pinning/leaf.key")).unwrap();
+        let config = rustls::ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(vec![leaf.clone(), intermediate.clone()], key)
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let _ = acceptor.accept(stream).await;
+            }
+        });
+
+        let chain = presented_chain("127.0.0.1", port, None).await.unwrap();
+        assert_eq!(chain, vec![leaf.clone(), intermediate.clone()]);
+
+        let pin = |certificate: &CertificateDer| spki_hash(certificate).unwrap();
+        assert!(verifies_to_pin(&leaf, &chain, "localhost", &[pin(&intermediate)]).unwrap());
+        assert!(!verifies_to_pin(&leaf, &chain, "example.com", &[pin(&intermediate)]).unwrap());
+        // The root isn't sent, and the leaf's own key is matched before the chain is fetched
+        assert!(!verifies_to_pin(&leaf, &chain, "localhost", &[pin(&root)]).unwrap());
+        assert!(!verifies_to_pin(&leaf, &chain, "localhost", &[pin(&leaf)]).unwrap());
+        // A pinned certificate that didn't issue the leaf proves nothing
+        assert!(!verifies_to_pin(&intermediate, &[root.clone(), intermediate.clone()], "localhost", &[pin(&intermediate)]).unwrap());
+    }
+}
@@ -0,0 +1,134 @@
+//! End-to-end smoke test against the live API
+
+use indicatif::ProgressBar;
+use serde::Serialize;
+use tokio::fs;
+
+use crate::utils::error::{ClientError, Result};
+
+/// Outcome of a single self-test step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Passed,
+    Failed,
+    Skipped, // An earlier step failed, or there was nothing to test
+}
+
+/// One check performed by the self-test
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestStep {
+    pub name: &'static str, // "login", "info", "price", "links" or "download"
+    pub status: StepStatus,
+    pub detail: String,
+}
+
+/// Results of running the self-test against one part
+#[derive(Debug, Default, Serialize)]
+pub struct SelfTestReport {
+    pub part: String,
+    pub steps: Vec<SelfTestStep>,
+}
+
+impl SelfTestReport {
+    /// Whether no step failed
+    pub fn passed(&self) -> bool {
+        self.steps.iter().all(|step| step.status != StepStatus::Failed)
+    }
+
+    fn record(&mut self, name: &'static str, outcome: Result<String>) -> bool {
+        let (status, detail) = match outcome {
+            Ok(detail) => (StepStatus::Passed, detail),
+            Err(e) => (StepStatus::Failed, e.to_string()),
+        };
+        self.steps.push(SelfTestStep { name, status, detail });
+        status == StepStatus::Passed
+    }
+
+    fn skip(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.steps.push(SelfTestStep {
+            name,
+            status: StepStatus::Skipped,
+            detail: detail.into(),
+        });
+    }
+}
+
+/// Self-test methods for McmasterClient
+impl super::api::McmasterClient {
+    /// Exercise login, product info, pricing, links and a small download for one part
+    ///
+    /// Every step is recorded in the report rather than returned as an error, so a
+    /// single run shows exactly which part of the account setup is broken.
+    pub async fn selftest(&mut self, part: &str) -> SelfTestReport {
+        let mut report = SelfTestReport {
+            part: part.to_string(),
+            ..Default::default()
+        };
+
+        let login = if self.credentials.is_some() {
+            self.login_with_stored_credentials().await
+                .map(|_| "Logged in with stored credentials".to_string())
+        } else if self.current_session().is_some() {
+            Ok("Using saved token (no credentials file to log in with)".to_string())
+        } else {
+            Err(ClientError::NotAuthenticated)
+        };
+        if !report.record("login", login) {
+            for name in ["info", "price", "links", "download"] {
+                report.skip(name, "Login failed");
+            }
+            return report;
+        }
+
+        let info = self.get_product(part).await
+            .map(|product| product.detail_description);
+        report.record("info", info);
+
+        let price = self.get_price(part).await
+            .map(|prices| format!("{} price break(s)", prices.len()));
+        report.record("price", price);
+
+        let links = match self.require_token().await {
+            Ok(token) => self.get_product_links(part, &token).await,
+            Err(e) => Err(e),
+        };
+        let links = match links {
+            Ok(links) => links,
+            Err(e) => {
+                report.record("links", Err(e));
+                report.skip("download", "No links to download from");
+                return report;
+            }
+        };
+        report.record("links", Ok(format!(
+            "{} image(s), {} CAD file(s), {} datasheet(s)",
+            links.images.len(), links.cad.len(), links.datasheets.len()
+        )));
+
+        // Images are usually the smallest asset, so prefer them for the download check
+        let url = links.images.first()
+            .or(links.datasheets.first())
+            .or(links.cad.first().map(|cad| &cad.url));
+        match url {
+            Some(url) => {
+                let download = self.selftest_download(url).await
+                    .map(|bytes| format!("Downloaded {} bytes", bytes));
+                report.record("download", download);
+            }
+            None => report.skip("download", "Part has no downloadable files"),
+        }
+
+        report
+    }
+
+    /// Download a file into a scratch directory that is removed afterwards
+    async fn selftest_download(&self, url: &str) -> Result<u64> {
+        let dir = std::env::temp_dir().join(format!("mmc-selftest-{}", std::process::id()));
+        fs::create_dir_all(&dir).await?;
+
+        let result = self.download_asset(url, &dir.join("selftest.download"), &ProgressBar::hidden()).await;
+        let _ = fs::remove_dir_all(&dir).await;
+        result
+    }
+}
@@ -0,0 +1,243 @@
+//! Dated archives of the cached product data, for `mmc snapshot`
+//!
+//! A snapshot is a zip holding `snapshot.json`: every cached product's details and price
+//! breaks at the time it was taken. Snapshots are kept in `snapshots/` next to the
+//! subscription database, since the cache may be cleared at any time, and comparing two
+//! of them shows which parts were added, removed or changed in between.
+
+use crate::models::product::{PriceInfo, ProductDetail, ProductDiff};
+use crate::utils::error::{ClientError, Result};
+use crate::utils::lock::write_atomic;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Name of the data file inside a snapshot archive
+const SNAPSHOT_ENTRY: &str = "snapshot.json";
+
+/// Every cached product at one point in time
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub taken_at: DateTime<Utc>,
+    pub parts: Vec<SnapshotPart>, // Sorted by part number
+}
+
+/// One product in a snapshot
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotPart {
+    pub detail: ProductDetail,
+    pub fetched_at: DateTime<Utc>, // When the details were cached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prices: Option<Vec<PriceInfo>>, // None when prices were never cached
+}
+
+/// What changed between two snapshots
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotDiff {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<PartChange>,
+}
+
+/// A part in both snapshots whose data differs
+#[derive(Debug, Clone, Serialize)]
+pub struct PartChange {
+    pub part_number: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// One differing field; empty values mean the field wasn't there
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    pub field: String, // "description", "status", a specification attribute, or "price @ {quantity}"
+    pub before: String,
+    pub after: String,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Snapshot {
+    /// Write the snapshot as a deflated zip
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .unix_permissions(0o644);
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(SNAPSHOT_ENTRY, options).map_err(|e| zip_error(path, e))?;
+        zip.write_all(&serde_json::to_vec_pretty(self)?)?;
+        let archive = zip.finish().map_err(|e| zip_error(path, e))?.into_inner();
+        write_atomic(path, &archive)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`Snapshot::write`]
+    pub fn read(path: &Path) -> Result<Self> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(ClientError::NotFound(format!("No snapshot at '{}'", path.display())));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut zip = zip::ZipArchive::new(file).map_err(|e| zip_error(path, e))?;
+        let mut json = String::new();
+        zip.by_name(SNAPSHOT_ENTRY).map_err(|e| zip_error(path, e))?.read_to_string(&mut json)?;
+        serde_json::from_str(&json)
+            .map_err(|e| ClientError::Parse(format!("Invalid snapshot '{}': {}", path.display(), e)))
+    }
+
+    pub fn part(&self, part_number: &str) -> Option<&SnapshotPart> {
+        self.parts.iter().find(|part| part.detail.part_number.eq_ignore_ascii_case(part_number))
+    }
+
+    /// Compare against a later snapshot
+    pub fn diff(&self, later: &Snapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff { from: self.taken_at, to: later.taken_at, added: Vec::new(), removed: Vec::new(), changed: Vec::new() };
+        for before in &self.parts {
+            match later.part(&before.detail.part_number) {
+                Some(after) => {
+                    let changes = field_changes(before, after);
+                    if !changes.is_empty() {
+                        diff.changed.push(PartChange { part_number: after.detail.part_number.clone(), changes });
+                    }
+                }
+                None => diff.removed.push(before.detail.part_number.clone()),
+            }
+        }
+        for after in &later.parts {
+            if self.part(&after.detail.part_number).is_none() {
+                diff.added.push(after.detail.part_number.clone());
+            }
+        }
+        diff
+    }
+}
+
+/// Description, status, specification and price differences between two versions of a part
+fn field_changes(before: &SnapshotPart, after: &SnapshotPart) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    let mut compare = |field: &str, before: String, after: String| {
+        if before != after {
+            changes.push(FieldChange { field: field.to_string(), before, after });
+        }
+    };
+
+    let (old, new) = (&before.detail, &after.detail);
+    compare("description", old.detail_description.clone(), new.detail_description.clone());
+    compare("family", old.family_description.clone(), new.family_description.clone());
+    compare("category", old.product_category.clone(), new.product_category.clone());
+    compare("status", old.product_status.clone(), new.product_status.clone());
+    for spec in ProductDiff::new(old, new).differences() {
+        compare(&spec.attribute, spec.first.join(", "), spec.second.join(", "));
+    }
+
+    // Price breaks are matched by minimum quantity
+    let (old_breaks, new_breaks) = (before.prices.as_deref().unwrap_or_default(), after.prices.as_deref().unwrap_or_default());
+    let mut quantities: Vec<f64> = old_breaks.iter().chain(new_breaks).map(|price| price.minimum_quantity).collect();
+    quantities.sort_by(f64::total_cmp);
+    quantities.dedup();
+    let price_at = |prices: &[PriceInfo], quantity: f64| {
+        prices.iter()
+            .find(|price| price.minimum_quantity == quantity)
+            .map(|price| format!("{} {} per {}", price.amount, price.currency(), price.unit_of_measure.to_lowercase()))
+            .unwrap_or_default()
+    };
+    for quantity in quantities {
+        compare(&format!("price @ {}", quantity), price_at(old_breaks, quantity), price_at(new_breaks, quantity));
+    }
+    changes
+}
+
+fn zip_error(path: &Path, error: zip::result::ZipError) -> ClientError {
+    ClientError::Parse(format!("Invalid snapshot archive '{}': {}", path.display(), error))
+}
+
+/// Snapshot methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Directory snapshots are written to by default
+    pub fn snapshot_dir(&self) -> PathBuf {
+        self.subscriptions().get_file_path().with_file_name("snapshots")
+    }
+
+    /// Archive every cached product and price, returning the snapshot and where it was written
+    ///
+    /// Without `output`, the archive is `mmc-snapshot-{date}.zip` in [`Self::snapshot_dir`],
+    /// numbered `-2`, `-3`, ... when one was already taken that day.
+    pub fn take_snapshot(&self, output: Option<&Path>) -> Result<(Snapshot, PathBuf)> {
+        let cache = self.cache();
+        let mut parts = Vec::new();
+        for cached in cache.all_products()? {
+            let prices = cache.get_prices(&cached.detail.part_number)?;
+            parts.push(SnapshotPart { detail: cached.detail, fetched_at: cached.fetched_at, prices });
+        }
+        drop(cache);
+        let snapshot = Snapshot { taken_at: Utc::now(), parts };
+
+        let path = match output {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let dir = self.snapshot_dir();
+                std::fs::create_dir_all(&dir)?;
+                let stem = format!("mmc-snapshot-{}", snapshot.taken_at.format("%Y-%m-%d"));
+                let mut path = dir.join(format!("{}.zip", stem));
+                let mut number = 2;
+                while path.exists() {
+                    path = dir.join(format!("{}-{}.zip", stem, number));
+                    number += 1;
+                }
+                path
+            }
+        };
+        snapshot.write(&path)?;
+        Ok((snapshot, path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockApi, PART_NUMBER};
+
+    #[tokio::test]
+    async fn test_snapshot_diff() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        client.get_product(PART_NUMBER).await.unwrap();
+        client.get_price(PART_NUMBER).await.unwrap();
+
+        let (first, path) = client.take_snapshot(None).unwrap();
+        assert!(path.file_name().unwrap().to_str().unwrap().starts_with("mmc-snapshot-"));
+        let (_, second_path) = client.take_snapshot(None).unwrap();
+        assert!(second_path.to_str().unwrap().ends_with("-2.zip"));
+
+        let read = Snapshot::read(&path).unwrap();
+        assert_eq!(read.parts.len(), 1);
+        assert!(first.diff(&read).is_empty());
+
+        // A later snapshot where the part's thread and price changed and another appeared
+        let mut later = Snapshot::read(&path).unwrap();
+        let part = &mut later.parts[0];
+        part.detail.specifications.iter_mut().find(|spec| spec.attribute == "Thread Size").unwrap().values = vec!["M4".to_string()];
+        part.prices.as_mut().unwrap()[0].amount = 10.0;
+        let mut added = Snapshot::read(&path).unwrap().parts.remove(0);
+        added.detail.part_number = "90591A111".to_string();
+        later.parts.push(added);
+
+        let diff = first.diff(&later);
+        assert_eq!((diff.added, diff.removed), (vec!["90591A111".to_string()], Vec::<String>::new()));
+        let changes = &diff.changed[0].changes;
+        assert_eq!(changes[0], FieldChange { field: "Thread Size".to_string(), before: "M3".to_string(), after: "M4".to_string() });
+        assert_eq!((changes[1].field.as_str(), changes[1].after.as_str()), ("price @ 1", "10 USD per pack"));
+        assert!(matches!(Snapshot::read(&api.data_dir().join("missing.zip")), Err(ClientError::NotFound(_))));
+    }
+}
@@ -1,185 +1,703 @@
 //! Subscription tracking and management
 
-use anyhow::Result;
-use std::collections::HashSet;
+use crate::utils::error::Result;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, BufWriter, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use crate::config::paths::{expand_path, get_subscriptions_path};
+use crate::client::alerts::{AlertDirection, AlertRule};
+use crate::config::paths::{expand_path, get_legacy_subscriptions_path, get_subscriptions_path};
 use crate::models::auth::Credentials;
+use crate::utils::lock::FileLock;
+
+/// A locally tracked part with its tags, note and custom metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackedPart {
+    pub part_number: String,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub metadata: BTreeMap<String, String>,
+    pub added_at: Option<String>, // RFC 3339 time the part started being tracked locally
+    pub last_synced_at: Option<String>, // RFC 3339 time `mmc sync` last verified the part
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_number: Option<String>, // Internal ERP/PLM item number, from the crosswalk
+}
+
+/// User-supplied information attached to a tracked part
+#[derive(Debug, Clone, Default)]
+struct Annotations {
+    tags: BTreeSet<String>,
+    note: Option<String>,
+    metadata: BTreeMap<String, String>,
+    item_number: Option<String>,
+}
 
-/// Manager for local subscription tracking
+/// Schema changes, applied in order; `PRAGMA user_version` records how many have run
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE parts (
+        part_number TEXT PRIMARY KEY,
+        note TEXT,
+        added_at TEXT,
+        last_synced_at TEXT
+    );
+    CREATE TABLE tags (
+        part_number TEXT NOT NULL REFERENCES parts(part_number) ON DELETE CASCADE,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (part_number, tag)
+    );
+    CREATE TABLE metadata (
+        part_number TEXT NOT NULL REFERENCES parts(part_number) ON DELETE CASCADE,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (part_number, key)
+    );",
+    // Item numbers outlive tracking, so untracking a part keeps its mapping
+    "CREATE TABLE crosswalk (
+        part_number TEXT PRIMARY KEY,
+        item_number TEXT NOT NULL
+    );",
+    "CREATE TABLE alerts (
+        part_number TEXT NOT NULL,
+        direction TEXT NOT NULL,
+        threshold REAL NOT NULL,
+        last_price REAL,
+        PRIMARY KEY (part_number, direction)
+    );",
+];
+
+/// Manager for local subscription tracking, backed by an SQLite database
+///
+/// Every change runs in its own transaction, so concurrent `mmc` processes can share
+/// the database. Import and export use the original flat-file format: one part per
+/// line, optionally followed by tab-separated columns for comma-separated tags, a note,
+/// `key=value` metadata joined by `;`, and the internal item number
+/// (e.g. `91290A115\trobot-v2,fasteners\tuse for base plate\tbin=A3;qty=40\tHW-00412`).
+/// Tabs, newlines, backslashes and metadata separators are backslash-escaped.
 pub struct SubscriptionManager {
     file_path: PathBuf,
-    parts: HashSet<String>, // In-memory cache for O(1) lookups and automatic deduplication
+    conn: Connection,
 }
 
 impl SubscriptionManager {
     /// Create a new subscription manager with the given credentials
+    ///
+    /// A configured `.txt` path is a subscription file from before the database; the
+    /// database is kept beside it and the file's parts are imported on first use.
     pub fn new(credentials: &Option<Credentials>) -> Result<Self> {
-        let file_path = if let Some(creds) = credentials {
-            if let Some(ref path) = creds.subscriptions_file {
-                expand_path(path)
-            } else {
-                // Fall back to default location if not specified
-                get_subscriptions_path()
-            }
-        } else {
-            // No credentials provided, use default location
-            get_subscriptions_path()
-        };
-
-        let mut manager = SubscriptionManager {
-            file_path,
-            parts: HashSet::new(),
-        };
-
-        // Load existing subscriptions from file
-        manager.load_from_file()?;
+        let (file_path, legacy_path) = Self::paths(credentials);
+        Self::open(file_path, legacy_path)
+    }
 
-        Ok(manager)
+    /// The database path and the legacy flat file to import from, for the given credentials
+    fn paths(credentials: &Option<Credentials>) -> (PathBuf, Option<PathBuf>) {
+        let configured = credentials.as_ref()
+            .and_then(|creds| creds.subscriptions_file.as_deref())
+            .map(expand_path);
+
+        match configured {
+            Some(path) if path.extension().is_some_and(|ext| ext == "txt") => (path.with_extension("db"), Some(path)),
+            Some(path) => (path, None),
+            // Fall back to default location if not specified
+            None => (get_subscriptions_path(), Some(get_legacy_subscriptions_path())),
+        }
     }
 
-    /// Load subscriptions from file into HashSet (automatically deduplicates)
-    fn load_from_file(&mut self) -> Result<()> {
+    /// Open (creating if needed) the database, migrating a legacy flat file into a new one
+    pub(crate) fn open(file_path: PathBuf, legacy_path: Option<PathBuf>) -> Result<Self> {
         // Create parent directory if it doesn't exist
-        if let Some(parent) = self.file_path.parent() {
+        if let Some(parent) = file_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        // If file doesn't exist, start with empty set
-        if !self.file_path.exists() {
+        // Only one process creates, migrates or imports into the database at a time
+        let _lock = FileLock::exclusive(&file_path)?;
+
+        let is_new = !file_path.exists();
+        let conn = Connection::open(&file_path)?;
+        conn.busy_timeout(Duration::from_secs(5))?; // Wait for other mmc processes instead of failing
+        conn.pragma_update(None, "journal_mode", "WAL")?; // Readers don't block the writer
+        conn.pragma_update(None, "foreign_keys", true)?;
+
+        let mut manager = SubscriptionManager { file_path, conn };
+        manager.migrate()?;
+
+        if let Some(legacy_path) = legacy_path.filter(|path| is_new && path.exists()) {
+            manager.import_from_path(&legacy_path)?;
+        }
+
+        Ok(manager)
+    }
+
+    /// Bring the schema up to date
+    fn migrate(&mut self) -> Result<()> {
+        let version: usize = self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version >= MIGRATIONS.len() {
             return Ok(());
         }
 
-        let file = File::open(&self.file_path)?;
-        let reader = BufReader::new(file);
+        let tx = self.conn.transaction()?;
+        for migration in &MIGRATIONS[version..] {
+            tx.execute_batch(migration)?;
+        }
+        tx.pragma_update(None, "user_version", MIGRATIONS.len())?;
+        tx.commit()?;
+        Ok(())
+    }
 
-        for line in reader.lines() {
-            let line = line?.trim().to_string();
-            if !line.is_empty() && !line.starts_with('#') {
-                // Remove any whitespace and convert to uppercase for consistency
-                let part_number = line.trim().to_uppercase();
-                self.parts.insert(part_number);
-            }
+    /// Add part to subscription tracking; returns whether it was new
+    pub fn add_part(&mut self, part_number: &str) -> Result<bool> {
+        let normalized_part = normalize_part(part_number);
+        Ok(insert_part(&self.conn, &normalized_part)?)
+    }
+
+    /// Remove part (and its tags, note and metadata) from subscription tracking
+    pub fn remove_part(&mut self, part_number: &str) -> Result<bool> {
+        let normalized_part = normalize_part(part_number);
+        let removed = self.conn.execute("DELETE FROM parts WHERE part_number = ?1", [&normalized_part])?;
+        Ok(removed > 0)
+    }
+
+    /// Check if part is tracked
+    pub fn has_part(&self, part_number: &str) -> Result<bool> {
+        let normalized_part = normalize_part(part_number);
+        Ok(self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM parts WHERE part_number = ?1)",
+            [&normalized_part],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Get all subscribed parts (sorted)
+    pub fn get_all_parts(&self) -> Result<Vec<String>> {
+        let mut statement = self.conn.prepare("SELECT part_number FROM parts ORDER BY part_number")?;
+        let parts = statement.query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(parts)
+    }
+
+    /// Attach tags to a part, tracking the part if needed; returns the part's tags
+    pub fn add_tags(&mut self, part_number: &str, tags: &[String]) -> Result<Vec<String>> {
+        let normalized_part = normalize_part(part_number);
+        let tx = self.conn.transaction()?;
+        insert_part(&tx, &normalized_part)?;
+        for tag in tags.iter().filter_map(|tag| normalize_tag(tag)) {
+            tx.execute("INSERT OR IGNORE INTO tags (part_number, tag) VALUES (?1, ?2)", params![normalized_part, tag])?;
+        }
+        tx.commit()?;
+        self.get_tags(&normalized_part)
+    }
+
+    /// Detach tags from a part; returns the part's remaining tags
+    pub fn remove_tags(&mut self, part_number: &str, tags: &[String]) -> Result<Vec<String>> {
+        let normalized_part = normalize_part(part_number);
+        let tx = self.conn.transaction()?;
+        for tag in tags.iter().filter_map(|tag| normalize_tag(tag)) {
+            tx.execute("DELETE FROM tags WHERE part_number = ?1 AND tag = ?2", params![normalized_part, tag])?;
         }
+        tx.commit()?;
+        self.get_tags(&normalized_part)
+    }
+
+    /// Get a part's tags (sorted)
+    pub fn get_tags(&self, part_number: &str) -> Result<Vec<String>> {
+        let normalized_part = normalize_part(part_number);
+        let mut statement = self.conn.prepare("SELECT tag FROM tags WHERE part_number = ?1 ORDER BY tag")?;
+        let tags = statement.query_map([&normalized_part], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(tags)
+    }
 
+    /// Set or clear a part's note, tracking the part if needed
+    pub fn set_note(&mut self, part_number: &str, note: Option<&str>) -> Result<()> {
+        let normalized_part = normalize_part(part_number);
+        let note = note.map(str::trim).filter(|note| !note.is_empty());
+        let tx = self.conn.transaction()?;
+        insert_part(&tx, &normalized_part)?;
+        tx.execute("UPDATE parts SET note = ?2 WHERE part_number = ?1", params![normalized_part, note])?;
+        tx.commit()?;
         Ok(())
     }
 
-    /// Save all parts to file (automatically deduplicated)
-    fn save_to_file(&self) -> Result<()> {
-        // Create parent directory if it doesn't exist
-        if let Some(parent) = self.file_path.parent() {
-            fs::create_dir_all(parent)?;
+    /// Set (or with `None`, remove) a custom metadata value on a part, tracking the part if needed
+    pub fn set_metadata(&mut self, part_number: &str, key: &str, value: Option<&str>) -> Result<()> {
+        let normalized_part = normalize_part(part_number);
+        let key = key.trim();
+        let tx = self.conn.transaction()?;
+        insert_part(&tx, &normalized_part)?;
+        match value {
+            Some(value) => tx.execute(
+                "INSERT INTO metadata (part_number, key, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (part_number, key) DO UPDATE SET value = excluded.value",
+                params![normalized_part, key, value.trim()],
+            )?,
+            None => tx.execute("DELETE FROM metadata WHERE part_number = ?1 AND key = ?2", params![normalized_part, key])?,
+        };
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The internal item number mapped to a part, if any
+    pub fn get_item_number(&self, part_number: &str) -> Result<Option<String>> {
+        let item_number = self.conn.query_row(
+            "SELECT item_number FROM crosswalk WHERE part_number = ?1",
+            params![normalize_part(part_number)],
+            |row| row.get(0),
+        );
+        match item_number {
+            Ok(item_number) => Ok(Some(item_number)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
         }
+    }
 
-        let file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(&self.file_path)?;
+    /// Map a part to an internal item number, or with `None` remove its mapping; returns whether it changed
+    pub fn set_item_number(&mut self, part_number: &str, item_number: Option<&str>) -> Result<bool> {
+        let normalized_part = normalize_part(part_number);
+        let changed = match item_number.map(str::trim).filter(|item| !item.is_empty()) {
+            Some(item_number) => self.conn.execute(
+                "INSERT INTO crosswalk (part_number, item_number) VALUES (?1, ?2)
+                 ON CONFLICT (part_number) DO UPDATE SET item_number = excluded.item_number
+                 WHERE item_number != excluded.item_number",
+                params![normalized_part, item_number],
+            )?,
+            None => self.conn.execute("DELETE FROM crosswalk WHERE part_number = ?1", params![normalized_part])?,
+        };
+        Ok(changed > 0)
+    }
 
-        let mut writer = BufWriter::new(file);
+    /// Every (part number, item number) mapping, sorted by part number
+    pub fn get_crosswalk(&self) -> Result<Vec<(String, String)>> {
+        let mut statement = self.conn.prepare("SELECT part_number, item_number FROM crosswalk ORDER BY part_number")?;
+        let mappings = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(mappings)
+    }
 
-        // Write header comment
-        writeln!(
-            writer,
-            "# McMaster-Carr Subscribed Parts\n# Auto-managed by mmcli - do not edit manually\n"
+    /// Add or replace the alert on one side of a part's price, forgetting the last checked price
+    pub fn set_alert(&mut self, part_number: &str, direction: AlertDirection, threshold: f64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO alerts (part_number, direction, threshold) VALUES (?1, ?2, ?3)
+             ON CONFLICT (part_number, direction) DO UPDATE SET threshold = excluded.threshold, last_price = NULL",
+            params![normalize_part(part_number), direction.as_str(), threshold],
         )?;
+        Ok(())
+    }
 
-        // Write sorted part numbers (one per line)
-        let mut sorted_parts: Vec<_> = self.parts.iter().collect();
-        sorted_parts.sort();
+    /// Remove every alert on a part; returns how many were removed
+    pub fn remove_alerts(&mut self, part_number: &str) -> Result<usize> {
+        Ok(self.conn.execute("DELETE FROM alerts WHERE part_number = ?1", params![normalize_part(part_number)])?)
+    }
 
-        for part in sorted_parts {
-            writeln!(writer, "{}", part)?;
+    /// Every alert rule, sorted by part number
+    pub fn get_alerts(&self) -> Result<Vec<AlertRule>> {
+        let mut statement = self.conn.prepare(
+            "SELECT part_number, direction, threshold, last_price FROM alerts ORDER BY part_number, direction",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get(2)?, row.get(3)?))
+        })?;
+        let mut alerts = Vec::new();
+        for row in rows {
+            let (part_number, direction, threshold, last_price) = row?;
+            // Skip directions written by a newer version
+            if let Some(direction) = AlertDirection::parse(&direction) {
+                alerts.push(AlertRule { part_number, direction, threshold, last_price });
+            }
         }
+        Ok(alerts)
+    }
 
-        writer.flush()?;
+    /// Remember the price an alert was last checked against
+    pub fn record_alert_price(&mut self, part_number: &str, direction: AlertDirection, price: f64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE alerts SET last_price = ?3 WHERE part_number = ?1 AND direction = ?2",
+            params![normalize_part(part_number), direction.as_str(), price],
+        )?;
         Ok(())
     }
 
-    /// Add part to subscription tracking (only writes if new)
-    pub fn add_part(&mut self, part_number: &str) -> Result<bool> {
-        let normalized_part = part_number.trim().to_uppercase();
-        
-        // Only add and save if it's actually new
-        if self.parts.insert(normalized_part) {
-            self.save_to_file()?; // Only write if part was newly added
-            Ok(true) // Part was new
-        } else {
-            Ok(false) // Part already existed, no file write needed
+    /// Record that parts were verified against the API just now
+    pub fn mark_synced(&mut self, part_numbers: &[String]) -> Result<()> {
+        let now = chrono::Utc::now().to_rfc3339();
+        let tx = self.conn.transaction()?;
+        for part in part_numbers {
+            tx.execute("UPDATE parts SET last_synced_at = ?2 WHERE part_number = ?1", params![normalize_part(part), now])?;
         }
+        tx.commit()?;
+        Ok(())
     }
 
-    /// Remove part from subscription tracking
-    pub fn remove_part(&mut self, part_number: &str) -> Result<bool> {
-        let normalized_part = part_number.trim().to_uppercase();
-        
-        if self.parts.remove(&normalized_part) {
-            self.save_to_file()?;
-            Ok(true) // Part was removed
-        } else {
-            Ok(false) // Part wasn't in the set
-        }
+    /// Get a tracked part with its annotations
+    pub fn get_tracked_part(&self, part_number: &str) -> Result<Option<TrackedPart>> {
+        let normalized_part = normalize_part(part_number);
+        Ok(self.query_tracked_parts(Some(&normalized_part), None)?.pop())
     }
 
-    /// Check if part exists in local cache
-    pub fn has_part(&self, part_number: &str) -> bool {
-        let normalized_part = part_number.trim().to_uppercase();
-        self.parts.contains(&normalized_part)
+    /// Get tracked parts with their annotations, optionally limited to parts with a given tag (sorted)
+    pub fn get_tracked_parts(&self, tag: Option<&str>) -> Result<Vec<TrackedPart>> {
+        match tag {
+            Some(tag) => match normalize_tag(tag) {
+                Some(tag) => self.query_tracked_parts(None, Some(&tag)),
+                None => Ok(Vec::new()),
+            },
+            None => self.query_tracked_parts(None, None),
+        }
     }
 
-    /// Get all subscribed parts (sorted)
-    pub fn get_all_parts(&self) -> Vec<String> {
-        let mut parts: Vec<_> = self.parts.iter().cloned().collect();
-        parts.sort();
-        parts
+    fn query_tracked_parts(&self, part_number: Option<&str>, tag: Option<&str>) -> Result<Vec<TrackedPart>> {
+        let mut statement = self.conn.prepare(
+            "SELECT parts.part_number, note, added_at, last_synced_at, item_number FROM parts
+             LEFT JOIN crosswalk USING (part_number)
+             WHERE (?1 IS NULL OR part_number = ?1)
+               AND (?2 IS NULL OR part_number IN (SELECT part_number FROM tags WHERE tag = ?2))
+             ORDER BY part_number",
+        )?;
+        let mut parts = statement.query_map(params![part_number, tag], |row| {
+            Ok(TrackedPart {
+                part_number: row.get(0)?,
+                tags: Vec::new(),
+                note: row.get(1)?,
+                metadata: BTreeMap::new(),
+                added_at: row.get(2)?,
+                last_synced_at: row.get(3)?,
+                item_number: row.get(4)?,
+            })
+        })?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+        // Attach tags and metadata in two queries rather than two per part
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        let mut statement = self.conn.prepare("SELECT part_number, tag FROM tags ORDER BY tag")?;
+        for row in statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))? {
+            let (part, tag) = row?;
+            tags.entry(part).or_default().push(tag);
+        }
+
+        let mut metadata: HashMap<String, BTreeMap<String, String>> = HashMap::new();
+        let mut statement = self.conn.prepare("SELECT part_number, key, value FROM metadata")?;
+        for row in statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))? {
+            let (part, key, value) = row?;
+            metadata.entry(part).or_default().insert(key, value);
+        }
+
+        for part in &mut parts {
+            part.tags = tags.remove(&part.part_number).unwrap_or_default();
+            part.metadata = metadata.remove(&part.part_number).unwrap_or_default();
+        }
+        Ok(parts)
     }
 
     /// Get count of tracked parts
-    pub fn count(&self) -> usize {
-        self.parts.len()
+    pub fn count(&self) -> Result<usize> {
+        Ok(self.conn.query_row("SELECT COUNT(*) FROM parts", [], |row| row.get(0))?)
     }
 
-    /// Import parts from a file (auto-deduplicates)
+    /// Import parts (with any tags, notes and metadata) from a file; returns the number of new parts
     pub fn import_from_file(&mut self, import_path: &str) -> Result<usize> {
-        let path = expand_path(import_path);
-        let file = File::open(&path)?;
+        Ok(self.import_new_parts(import_path)?.len())
+    }
+
+    /// Import parts from a file like `import_from_file`, returning the parts that weren't tracked yet
+    pub fn import_new_parts(&mut self, import_path: &str) -> Result<Vec<String>> {
+        self.import_from_path(&expand_path(import_path))
+    }
+
+    fn import_from_path(&mut self, path: &Path) -> Result<Vec<String>> {
+        let file = File::open(path)?;
         let reader = BufReader::new(file);
 
-        let mut imported_count = 0;
+        let tx = self.conn.transaction()?;
+        let mut imported = Vec::new();
 
         for line in reader.lines() {
             let line = line?.trim().to_string();
-            if !line.is_empty() && !line.starts_with('#') {
-                let part_number = line.trim().to_uppercase();
-                if self.parts.insert(part_number) {
-                    imported_count += 1;
-                }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
             }
+
+            let (part_number, annotations) = parse_line(&line);
+            if insert_part(&tx, &part_number)? {
+                imported.push(part_number.clone());
+            }
+            for tag in &annotations.tags {
+                tx.execute("INSERT OR IGNORE INTO tags (part_number, tag) VALUES (?1, ?2)", params![part_number, tag])?;
+            }
+            if let Some(note) = &annotations.note {
+                tx.execute("UPDATE parts SET note = ?2 WHERE part_number = ?1", params![part_number, note])?;
+            }
+            for (key, value) in &annotations.metadata {
+                tx.execute(
+                    "INSERT INTO metadata (part_number, key, value) VALUES (?1, ?2, ?3)
+                     ON CONFLICT (part_number, key) DO UPDATE SET value = excluded.value",
+                    params![part_number, key, value],
+                )?;
+            }
+            if let Some(item_number) = &annotations.item_number {
+                write_item_number(&tx, &part_number, item_number)?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(imported)
+    }
+
+    /// Export all parts with their annotations in the flat-file format; returns the number of parts
+    pub fn export_to_file(&self, export_path: &str) -> Result<usize> {
+        let path = expand_path(export_path);
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        let mut writer = BufWriter::new(file);
+
+        let parts = self.get_tracked_parts(None)?;
+        let header = "McMaster-Carr Subscribed Parts\nExported by mmcli; re-import with 'mmc import'";
+        writer.write_all(format_parts(header, &parts).as_bytes())?;
+
+        writer.flush()?;
+        Ok(parts.len())
+    }
+
+    /// Make the tracked parts exactly `parts`, keeping when each was added and last synced
+    pub fn replace_parts(&mut self, parts: &[TrackedPart]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        let keep: BTreeSet<String> = parts.iter().map(|part| normalize_part(&part.part_number)).collect();
+        let existing: Vec<String> = tx.prepare("SELECT part_number FROM parts")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        for part_number in existing.iter().filter(|part_number| !keep.contains(*part_number)) {
+            tx.execute("DELETE FROM parts WHERE part_number = ?1", params![part_number])?;
         }
 
-        if imported_count > 0 {
-            self.save_to_file()?;
+        for part in parts {
+            write_part(&tx, part)?;
         }
 
-        Ok(imported_count)
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Track a part again with the tags, note and metadata it had, e.g. when undoing its removal
+    pub fn restore_part(&mut self, part: &TrackedPart) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        write_part(&tx, part)?;
+        tx.commit()?;
+        Ok(())
     }
 
     /// Clear all parts (for testing or reset)
     pub fn clear(&mut self) -> Result<()> {
-        self.parts.clear();
-        self.save_to_file()?;
+        self.conn.execute("DELETE FROM parts", [])?;
         Ok(())
     }
 
-    /// Get the path to the subscription file being used
+    /// Get the path to the subscription database being used
     pub fn get_file_path(&self) -> &PathBuf {
         &self.file_path
     }
+
+    /// Path of the discontinued parts report, kept next to the subscription database
+    pub fn discontinued_report_path(&self) -> PathBuf {
+        self.file_path.with_file_name("discontinued.txt")
+    }
+}
+
+/// Insert a part if it isn't tracked yet; returns whether it was new
+fn insert_part(conn: &Connection, part_number: &str) -> rusqlite::Result<bool> {
+    let added_at = chrono::Utc::now().to_rfc3339();
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO parts (part_number, added_at) VALUES (?1, ?2)",
+        params![part_number, added_at],
+    )?;
+    Ok(inserted > 0)
+}
+
+/// Track a part (if needed) and set its tags, note and metadata to exactly those given
+fn write_part(conn: &Connection, part: &TrackedPart) -> rusqlite::Result<()> {
+    let part_number = normalize_part(&part.part_number);
+    insert_part(conn, &part_number)?;
+    conn.execute("UPDATE parts SET note = ?2 WHERE part_number = ?1", params![part_number, part.note])?;
+    conn.execute("DELETE FROM tags WHERE part_number = ?1", params![part_number])?;
+    for tag in part.tags.iter().filter_map(|tag| normalize_tag(tag)) {
+        conn.execute("INSERT OR IGNORE INTO tags (part_number, tag) VALUES (?1, ?2)", params![part_number, tag])?;
+    }
+    conn.execute("DELETE FROM metadata WHERE part_number = ?1", params![part_number])?;
+    for (key, value) in &part.metadata {
+        conn.execute("INSERT INTO metadata (part_number, key, value) VALUES (?1, ?2, ?3)", params![part_number, key, value])?;
+    }
+    // Parts from before the crosswalk don't clear an existing mapping
+    if let Some(item_number) = &part.item_number {
+        write_item_number(conn, &part_number, item_number)?;
+    }
+    Ok(())
+}
+
+fn write_item_number(conn: &Connection, part_number: &str, item_number: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO crosswalk (part_number, item_number) VALUES (?1, ?2)
+         ON CONFLICT (part_number) DO UPDATE SET item_number = excluded.item_number",
+        params![part_number, item_number],
+    )?;
+    Ok(())
+}
+
+/// Part numbers are stored uppercase without surrounding whitespace
+fn normalize_part(part_number: &str) -> String {
+    part_number.trim().to_uppercase()
+}
+
+/// Parse parts in the flat-file format, skipping blank lines and `#` comments
+pub(crate) fn parse_parts(text: &str) -> Vec<TrackedPart> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (part_number, annotations) = parse_line(line);
+            TrackedPart {
+                part_number,
+                tags: annotations.tags.into_iter().collect(),
+                note: annotations.note,
+                metadata: annotations.metadata,
+                added_at: None,
+                last_synced_at: None,
+                item_number: annotations.item_number,
+            }
+        })
+        .collect()
+}
+
+/// Format parts in the flat-file format under a `#` comment header, one line per part
+pub(crate) fn format_parts(header: &str, parts: &[TrackedPart]) -> String {
+    let mut text: String = header.lines().map(|line| format!("# {}\n", line)).collect();
+    text.push('\n');
+    for part in parts {
+        let annotations = Annotations {
+            tags: part.tags.iter().cloned().collect(),
+            note: part.note.clone(),
+            metadata: part.metadata.clone(),
+            item_number: part.item_number.clone(),
+        };
+        text.push_str(&format_line(&part.part_number, &annotations));
+        text.push('\n');
+    }
+    text
+}
+
+/// Parse a subscription file line into its part number and annotations
+fn parse_line(line: &str) -> (String, Annotations) {
+    let mut columns = line.split('\t');
+    // Remove any whitespace and convert to uppercase for consistency
+    let part_number = columns.next().unwrap_or("").trim().to_uppercase();
+
+    let tags = columns.next().unwrap_or("").split(',').filter_map(normalize_tag).collect();
+    let note = columns.next()
+        .map(unescape)
+        .filter(|note| !note.is_empty());
+    let metadata = columns.next()
+        .map(|column| {
+            split_unescaped(column, ';')
+                .into_iter()
+                .filter_map(|pair| {
+                    let mut parts = split_unescaped(&pair, '=').into_iter();
+                    let key = unescape(&parts.next()?);
+                    let value = parts.map(|rest| unescape(&rest)).collect::<Vec<_>>().join("=");
+                    (!key.is_empty()).then_some((key, value))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let item_number = columns.next()
+        .map(|column| unescape(column).trim().to_string())
+        .filter(|item_number| !item_number.is_empty());
+
+    (part_number, Annotations { tags, note, metadata, item_number })
+}
+
+/// Format a part and its annotations as a subscription file line
+fn format_line(part_number: &str, annotations: &Annotations) -> String {
+    let tags = annotations.tags.iter().cloned().collect::<Vec<_>>().join(",");
+    let note = annotations.note.as_deref().map(escape).unwrap_or_default();
+    let metadata = annotations.metadata.iter()
+        .map(|(key, value)| format!("{}={}", escape(key).replace('=', "\\="), escape(value)))
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let item_number = annotations.item_number.as_deref().map(escape).unwrap_or_default();
+
+    let line = format!("{}\t{}\t{}\t{}\t{}", part_number, tags, note, metadata, item_number);
+    line.trim_end_matches('\t').to_string()
+}
+
+/// Backslash-escape characters that would break the line format
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            ';' => escaped.push_str("\\;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Reverse `escape`
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Split on a separator that isn't preceded by a backslash, keeping escapes intact
+fn split_unescaped(value: &str, separator: char) -> Vec<String> {
+    let mut pieces = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        let current = pieces.last_mut().expect("pieces is never empty");
+        if c == '\\' {
+            current.push(c);
+            if let Some(next) = chars.next() {
+                current.push(next);
+            }
+        } else if c == separator {
+            pieces.push(String::new());
+        } else {
+            current.push(c);
+        }
+    }
+    pieces
+}
+
+/// Tags are lowercase with surrounding whitespace removed; separators can't appear inside them
+fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() || tag.contains([',', '\t', '\n']) {
+        None
+    } else {
+        Some(tag)
+    }
 }
 
 #[cfg(test)]
@@ -198,6 +716,7 @@ mod tests {
             password: "test".to_string(),
             certificate_path: None,
             certificate_password: None,
+            certificate_key_path: None,
             subscriptions_file: Some(test_file.to_string_lossy().to_string()),
         });
 
@@ -209,12 +728,12 @@ mod tests {
         assert!(manager.add_part("92141A008").unwrap());
 
         // Test checking parts
-        assert!(manager.has_part("91831A030"));
-        assert!(manager.has_part("91831a030")); // Case insensitive
-        assert!(!manager.has_part("99999X999"));
+        assert!(manager.has_part("91831A030").unwrap());
+        assert!(manager.has_part("91831a030").unwrap()); // Case insensitive
+        assert!(!manager.has_part("99999X999").unwrap());
 
         // Test getting all parts
-        let parts = manager.get_all_parts();
+        let parts = manager.get_all_parts().unwrap();
         assert_eq!(parts.len(), 2);
         assert!(parts.contains(&"91831A030".to_string()));
         assert!(parts.contains(&"92141A008".to_string()));
@@ -222,13 +741,73 @@ mod tests {
         // Test removing parts
         assert!(manager.remove_part("91831A030").unwrap());
         assert!(!manager.remove_part("91831A030").unwrap()); // Already removed
-        assert_eq!(manager.count(), 1);
+        assert_eq!(manager.count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_tags_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let test_file = temp_dir.path().join("tagged_subscriptions.db");
+        let creds = Some(Credentials {
+            username: "test".to_string(),
+            password: "test".to_string(),
+            certificate_path: None,
+            certificate_password: None,
+            certificate_key_path: None,
+            subscriptions_file: Some(test_file.to_string_lossy().to_string()),
+        });
+
+        let mut manager = SubscriptionManager::new(&creds).unwrap();
+        manager.add_part("92141A008").unwrap();
+        let tags = manager.add_tags("91290a115", &["Robot-V2".to_string(), "fasteners".to_string()]).unwrap();
+        assert_eq!(tags, vec!["fasteners", "robot-v2"]);
+
+        // Tags survive a reload, and untagged lines still parse
+        let manager = SubscriptionManager::new(&creds).unwrap();
+        let tagged = manager.get_tracked_parts(Some("robot-v2")).unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].part_number, "91290A115");
+        assert_eq!(manager.get_tracked_parts(None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_notes_and_metadata_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let test_file = temp_dir.path().join("annotated_subscriptions.db");
+        let creds = Some(Credentials {
+            username: "test".to_string(),
+            password: "test".to_string(),
+            certificate_path: None,
+            certificate_password: None,
+            certificate_key_path: None,
+            subscriptions_file: Some(test_file.to_string_lossy().to_string()),
+        });
+
+        let mut manager = SubscriptionManager::new(&creds).unwrap();
+        manager.add_tags("91290A115", &["robot-v2".to_string()]).unwrap();
+        manager.set_note("91290A115", Some("use for base plate;\tnot the lid")).unwrap();
+        manager.set_metadata("91290A115", "bin", Some("A3")).unwrap();
+        manager.set_metadata("91290A115", "a=b", Some("x;y")).unwrap();
+        manager.set_item_number("91290a115", Some("HW-00412")).unwrap();
+
+        // Export and re-import into a fresh database keeps everything
+        let export_path = temp_dir.path().join("export.txt");
+        assert_eq!(manager.export_to_file(&export_path.to_string_lossy()).unwrap(), 1);
+        let mut imported = SubscriptionManager::open(temp_dir.path().join("imported.db"), None).unwrap();
+        assert_eq!(imported.import_from_file(&export_path.to_string_lossy()).unwrap(), 1);
+
+        let part = imported.get_tracked_part("91290a115").unwrap().unwrap();
+        assert_eq!(part.tags, vec!["robot-v2"]);
+        assert_eq!(part.note.as_deref(), Some("use for base plate;\tnot the lid"));
+        assert_eq!(part.metadata.get("bin").map(String::as_str), Some("A3"));
+        assert_eq!(part.metadata.get("a=b").map(String::as_str), Some("x;y"));
+        assert_eq!(part.item_number.as_deref(), Some("HW-00412"));
     }
 
     #[test]
     fn test_configurable_subscription_file_path() {
         let temp_dir = tempdir().unwrap();
-        let custom_path = temp_dir.path().join("custom_location").join("my_subs.txt");
+        let custom_path = temp_dir.path().join("custom_location").join("my_subs.db");
 
         // Test with custom path specified
         let creds_custom = Some(Credentials {
@@ -236,6 +815,7 @@ mod tests {
             password: "test".to_string(),
             certificate_path: None,
             certificate_password: None,
+            certificate_key_path: None,
             subscriptions_file: Some(custom_path.to_string_lossy().to_string()),
         });
 
@@ -248,16 +828,39 @@ mod tests {
             password: "test".to_string(),
             certificate_path: None,
             certificate_password: None,
+            certificate_key_path: None,
             subscriptions_file: None,
         });
 
-        let manager_default = SubscriptionManager::new(&creds_default).unwrap();
-        assert!(manager_default.get_file_path().ends_with("subscriptions.txt"));
-        assert!(manager_default.get_file_path().to_string_lossy().contains(".config/mmc"));
+        // Only the paths are checked, so the real default database is never opened
+        let (default_path, legacy_path) = SubscriptionManager::paths(&creds_default);
+        assert!(default_path.ends_with("subscriptions.db"));
+        assert!(default_path.to_string_lossy().contains(".config/mmc"));
+        assert!(legacy_path.unwrap().ends_with("subscriptions.txt"));
 
         // Test with no credentials (should use default)
-        let manager_none = SubscriptionManager::new(&None).unwrap();
-        assert!(manager_none.get_file_path().ends_with("subscriptions.txt"));
-        assert!(manager_none.get_file_path().to_string_lossy().contains(".config/mmc"));
+        let (none_path, _) = SubscriptionManager::paths(&None);
+        assert_eq!(none_path, default_path);
+    }
+
+    #[test]
+    fn test_legacy_file_is_migrated() {
+        let temp_dir = tempdir().unwrap();
+        let legacy_path = temp_dir.path().join("subscriptions.txt");
+        fs::write(&legacy_path, "# McMaster-Carr Subscribed Parts\n\n91290a115\trobot-v2\n92141A008\n").unwrap();
+
+        let creds = Some(Credentials {
+            username: "test".to_string(),
+            password: "test".to_string(),
+            certificate_path: None,
+            certificate_password: None,
+            certificate_key_path: None,
+            subscriptions_file: Some(legacy_path.to_string_lossy().to_string()),
+        });
+
+        let manager = SubscriptionManager::new(&creds).unwrap();
+        assert_eq!(manager.get_file_path(), &temp_dir.path().join("subscriptions.db"));
+        assert_eq!(manager.get_all_parts().unwrap(), vec!["91290A115", "92141A008"]);
+        assert_eq!(manager.get_tags("91290A115").unwrap(), vec!["robot-v2"]);
     }
 }
\ No newline at end of file
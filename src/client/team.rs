@@ -0,0 +1,280 @@
+//! Tracked parts shared with a team through a common file or a git repository
+//!
+//! `mmc sync --remote` does a three-way merge of the local list, the shared list and the
+//! shared list as it was after the last sync. Parts, tags, notes and metadata changed on
+//! either side are kept, and a part removed on either side is removed. When both sides
+//! changed the same note or metadata value, the local one wins and the part is reported
+//! as a conflict. The shared list uses the `mmc export` format, so it diffs cleanly in git.
+
+use crate::client::subscriptions::{format_parts, parse_parts, TrackedPart};
+use crate::utils::error::{ClientError, Result};
+use crate::utils::lock::{write_atomic, FileLock};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Name of the shared list inside a git repository
+pub const TEAM_FILE: &str = "parts.txt";
+
+/// Attempts at pushing to a git remote that others may have pushed to in the meantime
+const PUSH_ATTEMPTS: usize = 3;
+
+const TEAM_FILE_HEADER: &str = "McMaster-Carr parts tracked by the team\nKept in sync by 'mmc sync --remote'; edit by hand or with mmc";
+
+/// Where the team's shared list lives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TeamRemote {
+    /// A file on a shared drive
+    File(PathBuf),
+    /// A git repository URL, holding the list as `parts.txt`
+    Git(String),
+}
+
+impl TeamRemote {
+    /// A URL, `git@host:repo` or `.git` path is a git repository; anything else is a file
+    pub fn parse(remote: &str) -> Self {
+        let remote = remote.trim();
+        if remote.contains("://") || remote.starts_with("git@") || remote.trim_end_matches('/').ends_with(".git") {
+            TeamRemote::Git(remote.to_string())
+        } else {
+            TeamRemote::File(crate::config::paths::expand_path(remote))
+        }
+    }
+
+    /// Stable name for the remote's local state (last synced list, git checkout)
+    fn id(&self) -> String {
+        let name = match self {
+            TeamRemote::File(path) => path.display().to_string(),
+            TeamRemote::Git(url) => url.clone(),
+        };
+        hex::encode(&Sha256::digest(name.as_bytes())[..8])
+    }
+}
+
+impl std::fmt::Display for TeamRemote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TeamRemote::File(path) => write!(f, "{}", path.display()),
+            TeamRemote::Git(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+/// What `mmc sync --remote` changed
+#[derive(Debug, Default, Serialize)]
+pub struct TeamSyncReport {
+    pub remote: String,
+    pub pulled: Vec<String>, // Parts added from the shared list
+    pub pushed: Vec<String>, // Local parts added to the shared list
+    pub removed: Vec<String>, // Parts removed here or from the shared list since the last sync
+    pub conflicts: Vec<String>, // Parts where both sides changed the same value; the local one was kept
+    pub total: usize, // Parts tracked after the sync
+}
+
+/// Team sync methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Merge the tracked parts with a team's shared list and write the result to both
+    pub fn sync_team(&self, remote: &TeamRemote) -> Result<TeamSyncReport> {
+        let mut subscriptions = self.subscriptions();
+        let state_dir = subscriptions.get_file_path().with_file_name("team");
+        fs::create_dir_all(&state_dir)?;
+        let base_path = state_dir.join(format!("{}.txt", remote.id()));
+        let base = read_parts(&base_path)?;
+        let local = subscriptions.get_tracked_parts(None)?;
+
+        let (merged, mut report) = match remote {
+            TeamRemote::File(path) => {
+                let _lock = FileLock::exclusive(path)?;
+                let (merged, report) = merge(&local, &read_parts(path)?, &base);
+                if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                    fs::create_dir_all(parent)?;
+                }
+                write_atomic(path, format_parts(TEAM_FILE_HEADER, &merged).as_bytes())?;
+                (merged, report)
+            }
+            TeamRemote::Git(url) => sync_git(url, &state_dir.join(remote.id()), &local, &base)?,
+        };
+
+        subscriptions.replace_parts(&merged)?;
+        write_atomic(&base_path, format_parts(TEAM_FILE_HEADER, &merged).as_bytes())?;
+        report.remote = remote.to_string();
+        Ok(report)
+    }
+}
+
+/// Merge with `parts.txt` in a git repository and push the result, retrying if someone pushed first
+fn sync_git(url: &str, checkout: &Path, local: &[TrackedPart], base: &[TrackedPart]) -> Result<(Vec<TrackedPart>, TeamSyncReport)> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        if checkout.join(".git").exists() {
+            git(checkout, &["fetch", "--quiet", "origin"])?;
+            // An empty repository has nothing to reset to until the first push
+            if git(checkout, &["rev-parse", "--verify", "--quiet", "@{upstream}"]).is_ok() {
+                git(checkout, &["reset", "--quiet", "--hard", "@{upstream}"])?;
+            }
+        } else {
+            let parent = checkout.parent().unwrap_or(Path::new("."));
+            let name = checkout.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+            git(parent, &["clone", "--quiet", url, name])?;
+        }
+
+        let shared_path = checkout.join(TEAM_FILE);
+        let (merged, report) = merge(local, &read_parts(&shared_path)?, base);
+        fs::write(&shared_path, format_parts(TEAM_FILE_HEADER, &merged))?;
+        if git(checkout, &["status", "--porcelain", "--", TEAM_FILE])?.trim().is_empty() {
+            return Ok((merged, report));
+        }
+
+        git(checkout, &["add", "--", TEAM_FILE])?;
+        git(checkout, &["commit", "--quiet", "-m", "Update tracked parts"])?;
+        match git(checkout, &["push", "--quiet", "-u", "origin", "HEAD"]) {
+            Ok(_) => return Ok((merged, report)),
+            Err(_) if attempt < PUSH_ATTEMPTS => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Run git in `dir`, returning its output or an error with what git printed
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| ClientError::Generic(format!("Could not run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(ClientError::Generic(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parts in a flat file, or none if it doesn't exist yet
+fn read_parts(path: &Path) -> Result<Vec<TrackedPart>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(parse_parts(&text)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Three-way merge of the local and shared lists against the list from the last sync
+fn merge(local: &[TrackedPart], shared: &[TrackedPart], base: &[TrackedPart]) -> (Vec<TrackedPart>, TeamSyncReport) {
+    let index = |parts: &[TrackedPart]| -> BTreeMap<String, TrackedPart> {
+        parts.iter().map(|part| (part.part_number.clone(), part.clone())).collect()
+    };
+    let (local, shared, base) = (index(local), index(shared), index(base));
+    let part_numbers: BTreeSet<&String> = local.keys().chain(shared.keys()).chain(base.keys()).collect();
+
+    let mut report = TeamSyncReport::default();
+    let mut merged = Vec::new();
+    for part_number in part_numbers {
+        match (local.get(part_number), shared.get(part_number), base.get(part_number)) {
+            (Some(local), Some(shared), base) => {
+                let mut conflict = false;
+                merged.push(merge_part(local, shared, base, &mut conflict));
+                if conflict {
+                    report.conflicts.push(part_number.clone());
+                }
+            }
+            // Removed on the other side since the last sync
+            (Some(_), None, Some(_)) | (None, Some(_), Some(_)) => report.removed.push(part_number.clone()),
+            (Some(local), None, None) => {
+                report.pushed.push(part_number.clone());
+                merged.push(local.clone());
+            }
+            (None, Some(shared), None) => {
+                report.pulled.push(part_number.clone());
+                merged.push(shared.clone());
+            }
+            (None, None, _) => {}
+        }
+    }
+    report.total = merged.len();
+    (merged, report)
+}
+
+/// Merge one part's tags, note and metadata
+fn merge_part(local: &TrackedPart, shared: &TrackedPart, base: Option<&TrackedPart>, conflict: &mut bool) -> TrackedPart {
+    let tags = |part: Option<&TrackedPart>| -> BTreeSet<String> {
+        part.map(|part| part.tags.iter().cloned().collect()).unwrap_or_default()
+    };
+    let (local_tags, shared_tags, base_tags) = (tags(Some(local)), tags(Some(shared)), tags(base));
+    // Kept on both sides, or added on either
+    let merged_tags = local_tags.intersection(&shared_tags)
+        .chain(local_tags.difference(&base_tags))
+        .chain(shared_tags.difference(&base_tags))
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
+    let note = merge_value(local.note.as_ref(), shared.note.as_ref(), base.and_then(|base| base.note.as_ref()), conflict);
+
+    let keys: BTreeSet<&String> = local.metadata.keys().chain(shared.metadata.keys()).collect();
+    let metadata = keys.into_iter()
+        .filter_map(|key| {
+            let base = base.and_then(|base| base.metadata.get(key));
+            merge_value(local.metadata.get(key), shared.metadata.get(key), base, conflict).map(|value| (key.clone(), value))
+        })
+        .collect();
+
+    TrackedPart {
+        tags: merged_tags.into_iter().collect(),
+        note,
+        metadata,
+        ..local.clone()
+    }
+}
+
+/// Take whichever side changed a value; if both did differently, keep the local one
+fn merge_value(local: Option<&String>, shared: Option<&String>, base: Option<&String>, conflict: &mut bool) -> Option<String> {
+    if local == base || local == shared {
+        shared.cloned()
+    } else {
+        *conflict |= shared != base;
+        local.cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockApi;
+
+    #[tokio::test]
+    async fn test_sync_team_file() {
+        let api = MockApi::start().await;
+        let client = api.client().unwrap();
+        let shared = api.data_dir().join("share/parts.txt");
+        let remote = TeamRemote::parse(shared.to_str().unwrap());
+        assert_eq!(remote, TeamRemote::File(shared.clone()));
+        assert_eq!(TeamRemote::parse("git@github.com:acme/parts.git"), TeamRemote::Git("git@github.com:acme/parts.git".to_string()));
+
+        client.subscriptions().add_part("91290A115").unwrap();
+        client.subscriptions().add_part("90591A111").unwrap();
+        client.subscriptions().set_note("91290A115", Some("base plate")).unwrap();
+        let report = client.sync_team(&remote).unwrap();
+        assert_eq!(report.pushed, vec!["90591A111", "91290A115"]);
+        assert!(fs::read_to_string(&shared).unwrap().contains("91290A115\t\tbase plate"));
+
+        // A teammate adds a part, drops one and retags another while the note changes here
+        fs::write(&shared, "91290A115\tfasteners\tsee drawing\n92141A008\n").unwrap();
+        client.subscriptions().set_note("91290A115", Some("top plate")).unwrap();
+        let report = client.sync_team(&remote).unwrap();
+        assert_eq!(report.pulled, vec!["92141A008"]);
+        assert_eq!(report.removed, vec!["90591A111"]);
+        assert_eq!(report.conflicts, vec!["91290A115"]);
+        let parts = client.subscriptions().get_tracked_parts(None).unwrap();
+        assert_eq!(parts.iter().map(|part| part.part_number.as_str()).collect::<Vec<_>>(), vec!["91290A115", "92141A008"]);
+        assert_eq!(parts[0].tags, vec!["fasteners"]);
+        assert_eq!(parts[0].note.as_deref(), Some("top plate"));
+        assert_eq!(parse_parts(&fs::read_to_string(&shared).unwrap()), parse_parts("91290A115\tfasteners\ttop plate\n92141A008\n"));
+    }
+}
@@ -0,0 +1,266 @@
+//! Recording and replaying API traffic
+//!
+//! With `--record <dir>` every request/response pair is saved as a JSON file, with
+//! credentials, tokens and cookies removed. `--replay <dir>` answers requests from
+//! those files instead of the network, so tests and bug reports can reproduce a
+//! session without access to the live API.
+
+use crate::utils::error::{ClientError, Result};
+use reqwest::{Request, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Headers that carry credentials and are never written to a transcript
+const SECRET_HEADERS: &[&str] = &["authorization", "cookie", "set-cookie", "proxy-authorization"];
+
+/// JSON body fields that carry credentials (login request and response)
+const SECRET_FIELDS: &[&str] = &["Password", "AuthToken"];
+
+const REDACTED: &str = "REDACTED";
+
+/// One recorded request and its response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exchange {
+    pub method: String,
+    pub url: String,
+    pub request_headers: BTreeMap<String, String>,
+    pub request_body: Option<String>,
+    pub status: u16,
+    pub response_headers: BTreeMap<String, String>,
+    pub response_body: Body,
+}
+
+/// A body as text when it's UTF-8, otherwise hex-encoded bytes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Body {
+    Text(String),
+    Hex(String),
+}
+
+impl Body {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Body::Text(redact_body(text)),
+            Err(_) => Body::Hex(hex::encode(bytes)),
+        }
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        match self {
+            Body::Text(text) => Ok(text.as_bytes().to_vec()),
+            Body::Hex(encoded) => hex::decode(encoded)
+                .map_err(|e| ClientError::Parse(format!("Invalid hex body in transcript: {}", e))),
+        }
+    }
+}
+
+/// Whether API traffic is being saved or served from a transcript directory
+#[derive(Debug)]
+pub enum Transcript {
+    Record {
+        dir: PathBuf,
+        next: Mutex<usize>, // Sequence number for the next file
+    },
+    Replay {
+        dir: PathBuf,
+        responses: Mutex<HashMap<String, VecDeque<Exchange>>>, // Keyed by "METHOD URL", in recorded order
+    },
+}
+
+impl Transcript {
+    /// Start recording into `dir`, creating it if needed
+    pub fn record(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Transcript::Record {
+            dir: dir.to_path_buf(),
+            next: Mutex::new(1),
+        })
+    }
+
+    /// Load every exchange recorded in `dir` for replay
+    pub fn replay(dir: &Path) -> Result<Self> {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+
+        let mut responses: HashMap<String, VecDeque<Exchange>> = HashMap::new();
+        for file in files {
+            let exchange: Exchange = serde_json::from_str(&fs::read_to_string(&file)?)
+                .map_err(|e| ClientError::Parse(format!("Invalid transcript file '{}': {}", file.display(), e)))?;
+            responses.entry(key(&exchange.method, &exchange.url)).or_default().push_back(exchange);
+        }
+
+        Ok(Transcript::Replay {
+            dir: dir.to_path_buf(),
+            responses: Mutex::new(responses),
+        })
+    }
+
+    /// Send a request through the transcript: record the real exchange, or answer from the recording
+    pub(crate) async fn send(&self, client: &reqwest::Client, request: Request) -> Result<Response> {
+        match self {
+            Transcript::Record { dir, next } => {
+                let method = request.method().to_string();
+                let url = request.url().to_string();
+                let request_headers = redact_headers(request.headers());
+                let request_body = request.body()
+                    .and_then(|body| body.as_bytes())
+                    .map(|bytes| redact_body(&String::from_utf8_lossy(bytes)));
+
+                let response = client.execute(request).await?;
+                let status = response.status().as_u16();
+                let headers = response.headers().clone();
+                let bytes = response.bytes().await?;
+
+                let exchange = Exchange {
+                    method,
+                    url,
+                    request_headers,
+                    request_body,
+                    status,
+                    response_headers: redact_headers(&headers),
+                    response_body: Body::from_bytes(&bytes),
+                };
+                let sequence = {
+                    let mut next = next.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+                    let sequence = *next;
+                    *next += 1;
+                    sequence
+                };
+                fs::write(dir.join(file_name(sequence, &exchange)), serde_json::to_string_pretty(&exchange)?)?;
+
+                // Hand back the real body, not the redacted copy
+                build_response(status, &headers, bytes.to_vec())
+            }
+            Transcript::Replay { dir, responses } => {
+                let key = key(request.method().as_str(), request.url().as_str());
+                let exchange = {
+                    let mut responses = responses.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+                    let queue = responses.get_mut(&key).ok_or_else(|| {
+                        ClientError::NotFound(format!("no recorded response for {} in {}", key, dir.display()))
+                    })?;
+                    // Repeat the last response once the recorded ones run out
+                    if queue.len() > 1 { queue.pop_front() } else { queue.front().cloned() }
+                }
+                .expect("replay queues are never empty");
+
+                let mut headers = reqwest::header::HeaderMap::new();
+                for (name, value) in &exchange.response_headers {
+                    if let (Ok(name), Ok(value)) = (
+                        reqwest::header::HeaderName::from_bytes(name.as_bytes()),
+                        reqwest::header::HeaderValue::from_str(value),
+                    ) {
+                        headers.insert(name, value);
+                    }
+                }
+                build_response(exchange.status, &headers, exchange.response_body.to_bytes()?)
+            }
+        }
+    }
+}
+
+fn key(method: &str, url: &str) -> String {
+    format!("{} {}", method.to_uppercase(), url)
+}
+
+/// e.g. `0003-GET-v1-products-91290A115.json`
+fn file_name(sequence: usize, exchange: &Exchange) -> String {
+    let path = exchange.url
+        .split("://")
+        .last()
+        .and_then(|rest| rest.split_once('/').map(|(_, path)| path))
+        .unwrap_or_default();
+    let slug: String = path.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .take(80)
+        .collect();
+    format!("{:04}-{}-{}.json", sequence, exchange.method, slug.trim_matches('-'))
+}
+
+fn redact_headers(headers: &reqwest::header::HeaderMap) -> BTreeMap<String, String> {
+    headers.iter()
+        .filter(|(name, _)| !SECRET_HEADERS.contains(&name.as_str()))
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).to_string()))
+        .collect()
+}
+
+/// Replace credential fields in a JSON body; other bodies are kept as they are
+fn redact_body(body: &str) -> String {
+    let Ok(mut value) = serde_json::from_str::<serde_json::Value>(body) else {
+        return body.to_string();
+    };
+    let Some(object) = value.as_object_mut() else {
+        return body.to_string();
+    };
+    if !SECRET_FIELDS.iter().any(|field| object.contains_key(*field)) {
+        return body.to_string();
+    }
+
+    for field in SECRET_FIELDS {
+        if let Some(secret) = object.get_mut(*field) {
+            *secret = serde_json::Value::String(REDACTED.to_string());
+        }
+    }
+    value.to_string()
+}
+
+/// Build a response from a status, headers and body read earlier
+pub(crate) fn build_response(status: u16, headers: &reqwest::header::HeaderMap, body: Vec<u8>) -> Result<Response> {
+    let mut builder = http::Response::builder().status(status);
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    let response = builder.body(body)
+        .map_err(|e| ClientError::Parse(format!("Invalid recorded response: {}", e)))?;
+    Ok(Response::from(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_replay_from_transcript() {
+        let temp_dir = tempdir().unwrap();
+        let exchange = Exchange {
+            method: "GET".to_string(),
+            url: "https://api.mcmaster.com/v1/products/91290A115/price".to_string(),
+            request_headers: BTreeMap::new(),
+            request_body: None,
+            status: 200,
+            response_headers: BTreeMap::from([("content-type".to_string(), "application/json".to_string())]),
+            response_body: Body::Text(r#"[{"Amount": 9.5}]"#.to_string()),
+        };
+        assert_eq!(file_name(1, &exchange), "0001-GET-v1-products-91290A115-price.json");
+        fs::write(temp_dir.path().join(file_name(1, &exchange)), serde_json::to_string(&exchange).unwrap()).unwrap();
+
+        let transcript = Transcript::replay(temp_dir.path()).unwrap();
+        let client = reqwest::Client::new();
+        for _ in 0..2 {
+            let request = client.get(&exchange.url).build().unwrap();
+            let response = transcript.send(&client, request).await.unwrap();
+            assert_eq!(response.status().as_u16(), 200);
+            assert_eq!(response.text().await.unwrap(), r#"[{"Amount": 9.5}]"#);
+        }
+
+        let request = client.get("https://api.mcmaster.com/v1/products/92141A008").build().unwrap();
+        assert!(matches!(transcript.send(&client, request).await, Err(ClientError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_redaction() {
+        assert_eq!(
+            redact_body(r#"{"UserName":"me@example.com","Password":"hunter2"}"#),
+            r#"{"Password":"REDACTED","UserName":"me@example.com"}"#
+        );
+        assert_eq!(redact_body("not json"), "not json");
+        assert_eq!(Body::from_bytes(&[0xff, 0x00]), Body::Hex("ff00".to_string()));
+    }
+}
@@ -1,8 +1,10 @@
 //! Configuration
 //! 
 //! This module handles configuration management, including XDG-compliant
-//! paths and certificate discovery.
+//! paths, certificate discovery and user defaults from `config.toml`.
 
 pub mod paths;
+pub mod settings;
 
-pub use paths::{get_config_dir, get_token_path, find_certificate_path};
\ No newline at end of file
+pub use paths::{get_config_dir, get_token_path, find_certificate_path};
+pub use settings::Config;
\ No newline at end of file
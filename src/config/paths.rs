@@ -15,8 +15,26 @@ pub fn get_token_path() -> PathBuf {
     get_config_dir().join("token")
 }
 
-/// Get the subscriptions file path
+/// Get the config file path for user defaults
+pub fn get_config_file_path() -> PathBuf {
+    get_config_dir().join("config.toml")
+}
+
+/// Get the product cache database path (XDG cache directory)
+pub fn get_cache_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(get_config_dir)
+        .join("mmc")
+        .join("products.db")
+}
+
+/// Get the subscriptions database path
 pub fn get_subscriptions_path() -> PathBuf {
+    get_config_dir().join("subscriptions.db")
+}
+
+/// Get the flat subscriptions file used before the database, migrated on first use
+pub fn get_legacy_subscriptions_path() -> PathBuf {
     get_config_dir().join("subscriptions.txt")
 }
 
@@ -28,6 +46,7 @@ pub fn find_certificate_path() -> Option<PathBuf> {
     let candidates = [
         config_dir.join("certificate.pfx"),
         config_dir.join("certificate.p12"),
+        config_dir.join("certificate.pem"),
         // Legacy locations
         dirs::home_dir()?.join(".mmcli").join("certificate.pfx"),
         dirs::home_dir()?.join(".mmcli").join("certificate.p12"),
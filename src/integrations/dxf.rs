@@ -0,0 +1,217 @@
+//! Post-processing 2-D DXF downloads for cutting workflows
+//!
+//! Driven by `[postprocess.dxf]` in config.toml: drawings can be converted to millimetres
+//! or inches, scaled, and stripped of layers such as title blocks and borders. DXF is a
+//! list of (group code, value) line pairs; lengths are rescaled by group code, with the
+//! per-entity exceptions that aren't lengths (bulges, ratios, angles, scale factors) left
+//! alone. DWG files are binary and aren't touched.
+
+use crate::utils::error::{ClientError, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Length units a drawing can be converted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DxfUnits {
+    In,
+    Mm,
+}
+
+impl DxfUnits {
+    /// Value of the `$INSUNITS` header variable
+    fn code(self) -> i32 {
+        match self {
+            DxfUnits::In => 1,
+            DxfUnits::Mm => 4,
+        }
+    }
+
+    fn millimetres(self) -> f64 {
+        match self {
+            DxfUnits::In => 25.4,
+            DxfUnits::Mm => 1.0,
+        }
+    }
+}
+
+/// DXF post-processing options, from the `[postprocess.dxf]` table
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DxfOptions {
+    /// Convert drawings to these units; drawings that don't say are taken to be in inches
+    pub units: Option<DxfUnits>,
+    /// Extra scale factor, applied after any unit conversion
+    pub scale: f64,
+    /// Drop drawing entities on these layers (case-insensitive), e.g. a title block
+    pub strip_layers: Vec<String>,
+}
+
+impl Default for DxfOptions {
+    fn default() -> Self {
+        DxfOptions { units: None, scale: 1.0, strip_layers: Vec::new() }
+    }
+}
+
+/// Header variables holding drawing-space points that scale with the drawing
+const SCALED_HEADER_VARIABLES: &[&str] = &["$EXTMIN", "$EXTMAX", "$LIMMIN", "$LIMMAX", "$INSBASE"];
+
+/// Whether a path has a DXF extension
+pub fn is_dxf_file(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("dxf"))
+}
+
+/// Post-process a DXF file in place
+pub fn postprocess_file(path: &Path, options: &DxfOptions) -> Result<()> {
+    let content = std::fs::read(path)?;
+    let content = String::from_utf8_lossy(&content);
+    let processed = postprocess(&content, options)
+        .map_err(|e| ClientError::Parse(format!("{}: {}", path.display(), e)))?;
+    crate::utils::lock::write_atomic(path, processed.as_bytes())?;
+    Ok(())
+}
+
+/// Apply unit conversion, scaling and layer stripping to DXF text
+pub fn postprocess(content: &str, options: &DxfOptions) -> std::result::Result<String, String> {
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let lines: Vec<&str> = content.lines().collect();
+    if !lines.len().is_multiple_of(2) && !lines.last().is_some_and(|line| line.trim().is_empty()) {
+        return Err("not an ASCII DXF file (odd number of lines)".to_string());
+    }
+    let mut pairs = Vec::with_capacity(lines.len() / 2);
+    for pair in lines.chunks_exact(2) {
+        let code: i32 = pair[0].trim().parse().map_err(|_| format!("not an ASCII DXF file (bad group code '{}')", pair[0].trim()))?;
+        pairs.push((pair[0], code, pair[1].to_string()));
+    }
+
+    let declared = pairs.iter().any(|(_, code, value)| *code == 9 && value.trim().eq_ignore_ascii_case("$INSUNITS"));
+    let factor = match options.units {
+        Some(units) => insunits(&pairs).unwrap_or(25.4) / units.millimetres() * options.scale,
+        None => options.scale,
+    };
+    let strip: Vec<String> = options.strip_layers.iter().map(|layer| layer.to_lowercase()).collect();
+
+    let mut output: Vec<(String, String)> = Vec::with_capacity(pairs.len() + 2);
+    let mut section = String::new();
+    let mut entity = String::new();
+    let mut header_variable = String::new();
+    let mut index = 0;
+    while index < pairs.len() {
+        let (raw_code, code, value) = &pairs[index];
+        let code = *code;
+        match code {
+            0 => {
+                entity = value.trim().to_uppercase();
+                if entity == "SECTION" {
+                    section = pairs.get(index + 1).map(|(_, _, name)| name.trim().to_uppercase()).unwrap_or_default();
+                }
+                // Skip every pair of an entity on a stripped layer
+                if section == "ENTITIES" && !strip.is_empty() {
+                    let end = pairs[index + 1..].iter().position(|(_, code, _)| *code == 0).map_or(pairs.len(), |offset| index + 1 + offset);
+                    let on_stripped_layer = pairs[index + 1..end].iter()
+                        .any(|(_, code, layer)| *code == 8 && strip.contains(&layer.trim().to_lowercase()));
+                    if on_stripped_layer {
+                        index = end;
+                        continue;
+                    }
+                }
+            }
+            9 if section == "HEADER" => header_variable = value.trim().to_uppercase(),
+            _ => {}
+        }
+
+        let mut value = value.clone();
+        if section == "HEADER" && header_variable == "$INSUNITS" && code == 70 {
+            if let Some(units) = options.units {
+                value = format!("{:>6}", units.code());
+            }
+        } else if factor != 1.0 && is_length(&section, &entity, &header_variable, code) {
+            if let Ok(number) = value.trim().parse::<f64>() {
+                value = format_number(number * factor);
+            }
+        }
+        output.push((raw_code.to_string(), value));
+
+        // Declare the new units in a drawing that didn't have $INSUNITS
+        if code == 2 && section == "HEADER" && entity == "SECTION" && !declared {
+            if let Some(units) = options.units {
+                output.push(("  9".to_string(), "$INSUNITS".to_string()));
+                output.push((" 70".to_string(), format!("{:>6}", units.code())));
+            }
+        }
+        index += 1;
+    }
+
+    let mut text = String::with_capacity(content.len());
+    for (code, value) in output {
+        text.push_str(&code);
+        text.push_str(newline);
+        text.push_str(&value);
+        text.push_str(newline);
+    }
+    Ok(text)
+}
+
+/// The drawing's units in millimetres, from `$INSUNITS`, or `None` when unitless or unknown
+fn insunits(pairs: &[(&str, i32, String)]) -> Option<f64> {
+    let position = pairs.iter().position(|(_, code, value)| *code == 9 && value.trim().eq_ignore_ascii_case("$INSUNITS"))?;
+    let (_, _, value) = pairs.get(position + 1)?;
+    match value.trim().parse::<i32>().ok()? {
+        1 => Some(25.4),
+        2 => Some(304.8),
+        4 => Some(1.0),
+        5 => Some(10.0),
+        6 => Some(1000.0),
+        _ => None,
+    }
+}
+
+/// Write a rescaled value without float noise such as `5.080000000000001`
+fn format_number(number: f64) -> String {
+    let text = format!("{:.10}", number);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
+
+/// Whether a group code holds a length that should scale with the drawing
+fn is_length(section: &str, entity: &str, header_variable: &str, code: i32) -> bool {
+    let point = matches!(code, 10..=18 | 20..=28 | 30..=39);
+    match section {
+        "HEADER" => point && SCALED_HEADER_VARIABLES.contains(&header_variable),
+        "BLOCKS" | "ENTITIES" => {
+            point || match entity {
+                "CIRCLE" | "ARC" | "TEXT" | "ATTRIB" | "ATTDEF" => code == 40,
+                "MTEXT" => matches!(code, 40 | 41),
+                "LWPOLYLINE" | "POLYLINE" | "VERTEX" => matches!(code, 40 | 41 | 43),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_postprocess() {
+        let dxf = "  0\nSECTION\n  2\nHEADER\n  9\n$EXTMAX\n 10\n2.0\n 20\n1.0\n  9\n$UCSXDIR\n 10\n1.0\n  0\nENDSEC\n\
+                   \x20 0\nSECTION\n  2\nENTITIES\n\
+                   \x20 0\nCIRCLE\n  8\n0\n 10\n1.0\n 20\n0.5\n 40\n0.25\n\
+                   \x20 0\nLWPOLYLINE\n  8\nTitle\n 10\n0.0\n 42\n0.5\n\
+                   \x20 0\nLWPOLYLINE\n  8\nCUT\n 10\n0.0\n 42\n0.5\n 43\n0.1\n\
+                   \x20 0\nENDSEC\n  0\nEOF\n";
+        let options = DxfOptions { units: Some(DxfUnits::Mm), scale: 2.0, strip_layers: vec!["TITLE".to_string()] };
+        let processed = postprocess(dxf, &options).unwrap();
+        let pairs: Vec<(&str, &str)> = processed.lines().collect::<Vec<_>>().chunks(2).map(|pair| (pair[0].trim(), pair[1].trim())).collect();
+
+        // Unitless drawings are taken as inches: 25.4 mm/in, then doubled
+        assert_eq!(&pairs[2..6], &[("9", "$INSUNITS"), ("70", "4"), ("9", "$EXTMAX"), ("10", "101.6")]);
+        assert!(processed.contains("$UCSXDIR\n 10\n1.0\n"));
+        assert!(processed.contains("CIRCLE\n  8\n0\n 10\n50.8\n 20\n25.4\n 40\n12.7\n"));
+        assert!(!processed.contains("Title"));
+        assert!(processed.contains("CUT\n 10\n0\n 42\n0.5\n 43\n5.08"));
+        assert!(postprocess("not\na\ndxf\n", &options).is_err());
+    }
+}
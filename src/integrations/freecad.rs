@@ -0,0 +1,114 @@
+//! FreeCAD parts: STEP models with a JSON metadata sidecar
+
+use crate::client::downloads::DownloadResult;
+use crate::integrations::solidworks::library_folder;
+use crate::models::product::ProductDetail;
+use crate::utils::error::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Metadata written next to a part's STEP file as `{part}.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PartMetadata {
+    pub part_number: String,
+    pub name: String,
+    pub family: String,
+    pub category: String,
+    pub material: Option<String>,
+    pub vendor: String,
+    pub url: String, // Public product page
+    pub step_file: Option<String>, // File name of the STEP model beside the sidecar
+    pub specifications: BTreeMap<String, String>,
+}
+
+/// Files written for a part by `mmc freecad`
+#[derive(Debug)]
+pub struct FreecadExport {
+    pub metadata: PartMetadata,
+    pub downloads: Vec<DownloadResult>,
+    pub sidecar: PathBuf,
+    pub library_files: Vec<PathBuf>, // Copies in the FreeCAD parts library, when requested
+}
+
+impl PartMetadata {
+    /// Build metadata from product details
+    pub fn from_product(detail: &ProductDetail) -> Self {
+        let material = detail.specifications.iter()
+            .find(|spec| spec.attribute.eq_ignore_ascii_case("Material"))
+            .map(|spec| spec.values.join(", "));
+        PartMetadata {
+            part_number: detail.part_number.clone(),
+            name: detail.detail_description.clone(),
+            family: detail.family_description.clone(),
+            category: detail.product_category.clone(),
+            material,
+            vendor: "McMaster-Carr".to_string(),
+            url: format!("https://www.mcmaster.com/{}/", detail.part_number),
+            step_file: None,
+            specifications: detail.specifications.iter()
+                .map(|spec| (spec.attribute.clone(), spec.values.join(", ")))
+                .collect(),
+        }
+    }
+}
+
+/// FreeCAD export methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Download a part's STEP model and write its metadata sidecar beside it
+    ///
+    /// With `library`, both files are also copied into `{library}/McMaster-Carr/{category}/{family}/`.
+    pub async fn export_freecad(&self, product: &str, output_dir: Option<&str>, library: Option<&Path>) -> Result<FreecadExport> {
+        let detail = self.get_product(product).await?;
+        let mut metadata = PartMetadata::from_product(&detail);
+
+        let downloads = self.download_cad(product, output_dir, &["step"], false).await?;
+        let step = downloads.iter().find(|result| result.is_success());
+        metadata.step_file = step.map(|result| result.filename.clone());
+
+        let folder = self.get_output_path(output_dir, product, "cad");
+        fs::create_dir_all(&folder).await?;
+        let sidecar = folder.join(format!("{}.json", detail.part_number));
+        fs::write(&sidecar, serde_json::to_string_pretty(&metadata)?).await?;
+
+        let mut library_files = Vec::new();
+        if let Some(library) = library {
+            let library_folder = library_folder(library, &detail);
+            fs::create_dir_all(&library_folder).await?;
+            let files = step.map(|step| step.file_path.clone()).into_iter().chain([sidecar.clone()]);
+            for file in files {
+                let Some(name) = file.file_name() else { continue };
+                let target = library_folder.join(name);
+                fs::copy(&file, &target).await?;
+                library_files.push(target);
+            }
+        }
+
+        Ok(FreecadExport { metadata, downloads, sidecar, library_files })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{MockApi, PART_NUMBER, STEP_FILE};
+
+    #[tokio::test]
+    async fn test_export_with_library_copy() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        let library = api.data_dir().join("freecad-parts");
+
+        let export = client.export_freecad(PART_NUMBER, None, Some(&library)).await.unwrap();
+        assert_eq!(export.metadata.material.as_deref(), Some("Alloy Steel"));
+        assert_eq!(export.metadata.step_file.as_deref(), Some("91290A115.step"));
+
+        let sidecar: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&export.sidecar).unwrap()).unwrap();
+        assert_eq!(sidecar["url"], "https://www.mcmaster.com/91290A115/");
+        assert_eq!(sidecar["specifications"]["Thread Size"], "M3");
+
+        let folder = library.join("McMaster-Carr/Screws/Black-Oxide Alloy Steel Socket Head Screws");
+        assert_eq!(export.library_files, vec![folder.join("91290A115.step"), folder.join("91290A115.json")]);
+        assert_eq!(std::fs::read(folder.join("91290A115.step")).unwrap(), STEP_FILE);
+    }
+}
@@ -0,0 +1,116 @@
+//! KiCad symbol fields and 3D models
+
+use crate::client::downloads::DownloadResult;
+use crate::models::product::ProductDetail;
+use crate::utils::error::Result;
+use serde::Serialize;
+use std::path::Path;
+
+/// Symbol fields for a McMaster-Carr part, ready to paste into a KiCad symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KicadFields {
+    pub mpn: String,
+    pub manufacturer: String,
+    pub description: String,
+    pub value: String,
+    pub datasheet: String, // Public product page, which links to the datasheet
+    pub model: Option<String>, // 3D model path for the footprint, when the STEP file was downloaded
+}
+
+/// Fields for a part plus any STEP download made for it
+#[derive(Debug)]
+pub struct KicadExport {
+    pub fields: KicadFields,
+    pub downloads: Vec<DownloadResult>,
+}
+
+impl KicadFields {
+    /// Build fields from product details
+    pub fn from_product(detail: &ProductDetail) -> Self {
+        KicadFields {
+            mpn: detail.part_number.clone(),
+            manufacturer: "McMaster-Carr".to_string(),
+            description: format!("{} {}", detail.family_description, detail.detail_description).trim().to_string(),
+            value: detail.detail_description.clone(),
+            datasheet: format!("https://www.mcmaster.com/{}/", detail.part_number),
+            model: None,
+        }
+    }
+
+    /// Render as KiCad `(property ...)` entries, followed by a footprint `(model ...)` line if there is a model
+    pub fn to_sexpr(&self) -> String {
+        let mut lines = vec![
+            property("Value", &self.value),
+            property("Datasheet", &self.datasheet),
+            property("Description", &self.description),
+            property("Manufacturer", &self.manufacturer),
+            property("MPN", &self.mpn),
+        ];
+        if let Some(model) = &self.model {
+            lines.push(format!("(model \"{}\")", escape(model)));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Path KiCad should use for a model saved in `models_dir`, relative to the project when possible
+pub fn model_reference(models_dir: &Path, file_name: &str) -> String {
+    let path = models_dir.join(file_name);
+    if path.is_relative() {
+        format!("${{KIPRJMOD}}/{}", path.to_string_lossy().replace('\\', "/"))
+    } else {
+        path.to_string_lossy().to_string()
+    }
+}
+
+fn property(name: &str, value: &str) -> String {
+    format!("(property \"{}\" \"{}\")", name, escape(value))
+}
+
+/// Escape a string for a KiCad S-expression
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// KiCad export methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Build KiCad fields for a part, downloading its STEP model into `models_dir` if given
+    pub async fn export_kicad(&self, product: &str, models_dir: Option<&Path>) -> Result<KicadExport> {
+        let detail = self.get_product(product).await?;
+        let mut fields = KicadFields::from_product(&detail);
+        let mut downloads = Vec::new();
+
+        if let Some(models_dir) = models_dir {
+            downloads = self.download_cad(product, Some(&models_dir.to_string_lossy()), &["step"], false).await?;
+            fields.model = downloads.iter()
+                .find(|result| result.is_success())
+                .map(|result| model_reference(models_dir, &result.filename));
+        }
+
+        Ok(KicadExport { fields, downloads })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kicad_fields() {
+        let mut fields = KicadFields {
+            mpn: "91290A115".to_string(),
+            manufacturer: "McMaster-Carr".to_string(),
+            description: "Socket Head Screw M3 x 0.5 mm Thread, 10 mm Long".to_string(),
+            value: "M3 x 0.5 mm Thread, 10 mm \"Long\"".to_string(),
+            datasheet: "https://www.mcmaster.com/91290A115/".to_string(),
+            model: None,
+        };
+        fields.model = Some(model_reference(Path::new("3dmodels"), "91290A115.STEP"));
+
+        let sexpr = fields.to_sexpr();
+        assert!(sexpr.starts_with("(property \"Value\" \"M3 x 0.5 mm Thread, 10 mm \\\"Long\\\"\")"));
+        assert!(sexpr.contains("(property \"MPN\" \"91290A115\")"));
+        assert!(sexpr.ends_with("(model \"${KIPRJMOD}/3dmodels/91290A115.STEP\")"));
+        assert_eq!(model_reference(Path::new("/lib/models"), "a.step"), "/lib/models/a.step");
+    }
+}
@@ -0,0 +1,353 @@
+//! Bin labels for thermal printers: ZPL for Zebra printers, or PNG and PDF
+//!
+//! Labels are 2 x 1 in at 203 dpi, the common Zebra desktop size: a QR code of the part
+//! number on the left, and the part number, description and key specifications beside it.
+
+use crate::models::product::ProductDetail;
+use crate::utils::error::{ClientError, Result};
+use crate::utils::qr::encode_png;
+use clap::ValueEnum;
+use embedded_graphics::mono_font::iso_8859_1::{FONT_10X20, FONT_7X13};
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::text::{Baseline, Text};
+use qrcode::{Color, QrCode};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+const DPI: u32 = 203;
+const WIDTH: u32 = 406; // Dots
+const HEIGHT: u32 = 203;
+const MARGIN: u32 = 12;
+const MAX_QR_SIZE: u32 = 120;
+
+/// Attribute words for the specifications worth printing on a bin label
+const LABEL_ATTRIBUTE_WORDS: &[&str] = &["thread", "length", "diameter", "size", "material"];
+const MAX_LABEL_SPECS: usize = 3;
+
+/// File format for `mmc label`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LabelFormat {
+    Zpl,
+    Png,
+    Pdf,
+}
+
+impl LabelFormat {
+    /// File extension for the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            LabelFormat::Zpl => "zpl",
+            LabelFormat::Png => "png",
+            LabelFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// What goes on a part's bin label
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Label {
+    pub part_number: String,
+    pub description: String,
+    pub specifications: Vec<(String, String)>, // Key specifications, at most MAX_LABEL_SPECS
+}
+
+/// A line of text placed on the label, in dots from the top left
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TextLine {
+    text: String,
+    x: u32,
+    y: u32,
+    large: bool,
+}
+
+impl TextLine {
+    /// Cap height in dots, matching the PNG fonts
+    fn height(&self) -> u32 {
+        if self.large { 20 } else { 13 }
+    }
+}
+
+/// Label contents positioned for printing
+struct Layout {
+    qr: QrCode,
+    qr_scale: u32, // Dots per QR module
+    lines: Vec<TextLine>,
+}
+
+impl Label {
+    /// Build a label from product details
+    pub fn from_product(detail: &ProductDetail) -> Self {
+        let has_word = |attribute: &str| {
+            let attribute = attribute.to_lowercase();
+            LABEL_ATTRIBUTE_WORDS.iter().any(|word| attribute.contains(word))
+        };
+        let mut specs: Vec<_> = detail.specifications.iter().filter(|spec| has_word(&spec.attribute)).collect();
+        if specs.is_empty() {
+            specs = detail.specifications.iter().collect();
+        }
+        Label {
+            part_number: detail.part_number.clone(),
+            description: detail.detail_description.clone(),
+            specifications: specs.into_iter()
+                .take(MAX_LABEL_SPECS)
+                .map(|spec| (spec.attribute.clone(), spec.values.join(", ")))
+                .collect(),
+        }
+    }
+
+    /// Render the label in `format`
+    pub fn render(&self, format: LabelFormat) -> Result<Vec<u8>> {
+        let layout = self.layout()?;
+        match format {
+            LabelFormat::Zpl => Ok(self.to_zpl(&layout).into_bytes()),
+            LabelFormat::Png => to_png(&layout),
+            LabelFormat::Pdf => to_pdf(&layout),
+        }
+    }
+
+    fn layout(&self) -> Result<Layout> {
+        let qr = QrCode::new(self.part_number.as_bytes())
+            .map_err(|e| ClientError::Generic(format!("Could not encode QR code: {}", e)))?;
+        let modules = qr.width() as u32;
+        let qr_scale = (MAX_QR_SIZE.min(HEIGHT - 2 * MARGIN) / modules).max(1);
+
+        let x = 2 * MARGIN + modules * qr_scale;
+        let small_chars = ((WIDTH - MARGIN - x) / 7) as usize;
+        let large_chars = ((WIDTH - MARGIN - x) / 10) as usize;
+        let mut lines = vec![TextLine { text: truncate(&self.part_number, large_chars), x, y: MARGIN, large: true }];
+        let mut y = MARGIN + 30;
+        let small = wrap(&self.description, small_chars, 2).into_iter()
+            .chain(std::iter::once(String::new()))
+            .chain(self.specifications.iter().map(|(attribute, value)| truncate(&format!("{}: {}", attribute, value), small_chars)));
+        for text in small {
+            if y + 13 > HEIGHT - MARGIN {
+                break;
+            }
+            if !text.is_empty() {
+                lines.push(TextLine { text, x, y, large: false });
+            }
+            y += 17;
+        }
+        Ok(Layout { qr, qr_scale, lines })
+    }
+
+    fn to_zpl(&self, layout: &Layout) -> String {
+        let mut zpl = format!("^XA\n^CI28\n^PW{}\n^LL{}\n", WIDTH, HEIGHT);
+        let _ = writeln!(zpl, "^FO{},{}^BQN,2,{}^FDQA,{}^FS", MARGIN, MARGIN, layout.qr_scale.min(10), zpl_text(&self.part_number));
+        for line in &layout.lines {
+            let height = line.height() + if line.large { 4 } else { 2 };
+            let _ = writeln!(zpl, "^FO{},{}^A0N,{},{}^FD{}^FS", line.x, line.y, height, height * 3 / 4, zpl_text(&line.text));
+        }
+        zpl.push_str("^XZ\n");
+        zpl
+    }
+}
+
+/// Strip ZPL's command and control prefixes from field data
+fn zpl_text(text: &str) -> String {
+    text.replace(['^', '~'], " ")
+}
+
+/// Cut text to `max` characters, ending in "..." when shortened
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max.saturating_sub(3)).collect();
+    format!("{}...", kept.trim_end())
+}
+
+/// Word-wrap text into at most `max_lines` lines of `width` characters
+fn wrap(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut words = text.split_whitespace();
+    while let Some(word) = words.next() {
+        let fits = lines.last().is_some_and(|line| line.chars().count() + 1 + word.chars().count() <= width);
+        if fits {
+            let line = lines.last_mut().expect("checked above");
+            line.push(' ');
+            line.push_str(word);
+        } else if lines.len() == max_lines {
+            let rest: Vec<&str> = std::iter::once(word).chain(words.by_ref()).collect();
+            let last = lines.last_mut().expect("max_lines is at least 1");
+            *last = truncate(&format!("{} {}", last, rest.join(" ")), width);
+            break;
+        } else {
+            lines.push(truncate(word, width));
+        }
+    }
+    lines
+}
+
+/// A 1-bit drawing surface for embedded-graphics, stored as 8-bit gray for PNG
+struct Canvas {
+    pixels: Vec<u8>,
+}
+
+impl OriginDimensions for Canvas {
+    fn size(&self) -> Size {
+        Size::new(WIDTH, HEIGHT)
+    }
+}
+
+impl DrawTarget for Canvas {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I: IntoIterator<Item = Pixel<BinaryColor>>>(&mut self, pixels: I) -> std::result::Result<(), Infallible> {
+        for Pixel(point, color) in pixels {
+            if let (Ok(x @ 0..WIDTH), Ok(y @ 0..HEIGHT)) = (u32::try_from(point.x), u32::try_from(point.y)) {
+                self.pixels[(y * WIDTH + x) as usize] = if color.is_on() { 0 } else { 255 };
+            }
+        }
+        Ok(())
+    }
+}
+
+fn to_png(layout: &Layout) -> Result<Vec<u8>> {
+    let mut canvas = Canvas { pixels: vec![255; (WIDTH * HEIGHT) as usize] };
+    let modules = layout.qr.width() as u32;
+    for (index, color) in layout.qr.to_colors().into_iter().enumerate() {
+        if color == Color::Dark {
+            let (column, row) = (index as u32 % modules, index as u32 / modules);
+            for dy in 0..layout.qr_scale {
+                let y = MARGIN + row * layout.qr_scale + dy;
+                let start = (y * WIDTH + MARGIN + column * layout.qr_scale) as usize;
+                canvas.pixels[start..start + layout.qr_scale as usize].fill(0);
+            }
+        }
+    }
+    for line in &layout.lines {
+        let font = if line.large { &FONT_10X20 } else { &FONT_7X13 };
+        let style = MonoTextStyle::new(font, BinaryColor::On);
+        let _ = Text::with_baseline(&line.text, Point::new(line.x as i32, line.y as i32), style, Baseline::Top).draw(&mut canvas);
+    }
+
+    encode_png(WIDTH, HEIGHT, &canvas.pixels, Some(DPI))
+}
+
+fn to_pdf(layout: &Layout) -> Result<Vec<u8>> {
+    use lopdf::content::{Content, Operation};
+    use lopdf::{dictionary, Document, Object, Stream};
+
+    let scale = 72.0 / f64::from(DPI); // Points per dot
+    let (width, height) = (f64::from(WIDTH) * scale, f64::from(HEIGHT) * scale);
+    let mut operations = Vec::new();
+
+    let modules = layout.qr.width() as u32;
+    let module = f64::from(layout.qr_scale) * scale;
+    for (index, color) in layout.qr.to_colors().into_iter().enumerate() {
+        if color == Color::Dark {
+            let (column, row) = (index as u32 % modules, index as u32 / modules);
+            let x = f64::from(MARGIN + column * layout.qr_scale) * scale;
+            let y = height - f64::from(MARGIN + (row + 1) * layout.qr_scale) * scale;
+            operations.push(Operation::new("re", vec![x.into(), y.into(), module.into(), module.into()]));
+        }
+    }
+    operations.push(Operation::new("f", vec![]));
+
+    for line in &layout.lines {
+        let size = f64::from(line.height()) * scale * 1.3;
+        let baseline = height - f64::from(line.y + line.height()) * scale;
+        // Helvetica with WinAnsiEncoding covers Latin-1; anything else prints as '?'
+        let text: Vec<u8> = line.text.chars().map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?')).collect();
+        operations.extend([
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["F1".into(), size.into()]),
+            Operation::new("Td", vec![(f64::from(line.x) * scale).into(), baseline.into()]),
+            Operation::new("Tj", vec![Object::string_literal(text)]),
+            Operation::new("ET", vec![]),
+        ]);
+    }
+
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    });
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => font_id },
+    });
+    let content = Content { operations }.encode().map_err(pdf_error)?;
+    let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+    let page_id = doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "Contents" => content_id,
+    });
+    doc.objects.insert(pages_id, Object::Dictionary(dictionary! {
+        "Type" => "Pages",
+        "Kids" => vec![page_id.into()],
+        "Count" => 1,
+        "Resources" => resources_id,
+        "MediaBox" => vec![0.into(), 0.into(), width.into(), height.into()],
+    }));
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+
+    let mut pdf = Vec::new();
+    doc.save_to(&mut pdf).map_err(pdf_error)?;
+    Ok(pdf)
+}
+
+fn pdf_error(error: impl std::fmt::Display) -> ClientError {
+    ClientError::Generic(format!("Could not write PDF: {}", error))
+}
+
+/// Label methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Render a part's bin label and write it to `output`, or `{part}.{ext}` in the part's labels folder
+    pub async fn write_label(&self, product: &str, format: LabelFormat, output: Option<&Path>) -> Result<PathBuf> {
+        let detail = self.get_product(product).await?;
+        let label = Label::from_product(&detail).render(format)?;
+        let path = match output {
+            Some(path) => path.to_path_buf(),
+            None => self.get_output_path(None, product, "labels").join(format!("{}.{}", detail.part_number, format.extension())),
+        };
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, label).await?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{product_fixture, PART_NUMBER};
+
+    #[test]
+    fn test_render_label() {
+        let detail: ProductDetail = serde_json::from_value(product_fixture()).unwrap();
+        let label = Label::from_product(&detail);
+        assert_eq!(label.specifications, vec![
+            ("Thread Size".to_string(), "M3".to_string()),
+            ("Material".to_string(), "Alloy Steel".to_string()),
+        ]);
+
+        let zpl = String::from_utf8(label.render(LabelFormat::Zpl).unwrap()).unwrap();
+        assert!(zpl.starts_with("^XA\n") && zpl.ends_with("^XZ\n"));
+        assert!(zpl.contains(&format!("^FDQA,{}^FS", PART_NUMBER)));
+        assert!(zpl.contains("^FDThread Size: M3^FS"));
+
+        let png = label.render(LabelFormat::Png).unwrap();
+        let info = png::Decoder::new(png.as_slice()).read_info().unwrap().info().clone();
+        assert_eq!((info.width, info.height), (WIDTH, HEIGHT));
+
+        let pdf = lopdf::Document::load_mem(&label.render(LabelFormat::Pdf).unwrap()).unwrap();
+        assert_eq!(pdf.get_pages().len(), 1);
+
+        assert_eq!(wrap("Black-Oxide Alloy Steel Socket Head Screw", 16, 2), vec!["Black-Oxide", "Alloy Steel S..."]);
+    }
+}
@@ -0,0 +1,17 @@
+//! CAD and EDA tool integrations
+//!
+//! This module turns McMaster-Carr product data and downloads into the
+//! formats other design tools expect, writes data sheets, and compares prices with
+//! other suppliers.
+
+pub mod dxf;
+pub mod freecad;
+pub mod kicad;
+pub mod label;
+pub mod onshape;
+pub mod package;
+pub mod report;
+pub mod site;
+pub mod solidworks;
+pub mod step;
+pub mod vendors;
@@ -0,0 +1,123 @@
+//! Import STEP models into Onshape documents
+
+use crate::client::downloads::DownloadResult;
+use crate::config::settings::OnshapeConfig;
+use crate::utils::error::{ClientError, Result};
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+use tokio::fs;
+
+/// Document and workspace to import into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnshapeTarget {
+    pub document_id: String,
+    pub workspace_id: String,
+}
+
+impl OnshapeTarget {
+    /// Build a target from IDs, or from a document URL like
+    /// `https://cad.onshape.com/documents/{did}/w/{wid}/e/{eid}` that includes the workspace
+    pub fn parse(document: &str, workspace: Option<&str>) -> Result<Self> {
+        let segments: Vec<&str> = document.trim_end_matches('/').split('/').collect();
+        let from_url = |marker: &str| {
+            segments.iter()
+                .position(|segment| *segment == marker)
+                .and_then(|index| segments.get(index + 1))
+                .map(|id| id.to_string())
+        };
+
+        let (document_id, url_workspace) = match from_url("documents") {
+            Some(document_id) => (document_id, from_url("w")),
+            None => (document.to_string(), None),
+        };
+
+        let workspace_id = workspace.map(str::to_string).or(url_workspace).ok_or_else(|| {
+            ClientError::Config("Onshape workspace not given: pass --workspace or a document URL containing /w/{workspace}".to_string())
+        })?;
+
+        Ok(OnshapeTarget { document_id, workspace_id })
+    }
+}
+
+/// Result of importing a part into Onshape
+#[derive(Debug)]
+pub struct OnshapeImport {
+    pub part_number: String,
+    pub element_id: String,
+    pub name: String,
+    pub downloads: Vec<DownloadResult>,
+}
+
+/// Element created by a blob upload
+#[derive(Debug, Deserialize)]
+struct BlobElement {
+    id: String,
+    name: String,
+}
+
+/// Onshape import methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Download a part's STEP file and import it into an Onshape workspace, named by part number
+    pub async fn import_to_onshape(&self, product: &str, onshape: &OnshapeConfig, target: &OnshapeTarget) -> Result<OnshapeImport> {
+        self.require_online()?;
+        let part_number = product.trim().to_uppercase();
+        let downloads = self.download_cad(product, None, &["step"], false).await?;
+        let step = downloads.iter()
+            .find(|result| result.is_success())
+            .ok_or_else(|| ClientError::NotFound(format!("No STEP file could be downloaded for {}", part_number)))?;
+
+        let data = fs::read(&step.file_path).await?;
+        let filename = format!("{}.step", part_number);
+        let form = Form::new()
+            .part("file", Part::bytes(data).file_name(filename.clone()))
+            .text("encodedFilename", filename)
+            .text("translate", "true")
+            .text("flattenAssemblies", "false");
+
+        let url = format!(
+            "{}/api/v6/blobelements/d/{}/w/{}",
+            onshape.base_url.trim_end_matches('/'),
+            target.document_id,
+            target.workspace_id
+        );
+        // A plain client: the McMaster-Carr client certificate isn't meant for Onshape
+        let response = reqwest::Client::new()
+            .post(&url)
+            .basic_auth(&onshape.access_key, Some(&onshape.secret_key))
+            .multipart(form)
+            .send()
+            .await?;
+
+        match response.status().as_u16() {
+            200..=299 => {}
+            // Not the McMaster-Carr token, so don't suggest `mmc login`
+            401 | 403 => return Err(ClientError::Auth("Onshape rejected the API keys in config.toml".to_string())),
+            _ => return Err(ClientError::from_http(response, "Onshape import failed").await),
+        }
+
+        let element: BlobElement = response.json().await?;
+        Ok(OnshapeImport {
+            part_number,
+            element_id: element.id,
+            name: element.name,
+            downloads,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        let target = OnshapeTarget::parse("https://cad.onshape.com/documents/abc123/w/def456/e/789", None).unwrap();
+        assert_eq!(target, OnshapeTarget { document_id: "abc123".to_string(), workspace_id: "def456".to_string() });
+
+        let target = OnshapeTarget::parse("abc123", Some("ws1")).unwrap();
+        assert_eq!(target.document_id, "abc123");
+        assert_eq!(target.workspace_id, "ws1");
+
+        assert!(matches!(OnshapeTarget::parse("abc123", None), Err(ClientError::Config(_))));
+    }
+}
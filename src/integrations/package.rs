@@ -0,0 +1,193 @@
+//! Zip packages of STEP models, datasheets and a metadata manifest
+//!
+//! Archives are reproducible: entries are sorted, timestamps and permissions are fixed,
+//! and the manifest holds nothing that changes between runs, so packaging the same
+//! files twice gives byte-identical zips that vault systems won't see as new revisions.
+
+use crate::client::downloads::{BulkDownloadReport, DownloadResult};
+use crate::integrations::freecad::PartMetadata;
+use crate::integrations::solidworks::folder_name;
+use crate::utils::error::{ClientError, Result};
+use crate::utils::lock::write_atomic;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Archive entries as (path in the archive, contents)
+type Entries = Vec<(String, Vec<u8>)>;
+
+/// `manifest.json` at the root of every package
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageManifest {
+    pub parts: Vec<PackagedPart>,
+}
+
+/// One part in a package and the files stored for it under `{part}/`
+#[derive(Debug, Clone, Serialize)]
+pub struct PackagedPart {
+    #[serde(flatten)]
+    pub metadata: PartMetadata,
+    pub files: Vec<PackagedFile>,
+}
+
+/// A file in the archive
+#[derive(Debug, Clone, Serialize)]
+pub struct PackagedFile {
+    pub path: String, // Path inside the archive
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// Archives written by `mmc package` and the downloads behind them
+#[derive(Debug, Default)]
+pub struct PackageReport {
+    pub downloads: BulkDownloadReport,
+    pub archives: Vec<PathBuf>,
+}
+
+/// Packaging methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Package parts as `{part}.zip` each, or together as `{bundle}.zip`, in `output_dir`
+    ///
+    /// Each part's STEP model and datasheets are downloaded (or reused from earlier downloads)
+    /// first. A part that can't be fetched is reported as a failure and left out.
+    pub async fn package_parts(&self, parts: &[String], output_dir: Option<&Path>, bundle: Option<&str>) -> Result<PackageReport> {
+        let output_dir = output_dir.map(Path::to_path_buf).unwrap_or_else(|| self.download_root().join("packages"));
+        std::fs::create_dir_all(&output_dir)?;
+
+        let mut report = PackageReport {
+            downloads: BulkDownloadReport { total_parts: parts.len(), ..Default::default() },
+            archives: Vec::new(),
+        };
+        let mut bundled = Vec::new();
+
+        for part in parts {
+            let (packaged, entries) = match self.collect_package_files(part, &mut report.downloads).await {
+                Ok(collected) => collected,
+                Err(e) => {
+                    report.downloads.failures.push((part.clone(), e.to_string()));
+                    continue;
+                }
+            };
+            if bundle.is_some() {
+                bundled.push((packaged, entries));
+            } else {
+                let path = output_dir.join(format!("{}.zip", folder_name(&packaged.metadata.part_number)));
+                write_package(&path, vec![(packaged, entries)])?;
+                report.archives.push(path);
+            }
+        }
+
+        if let Some(bundle) = bundle.filter(|_| !bundled.is_empty()) {
+            let path = output_dir.join(format!("{}.zip", folder_name(bundle)));
+            write_package(&path, bundled)?;
+            report.archives.push(path);
+        }
+        Ok(report)
+    }
+
+    /// Download a part's STEP model and datasheets and read them in as archive entries
+    async fn collect_package_files(&self, part: &str, downloads: &mut BulkDownloadReport) -> Result<(PackagedPart, Entries)> {
+        let detail = self.get_product(part).await?;
+        let folder = folder_name(&detail.part_number);
+
+        let step = self.download_cad(part, None, &["step"], false).await?;
+        let datasheets = self.download_datasheets(part, None).await?;
+        if step.is_empty() && datasheets.is_empty() {
+            downloads.no_files.push(part.to_string());
+        }
+
+        let mut files = Vec::new();
+        let mut entries = Vec::new();
+        let sources = step.iter().map(|result| (result, folder.clone()))
+            .chain(datasheets.iter().map(|result| (result, format!("{}/datasheets", folder))));
+        for (result, dir) in sources {
+            if !result.is_success() {
+                let error = result.error.clone().unwrap_or_default();
+                downloads.failures.push((part.to_string(), format!("{}: {}", result.filename, error)));
+                continue;
+            }
+            let contents = tokio::fs::read(&result.file_path).await?;
+            let path = format!("{}/{}", dir, result.filename);
+            files.push(PackagedFile {
+                path: path.clone(),
+                bytes: contents.len() as u64,
+                sha256: hex::encode(Sha256::digest(&contents)),
+            });
+            entries.push((path, contents));
+        }
+        downloads.results.extend(step.into_iter().chain(datasheets).collect::<Vec<DownloadResult>>());
+
+        let packaged = PackagedPart { metadata: PartMetadata::from_product(&detail), files };
+        Ok((packaged, entries))
+    }
+}
+
+/// Write parts and their files to a zip with a root `manifest.json`, replacing any old archive
+fn write_package(path: &Path, parts: Vec<(PackagedPart, Entries)>) -> Result<()> {
+    let mut manifest = PackageManifest { parts: Vec::new() };
+    let mut entries = Vec::new();
+    for (packaged, files) in parts {
+        manifest.parts.push(packaged);
+        entries.extend(files);
+    }
+    manifest.parts.sort_by(|a, b| a.metadata.part_number.cmp(&b.metadata.part_number));
+    entries.push(("manifest.json".to_string(), serde_json::to_vec_pretty(&manifest)?));
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(zip::DateTime::default())
+        .unix_permissions(0o644);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in entries {
+        zip.start_file(name, options).map_err(zip_error)?;
+        zip.write_all(&contents)?;
+    }
+    let archive = zip.finish().map_err(zip_error)?.into_inner();
+    write_atomic(path, &archive)?;
+    Ok(())
+}
+
+fn zip_error(error: zip::result::ZipError) -> ClientError {
+    ClientError::Generic(format!("Could not write zip archive: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{MockApi, PART_NUMBER, STEP_FILE};
+    use std::io::Read;
+
+    #[tokio::test]
+    async fn test_package_is_reproducible() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        let output = api.data_dir().join("packages");
+        let parts = vec![PART_NUMBER.to_string()];
+
+        let report = client.package_parts(&parts, Some(&output), None).await.unwrap();
+        assert_eq!(report.archives, vec![output.join("91290A115.zip")]);
+        let first = std::fs::read(&report.archives[0]).unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(first.clone())).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert_eq!(names, vec!["91290A115/91290A115.step", "manifest.json"]);
+        let mut step = Vec::new();
+        archive.by_name("91290A115/91290A115.step").unwrap().read_to_end(&mut step).unwrap();
+        assert_eq!(step, STEP_FILE);
+
+        let mut manifest = String::new();
+        archive.by_name("manifest.json").unwrap().read_to_string(&mut manifest).unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+        assert_eq!(manifest["parts"][0]["part_number"], PART_NUMBER);
+        assert_eq!(manifest["parts"][0]["files"][0]["bytes"], STEP_FILE.len());
+
+        let report = client.package_parts(&parts, Some(&output), Some("robot-v2")).await.unwrap();
+        assert_eq!(report.archives, vec![output.join("robot-v2.zip")]);
+        client.package_parts(&parts, Some(&output), None).await.unwrap();
+        assert_eq!(std::fs::read(output.join("91290A115.zip")).unwrap(), first);
+    }
+}
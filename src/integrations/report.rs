@@ -0,0 +1,229 @@
+//! Markdown and HTML data sheets for wiki pages
+//!
+//! `mmc report` writes a section per part: description, the first downloaded image, a
+//! specification table, price breaks, and links to the CAD files and datasheets already
+//! in the part's download folders. Nothing is downloaded for a report, so files show up
+//! once `mmc image`, `mmc cad` or `mmc datasheet` has fetched them.
+
+use crate::models::product::{PriceInfo, ProductDetail};
+use crate::utils::error::Result;
+use clap::ValueEnum;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Just enough CSS for readable tables in a browser
+const STYLE: &str = "body{font-family:sans-serif;max-width:60em;margin:auto;padding:1em}\
+                     table{border-collapse:collapse}td,th{border:1px solid #ccc;padding:.2em .6em;text-align:left}\
+                     img{max-width:20em}";
+
+/// Document format for `mmc report`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+/// Everything a part's data sheet shows
+#[derive(Debug)]
+pub struct PartReport {
+    pub detail: ProductDetail,
+    pub prices: Vec<PriceInfo>,
+    pub images: Vec<PathBuf>,
+    pub cad_files: Vec<PathBuf>,
+    pub datasheets: Vec<PathBuf>,
+}
+
+impl PartReport {
+    fn product_url(&self) -> String {
+        format!("https://www.mcmaster.com/{}/", urlencoding::encode(&self.detail.part_number))
+    }
+
+    /// Family, category and status on one line
+    fn summary(&self) -> String {
+        [&self.detail.family_description, &self.detail.product_category, &self.detail.product_status]
+            .into_iter()
+            .filter(|value| !value.is_empty())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+
+    fn price_cells(price: &PriceInfo) -> [String; 3] {
+        [format!("{}+", price.minimum_quantity), format!("{:.2} {}", price.amount, price.currency()), price.unit_of_measure.clone()]
+    }
+
+    /// Downloaded files as (kind, path)
+    fn files(&self) -> impl Iterator<Item = (&'static str, &PathBuf)> {
+        self.cad_files.iter().map(|path| ("CAD", path))
+            .chain(self.datasheets.iter().map(|path| ("Datasheet", path)))
+    }
+
+    fn to_markdown(&self) -> String {
+        let detail = &self.detail;
+        let mut text = String::new();
+        let _ = writeln!(text, "## {}: {}\n", detail.part_number, markdown_escape(&detail.detail_description));
+        if let Some(image) = self.images.first() {
+            let _ = writeln!(text, "![{}]({})\n", detail.part_number, file_link(image));
+        }
+        let _ = writeln!(text, "{}  ", markdown_escape(&self.summary()));
+        if let Some(item_number) = &detail.item_number {
+            let _ = writeln!(text, "Item number: {}  ", markdown_escape(item_number));
+        }
+        let _ = writeln!(text, "[McMaster-Carr product page]({})\n", self.product_url());
+
+        if !detail.specifications.is_empty() {
+            text.push_str("### Specifications\n\n| Attribute | Value |\n| --- | --- |\n");
+            for spec in &detail.specifications {
+                let _ = writeln!(text, "| {} | {} |", markdown_escape(&spec.attribute), markdown_escape(&spec.values.join(", ")));
+            }
+            text.push('\n');
+        }
+        if !self.prices.is_empty() {
+            text.push_str("### Price Breaks\n\n| Quantity | Price | Unit |\n| --- | --- | --- |\n");
+            for price in &self.prices {
+                let [quantity, amount, unit] = Self::price_cells(price);
+                let _ = writeln!(text, "| {} | {} | {} |", quantity, amount, markdown_escape(&unit));
+            }
+            text.push('\n');
+        }
+        if self.files().next().is_some() {
+            text.push_str("### Files\n\n");
+            for (kind, path) in self.files() {
+                let _ = writeln!(text, "- {}: [{}]({})", kind, file_name(path), file_link(path));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    pub(crate) fn to_html(&self) -> String {
+        let detail = &self.detail;
+        let mut html = String::new();
+        let _ = writeln!(html, "<section id=\"{}\">", html_escape(&detail.part_number));
+        let _ = writeln!(html, "<h2>{}: {}</h2>", html_escape(&detail.part_number), html_escape(&detail.detail_description));
+        if let Some(image) = self.images.first() {
+            let _ = writeln!(html, "<img src=\"{}\" alt=\"{}\">", html_escape(&file_link(image)), html_escape(&detail.part_number));
+        }
+        let _ = writeln!(html, "<p>{}", html_escape(&self.summary()));
+        if let Some(item_number) = &detail.item_number {
+            let _ = write!(html, "<br>Item number: {}", html_escape(item_number));
+        }
+        let _ = writeln!(html, "<br><a href=\"{}\">McMaster-Carr product page</a></p>", html_escape(&self.product_url()));
+
+        if !detail.specifications.is_empty() {
+            html.push_str("<h3>Specifications</h3>\n<table>\n<tr><th>Attribute</th><th>Value</th></tr>\n");
+            for spec in &detail.specifications {
+                let _ = writeln!(html, "<tr><td>{}</td><td>{}</td></tr>", html_escape(&spec.attribute), html_escape(&spec.values.join(", ")));
+            }
+            html.push_str("</table>\n");
+        }
+        if !self.prices.is_empty() {
+            html.push_str("<h3>Price Breaks</h3>\n<table>\n<tr><th>Quantity</th><th>Price</th><th>Unit</th></tr>\n");
+            for price in &self.prices {
+                let [quantity, amount, unit] = Self::price_cells(price);
+                let _ = writeln!(html, "<tr><td>{}</td><td>{}</td><td>{}</td></tr>", quantity, html_escape(&amount), html_escape(&unit));
+            }
+            html.push_str("</table>\n");
+        }
+        if self.files().next().is_some() {
+            html.push_str("<h3>Files</h3>\n<ul>\n");
+            for (kind, path) in self.files() {
+                let _ = writeln!(html, "<li>{}: <a href=\"{}\">{}</a></li>", kind, html_escape(&file_link(path)), html_escape(&file_name(path)));
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str("</section>\n");
+        html
+    }
+}
+
+/// Render data sheets for several parts as one document
+pub fn render(reports: &[PartReport], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Markdown => reports.iter().map(PartReport::to_markdown).collect::<Vec<_>>().join("\n"),
+        ReportFormat::Html => {
+            let title = match reports {
+                [report] => format!("{} data sheet", report.detail.part_number),
+                _ => "Part data sheets".to_string(),
+            };
+            html_page(&title, &reports.iter().map(PartReport::to_html).collect::<String>())
+        }
+    }
+}
+
+/// A standalone HTML page around `body`
+pub(crate) fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        html_escape(title),
+        STYLE,
+        body
+    )
+}
+
+fn markdown_escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+pub(crate) fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// A local file as a link target, with the spaces that would end a Markdown link encoded
+fn file_link(path: &Path) -> String {
+    path.display().to_string().replace('\\', "/").replace(' ', "%20")
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+/// Report methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Gather a part's details, prices and downloaded files for its data sheet
+    pub async fn part_report(&self, product: &str) -> Result<PartReport> {
+        let detail = self.get_product(product).await?;
+        let prices = self.get_price(product).await?;
+        let folder = |category| self.downloaded_files(&detail.part_number, category);
+        Ok(PartReport {
+            images: folder("images")?,
+            cad_files: folder("cad")?,
+            datasheets: folder("datasheets")?,
+            prices,
+            detail,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::manifest::MANIFEST_FILENAME;
+    use crate::test_support::{MockApi, PART_NUMBER};
+
+    #[tokio::test]
+    async fn test_render_report() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        let cad = client.get_output_path(None, PART_NUMBER, "cad");
+        std::fs::create_dir_all(&cad).unwrap();
+        for name in ["91290A115.step", "91290A115.dxf.part", MANIFEST_FILENAME] {
+            std::fs::write(cad.join(name), "").unwrap();
+        }
+
+        let report = client.part_report(PART_NUMBER).await.unwrap();
+        assert_eq!(report.cad_files, vec![cad.join("91290A115.step")]);
+        assert!(report.images.is_empty());
+
+        let markdown = render(std::slice::from_ref(&report), ReportFormat::Markdown);
+        assert!(markdown.starts_with("## 91290A115: M3 x 0.5 mm Thread, 10 mm Long\n"));
+        assert!(markdown.contains("| Thread Size | M3 |\n"));
+        assert!(markdown.contains("| 10+ | 8.75 USD | Pack |\n"));
+        assert!(markdown.contains("- CAD: [91290A115.step]("));
+
+        let html = render(&[report], ReportFormat::Html);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<tr><td>Material</td><td>Alloy Steel</td></tr>"));
+        assert_eq!(html_escape("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+}
@@ -0,0 +1,143 @@
+//! Static HTML catalog of the tracked parts, for `mmc site build`
+//!
+//! The catalog is a folder that can be opened from a file share or served by any web
+//! server: `index.html` lists the parts by category and by tag, and `parts/{part}.html`
+//! holds each part's data sheet, as `mmc report` renders it. Each part's first downloaded
+//! image is copied into `images/` so the folder is self-contained; links to local CAD
+//! files and datasheets are left out, since they'd only work on this machine.
+
+use crate::integrations::report::{html_escape, html_page, PartReport};
+use crate::integrations::solidworks::folder_name;
+use crate::utils::error::{ClientError, Result};
+use crate::utils::lock::write_atomic;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// What `mmc site build` wrote
+#[derive(Debug, Default)]
+pub struct SiteReport {
+    pub dir: PathBuf,
+    pub pages: usize, // Part pages written
+    pub failures: Vec<(String, String)>, // (part, error) for parts left out
+}
+
+/// A part as listed on the index page
+struct IndexEntry {
+    part_number: String,
+    description: String,
+    page: String, // Link relative to the index
+}
+
+/// Catalog methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Render every tracked part, or those with `tag`, into a static catalog in `dir`
+    ///
+    /// A part whose details can't be fetched is reported as a failure and left out.
+    pub async fn build_site(&self, dir: &Path, tag: Option<&str>) -> Result<SiteReport> {
+        let parts = self.list_tracked_parts(tag)?;
+        std::fs::create_dir_all(dir.join("parts"))?;
+
+        let mut report = SiteReport { dir: dir.to_path_buf(), ..Default::default() };
+        let mut categories: BTreeMap<String, Vec<IndexEntry>> = BTreeMap::new();
+        let mut tags: BTreeMap<String, Vec<IndexEntry>> = BTreeMap::new();
+        for tracked in parts {
+            let mut part = match self.part_report(&tracked.part_number).await {
+                Ok(part) => part,
+                Err(e @ (ClientError::NotAuthenticated | ClientError::AuthExpired)) => return Err(e),
+                Err(e) => {
+                    report.failures.push((tracked.part_number, e.to_string()));
+                    continue;
+                }
+            };
+            let name = folder_name(&part.detail.part_number);
+            if let Some(image) = part.images.first() {
+                let extension = image.extension().map(|extension| extension.to_string_lossy().into_owned()).unwrap_or_else(|| "jpg".to_string());
+                let copied = format!("{}.{}", name, extension);
+                std::fs::create_dir_all(dir.join("images"))?;
+                std::fs::copy(image, dir.join("images").join(&copied))?;
+                part.images = vec![PathBuf::from(format!("../images/{}", copied))];
+            }
+            part.cad_files.clear();
+            part.datasheets.clear();
+            write_atomic(&dir.join("parts").join(format!("{}.html", name)), part_page(&part).as_bytes())?;
+            report.pages += 1;
+
+            let entry = || IndexEntry {
+                part_number: part.detail.part_number.clone(),
+                description: part.detail.detail_description.clone(),
+                page: format!("parts/{}.html", urlencoding::encode(&name)),
+            };
+            let category = [&part.detail.product_category, &part.detail.family_description]
+                .into_iter()
+                .find(|category| !category.is_empty())
+                .cloned()
+                .unwrap_or_else(|| "Uncategorized".to_string());
+            categories.entry(category).or_default().push(entry());
+            for tag in &tracked.tags {
+                tags.entry(tag.clone()).or_default().push(entry());
+            }
+        }
+
+        write_atomic(&dir.join("index.html"), index_page(&categories, &tags, report.pages).as_bytes())?;
+        Ok(report)
+    }
+}
+
+fn part_page(part: &PartReport) -> String {
+    let body = format!("<p><a href=\"../index.html\">All parts</a></p>\n{}", part.to_html());
+    html_page(&format!("{} {}", part.detail.part_number, part.detail.detail_description), &body)
+}
+
+fn index_page(categories: &BTreeMap<String, Vec<IndexEntry>>, tags: &BTreeMap<String, Vec<IndexEntry>>, count: usize) -> String {
+    let mut body = format!("<h1>Parts catalog</h1>\n<p>{} parts</p>\n", count);
+    for (heading, groups) in [("By category", categories), ("By tag", tags)] {
+        if groups.is_empty() {
+            continue;
+        }
+        let _ = writeln!(body, "<h2>{}</h2>", heading);
+        for (group, entries) in groups {
+            let _ = writeln!(body, "<h3>{}</h3>\n<ul>", html_escape(group));
+            for entry in entries {
+                let _ = writeln!(
+                    body,
+                    "<li><a href=\"{}\">{}</a> {}</li>",
+                    html_escape(&entry.page),
+                    html_escape(&entry.part_number),
+                    html_escape(&entry.description)
+                );
+            }
+            body.push_str("</ul>\n");
+        }
+    }
+    html_page("Parts catalog", &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{MockApi, PART_NUMBER};
+
+    #[tokio::test]
+    async fn test_build_site() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        client.subscriptions().add_part(PART_NUMBER).unwrap();
+        client.subscriptions().add_tags(PART_NUMBER, &["robot-v2".to_string()]).unwrap();
+        let images = client.get_output_path(None, PART_NUMBER, "images");
+        std::fs::create_dir_all(&images).unwrap();
+        std::fs::write(images.join("91290A115.jpg"), "jpeg").unwrap();
+
+        let dir = api.data_dir().join("site");
+        let report = client.build_site(&dir, None).await.unwrap();
+        assert_eq!((report.pages, report.failures.len()), (1, 0));
+
+        let index = std::fs::read_to_string(dir.join("index.html")).unwrap();
+        assert!(index.contains("<h3>Screws</h3>"));
+        assert!(index.contains("<h3>robot-v2</h3>"));
+        assert!(index.contains("<a href=\"parts/91290A115.html\">91290A115</a>"));
+        let page = std::fs::read_to_string(dir.join("parts/91290A115.html")).unwrap();
+        assert!(page.contains("<img src=\"../images/91290A115.jpg\""));
+        assert!(page.contains("<td>Thread Size</td><td>M3</td>"));
+        assert_eq!(std::fs::read(dir.join("images/91290A115.jpg")).unwrap(), b"jpeg");
+    }
+}
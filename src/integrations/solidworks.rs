@@ -0,0 +1,146 @@
+//! SolidWorks Design Library folders with custom property files
+
+use crate::client::downloads::BulkDownloadReport;
+use crate::models::product::ProductDetail;
+use crate::utils::error::Result;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Outcome of adding parts to a SolidWorks Design Library
+#[derive(Debug, Default)]
+pub struct SolidworksLibraryReport {
+    pub root: PathBuf,
+    pub downloads: BulkDownloadReport,
+    pub property_files: Vec<PathBuf>,
+}
+
+/// Design Library folder for a part: {root}/McMaster-Carr/{category}/{family}/
+pub fn library_folder(root: &Path, detail: &ProductDetail) -> PathBuf {
+    let mut folder = root.join("McMaster-Carr");
+    for name in [&detail.product_category, &detail.family_description] {
+        let name = folder_name(name);
+        if !name.is_empty() {
+            folder = folder.join(name);
+        }
+    }
+    folder
+}
+
+/// Custom properties as tab-separated name/value lines, for the SolidWorks property tab or a design table
+pub fn custom_properties(detail: &ProductDetail) -> String {
+    let mut properties = vec![
+        ("PartNo".to_string(), detail.part_number.clone()),
+        ("Description".to_string(), detail.detail_description.clone()),
+        ("Vendor".to_string(), "McMaster-Carr".to_string()),
+    ];
+    if let Some(material) = detail.specifications.iter().find(|spec| spec.attribute.eq_ignore_ascii_case("Material")) {
+        properties.push(("Material".to_string(), material.values.join(", ")));
+    }
+    for spec in &detail.specifications {
+        if !spec.attribute.eq_ignore_ascii_case("Material") {
+            properties.push((spec.attribute.clone(), spec.values.join(", ")));
+        }
+    }
+
+    let mut content = String::from("Property\tValue\n");
+    for (name, value) in properties {
+        content.push_str(&format!("{}\t{}\n", single_line(&name), single_line(&value)));
+    }
+    content
+}
+
+/// Make a folder name that's valid on Windows, where SolidWorks runs
+pub(crate) fn folder_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || c.is_control() { '-' } else { c })
+        .collect::<String>()
+        .trim_matches(|c: char| c == '.' || c.is_whitespace())
+        .to_string()
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\t', '\r', '\n'], " ")
+}
+
+/// SolidWorks library methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Download SolidWorks files for parts into a Design Library folder tree, with a property file per part
+    pub async fn build_solidworks_library(&self, parts: &[String], root: Option<&Path>) -> Result<SolidworksLibraryReport> {
+        let root = root.map(Path::to_path_buf).unwrap_or_else(|| self.download_root().join("solidworks-library"));
+        let mut report = SolidworksLibraryReport {
+            root: root.clone(),
+            downloads: BulkDownloadReport {
+                total_parts: parts.len(),
+                ..Default::default()
+            },
+            property_files: Vec::new(),
+        };
+
+        for part in parts {
+            let detail = match self.get_product(part).await {
+                Ok(detail) => detail,
+                Err(e) => {
+                    report.downloads.failures.push((part.clone(), e.to_string()));
+                    continue;
+                }
+            };
+
+            let folder = library_folder(&root, &detail);
+            let results = self.download_cad(part, Some(&folder.to_string_lossy()), &["solidworks"], false).await;
+            let results = match results {
+                Ok(results) if results.is_empty() => {
+                    report.downloads.no_files.push(part.clone());
+                    continue;
+                }
+                Ok(results) => results,
+                Err(e) => {
+                    report.downloads.failures.push((part.clone(), e.to_string()));
+                    continue;
+                }
+            };
+
+            for result in results.iter().filter(|result| !result.is_success()) {
+                let error = result.error.clone().unwrap_or_default();
+                report.downloads.failures.push((part.clone(), format!("{}: {}", result.filename, error)));
+            }
+            report.downloads.results.extend(results);
+
+            let properties_path = folder.join(format!("{}.txt", detail.part_number));
+            fs::write(&properties_path, custom_properties(&detail)).await?;
+            report.property_files.push(properties_path);
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::product::Specification;
+
+    #[test]
+    fn test_library_layout_and_properties() {
+        let detail = ProductDetail {
+            part_number: "91290A115".to_string(),
+            detail_description: "M3 x 0.5 mm Thread, 10 mm Long".to_string(),
+            family_description: "Black-Oxide Alloy Steel Socket Head Screws".to_string(),
+            product_category: "Screws/Bolts".to_string(),
+            product_status: "Active".to_string(),
+            specifications: vec![
+                Specification { attribute: "Thread Size".to_string(), values: vec!["M3".to_string()] },
+                Specification { attribute: "Material".to_string(), values: vec!["Alloy Steel".to_string()] },
+            ],
+            item_number: None,
+        };
+
+        assert_eq!(
+            library_folder(Path::new("/lib"), &detail),
+            PathBuf::from("/lib/McMaster-Carr/Screws-Bolts/Black-Oxide Alloy Steel Socket Head Screws")
+        );
+        assert_eq!(
+            custom_properties(&detail),
+            "Property\tValue\nPartNo\t91290A115\nDescription\tM3 x 0.5 mm Thread, 10 mm Long\nVendor\tMcMaster-Carr\nMaterial\tAlloy Steel\nThread Size\tM3\n"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Part metadata in STEP file headers
+//!
+//! CAD systems that show a STEP file's header name and description on import otherwise
+//! see whatever the exporter wrote, often a temp file name. `--embed-metadata` rewrites
+//! the FILE_NAME name and FILE_DESCRIPTION description of downloaded STEP files to the
+//! part number and description. Only the header changes; the model data is copied as is.
+
+use crate::utils::error::{ClientError, Result};
+use std::path::Path;
+
+/// What to write into a STEP header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepMetadata {
+    pub part_number: String,
+    pub description: String,
+}
+
+impl StepMetadata {
+    /// Name shown for the model, e.g. "91290A115 Socket Head Screw"
+    fn name(&self) -> String {
+        if self.description.is_empty() {
+            self.part_number.clone()
+        } else {
+            format!("{} {}", self.part_number, self.description)
+        }
+    }
+}
+
+/// Whether a path has a STEP extension
+pub fn is_step_file(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("step") || extension.eq_ignore_ascii_case("stp"))
+}
+
+/// Rewrite a STEP file's header in place
+pub fn embed_metadata_in_file(path: &Path, metadata: &StepMetadata) -> Result<()> {
+    let content = std::fs::read(path)?;
+    let rewritten = embed_metadata(&content, metadata)
+        .map_err(|e| ClientError::Parse(format!("{}: {}", path.display(), e)))?;
+    crate::utils::lock::write_atomic(path, &rewritten)?;
+    Ok(())
+}
+
+/// Return `content` with the header's FILE_NAME name and FILE_DESCRIPTION description replaced
+pub fn embed_metadata(content: &[u8], metadata: &StepMetadata) -> std::result::Result<Vec<u8>, String> {
+    let statements = header_statements(content)?;
+    let mut output = Vec::with_capacity(content.len() + 256);
+    let mut copied = 0;
+    let mut found = (false, false);
+
+    for (start, end) in statements {
+        let statement = std::str::from_utf8(&content[start..end]).map_err(|_| "header isn't ASCII text".to_string())?;
+        let keyword = statement.trim_start().split(|c: char| c == '(' || c.is_whitespace()).next().unwrap_or_default();
+        let replacement = match keyword.to_ascii_uppercase().as_str() {
+            "FILE_DESCRIPTION" => {
+                found.0 = true;
+                let description = format!("({},{})", step_string(&format!("McMaster-Carr {}", metadata.part_number)), step_string(&metadata.description));
+                replace_argument(statement, 0, &description)?
+            }
+            "FILE_NAME" => {
+                found.1 = true;
+                replace_argument(statement, 0, &step_string(&metadata.name()))?
+            }
+            _ => continue,
+        };
+        output.extend_from_slice(&content[copied..start]);
+        output.extend_from_slice(replacement.as_bytes());
+        copied = end;
+    }
+
+    match found {
+        (true, true) => {
+            output.extend_from_slice(&content[copied..]);
+            Ok(output)
+        }
+        (false, _) => Err("header has no FILE_DESCRIPTION".to_string()),
+        (_, false) => Err("header has no FILE_NAME".to_string()),
+    }
+}
+
+/// Byte ranges of the statements between `HEADER;` and `ENDSEC;`, without their semicolons
+fn header_statements(content: &[u8]) -> std::result::Result<Vec<(usize, usize)>, String> {
+    let mut statements = Vec::new();
+    let mut in_header = false;
+    let mut start = 0;
+    let mut index = 0;
+    while index < content.len() {
+        match content[index] {
+            b'\'' => {
+                // Strings run to the next lone quote; '' is an escaped quote
+                index += 1;
+                while index < content.len() {
+                    if content[index] == b'\'' {
+                        if content.get(index + 1) == Some(&b'\'') {
+                            index += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    index += 1;
+                }
+            }
+            b'/' if content.get(index + 1) == Some(&b'*') => {
+                let leading = content[start..index].iter().all(u8::is_ascii_whitespace);
+                index = find(content, index + 2, b"*/").ok_or("unterminated comment in header")? + 1;
+                if leading {
+                    start = index + 1; // Keep comments between statements out of them
+                }
+            }
+            b';' => {
+                let statement = String::from_utf8_lossy(&content[start..index]);
+                let statement = statement.trim();
+                if !in_header {
+                    if statement.eq_ignore_ascii_case("HEADER") {
+                        in_header = true;
+                    } else if !statement.eq_ignore_ascii_case("ISO-10303-21") {
+                        return Err("not a STEP file (no ISO-10303-21 header)".to_string());
+                    }
+                } else if statement.eq_ignore_ascii_case("ENDSEC") {
+                    return Ok(statements);
+                } else {
+                    statements.push((start, index));
+                }
+                start = index + 1;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    Err("header section never ends".to_string())
+}
+
+fn find(content: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    content.get(from..)?.windows(needle.len()).position(|window| window == needle).map(|position| from + position)
+}
+
+/// Replace one top-level argument of a statement such as `FILE_NAME('a', 'b', ...)`
+fn replace_argument(statement: &str, position: usize, value: &str) -> std::result::Result<String, String> {
+    let open = statement.find('(').ok_or_else(|| format!("malformed header entry '{}'", statement.trim()))?;
+    let bytes = statement.as_bytes();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut argument_start = open + 1;
+    let mut argument = 0;
+    for (index, &byte) in bytes.iter().enumerate().skip(open) {
+        match byte {
+            b'\'' => in_string = !in_string, // An escaped '' toggles twice
+            _ if in_string => {}
+            b'(' => depth += 1,
+            b')' | b',' if depth == 1 => {
+                if argument == position {
+                    return Ok(format!("{}{}{}", &statement[..argument_start], value, &statement[index..]));
+                }
+                argument += 1;
+                argument_start = index + 1;
+                if byte == b')' {
+                    break;
+                }
+            }
+            b')' => depth -= 1,
+            _ => {}
+        }
+    }
+    Err(format!("header entry '{}' has too few arguments", statement.trim()))
+}
+
+/// Quote a string for a STEP file, escaping quotes and backslashes and encoding non-ASCII characters
+fn step_string(value: &str) -> String {
+    let mut quoted = String::from("'");
+    for c in value.chars() {
+        match c {
+            '\'' => quoted.push_str("''"),
+            '\\' => quoted.push_str("\\\\"),
+            ' '..='~' => quoted.push(c),
+            _ => {
+                quoted.push_str("\\X2\\");
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    quoted.push_str(&format!("{:04X}", unit));
+                }
+                quoted.push_str("\\X0\\");
+            }
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embed_metadata() {
+        let step = "ISO-10303-21;\nHEADER;\n/* exported; by CAD */\nFILE_DESCRIPTION(('a;b'),'2;1');\n\
+                    FILE_NAME('C:\\\\temp\\\\x.stp','2024-01-01T00:00:00',('it''s me'),(''),'pre','sys','');\n\
+                    FILE_SCHEMA(('AUTOMOTIVE_DESIGN'));\nENDSEC;\nDATA;\n#1=PRODUCT('x','x','',(#2));\nENDSEC;\nEND-ISO-10303-21;\n";
+        let metadata = StepMetadata { part_number: "91290A115".to_string(), description: "Screw, 18-8 Stainless ½\"".to_string() };
+        let rewritten = String::from_utf8(embed_metadata(step.as_bytes(), &metadata).unwrap()).unwrap();
+
+        assert!(rewritten.contains("FILE_DESCRIPTION(('McMaster-Carr 91290A115','Screw, 18-8 Stainless \\X2\\00BD\\X0\\\"'),'2;1');"));
+        assert!(rewritten.contains("FILE_NAME('91290A115 Screw, 18-8 Stainless \\X2\\00BD\\X0\\\"','2024-01-01T00:00:00',('it''s me'),"));
+        assert!(rewritten.contains("/* exported; by CAD */") && rewritten.ends_with("#1=PRODUCT('x','x','',(#2));\nENDSEC;\nEND-ISO-10303-21;\n"));
+        assert!(embed_metadata(b"solid cube\nendsolid\n", &metadata).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Price comparisons against other suppliers
+//!
+//! A [`VendorLookup`] turns a McMaster-Carr product into another supplier's offer: at least
+//! a search link, and a price when the supplier can be asked for one. Lookups are collected
+//! in a [`Vendors`] registry; `[[vendors]]` tables in config.toml register the reference
+//! [`ParametricSearch`] lookup, and library users can register their own.
+
+use crate::models::product::ProductDetail;
+use crate::utils::error::{ClientError, Result};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+/// Attribute words whose specifications go into `{specs}`: what a fastener search needs
+const SEARCH_ATTRIBUTE_WORDS: &[&str] = &["thread", "length", "head", "diameter", "drive", "size", "material"];
+
+/// Vendor name used for McMaster-Carr's own price in a comparison
+pub const MCMASTER: &str = "McMaster-Carr";
+
+/// A supplier lookup, from a `[[vendors]]` table in config.toml
+///
+/// URLs are templates: `{part}`, `{description}`, `{family}` and `{category}` are the
+/// product's, `{specs}` is its thread, length, head, drive, size and material values, and
+/// any other `{Attribute}` is that specification's value. Values are URL-encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VendorConfig {
+    pub name: String,
+    /// Search results page for the part, e.g. `https://example.com/search?q={specs}`
+    pub search_url: String,
+    /// Endpoint answering a GET with `{"price": 0.12, "currency": "USD", "url": "..."}` (price per piece)
+    pub price_url: Option<String>,
+}
+
+/// One supplier's offer for a part
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VendorQuote {
+    pub vendor: String,
+    pub url: Option<String>, // Product or search page
+    pub price: Option<f64>, // Per piece, when known
+    pub currency: Option<String>,
+    pub error: Option<String>, // Why the lookup failed
+}
+
+/// A part's McMaster-Carr price alongside other suppliers'
+#[derive(Debug, Clone, Serialize)]
+pub struct VendorComparison {
+    pub part_number: String,
+    pub description: String,
+    pub quotes: Vec<VendorQuote>, // McMaster-Carr first, then vendors in registration order
+}
+
+/// Something that can find a part at another supplier
+pub trait VendorLookup: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Find the supplier's offer for a product
+    fn lookup<'a>(&'a self, product: &'a ProductDetail) -> BoxFuture<'a, Result<VendorQuote>>;
+}
+
+/// Registered vendor lookups, asked in order
+#[derive(Default)]
+pub struct Vendors {
+    lookups: Vec<Box<dyn VendorLookup>>,
+}
+
+impl Vendors {
+    /// A registry with a [`ParametricSearch`] for each configured vendor
+    pub fn from_config(vendors: &[VendorConfig]) -> Self {
+        let mut registry = Vendors::default();
+        for vendor in vendors {
+            registry.register(ParametricSearch::new(vendor.clone()));
+        }
+        registry
+    }
+
+    pub fn register(&mut self, lookup: impl VendorLookup + 'static) {
+        self.lookups.push(Box::new(lookup));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lookups.is_empty()
+    }
+
+    /// Ask every vendor about a product; a failed lookup becomes a quote with an error
+    pub async fn quotes(&self, product: &ProductDetail) -> Vec<VendorQuote> {
+        let mut quotes = Vec::with_capacity(self.lookups.len());
+        for lookup in &self.lookups {
+            let quote = lookup.lookup(product).await.unwrap_or_else(|e| VendorQuote {
+                vendor: lookup.name().to_string(),
+                url: None,
+                price: None,
+                currency: None,
+                error: Some(e.to_string()),
+            });
+            quotes.push(quote);
+        }
+        quotes
+    }
+}
+
+/// Reference lookup: a parametric search link, priced through an optional API
+pub struct ParametricSearch {
+    config: VendorConfig,
+    http: reqwest::Client, // A plain client: the McMaster-Carr client certificate isn't meant for other suppliers
+}
+
+/// Response expected from a vendor's `price_url`
+#[derive(Debug, Deserialize)]
+struct PriceResponse {
+    price: Option<f64>,
+    currency: Option<String>,
+    url: Option<String>,
+}
+
+impl ParametricSearch {
+    pub fn new(config: VendorConfig) -> Self {
+        ParametricSearch { config, http: reqwest::Client::new() }
+    }
+
+    async fn quote(&self, product: &ProductDetail) -> Result<VendorQuote> {
+        let search_url = fill_template(&self.config.search_url, product)?;
+        let mut quote = VendorQuote {
+            vendor: self.config.name.clone(),
+            url: Some(search_url),
+            price: None,
+            currency: None,
+            error: None,
+        };
+
+        if let Some(price_url) = &self.config.price_url {
+            let response = self.http.get(fill_template(price_url, product)?).send().await?;
+            if !response.status().is_success() {
+                return Err(ClientError::from_http(response, &format!("{} price lookup failed", self.config.name)).await);
+            }
+            let priced: PriceResponse = response.json().await?;
+            quote.price = priced.price;
+            quote.currency = priced.currency;
+            quote.url = priced.url.or(quote.url);
+        }
+        Ok(quote)
+    }
+}
+
+impl VendorLookup for ParametricSearch {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn lookup<'a>(&'a self, product: &'a ProductDetail) -> BoxFuture<'a, Result<VendorQuote>> {
+        Box::pin(self.quote(product))
+    }
+}
+
+/// Replace a URL template's `{placeholders}` with a product's URL-encoded values
+fn fill_template(template: &str, product: &ProductDetail) -> Result<String> {
+    let mut url = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..].find('}')
+            .map(|offset| start + offset)
+            .ok_or_else(|| ClientError::Config(format!("Unclosed '{{' in vendor URL '{}'", template)))?;
+        url.push_str(&rest[..start]);
+        let value = placeholder_value(&rest[start + 1..end], product)?;
+        url.push_str(&urlencoding::encode(&value));
+        rest = &rest[end + 1..];
+    }
+    url.push_str(rest);
+    Ok(url)
+}
+
+fn placeholder_value(name: &str, product: &ProductDetail) -> Result<String> {
+    // In the order of `words`, so searches read like "M3 10 mm Alloy Steel"
+    let spec_values = |words: &[&str]| {
+        let mut matching: Vec<(usize, &String)> = product.specifications.iter()
+            .filter_map(|spec| {
+                let attribute = spec.attribute.to_lowercase();
+                let rank = words.iter().position(|word| attribute.contains(word))?;
+                Some((rank, spec.values.first()?))
+            })
+            .collect();
+        matching.sort_by_key(|(rank, _)| *rank);
+        matching.into_iter().map(|(_, value)| value.as_str()).collect::<Vec<_>>().join(" ")
+    };
+
+    Ok(match name.trim().to_lowercase().as_str() {
+        "part" => product.part_number.clone(),
+        "description" => product.detail_description.clone(),
+        "family" => product.family_description.clone(),
+        "category" => product.product_category.clone(),
+        "specs" => spec_values(SEARCH_ATTRIBUTE_WORDS),
+        attribute => product.specifications.iter()
+            .find(|spec| spec.attribute.eq_ignore_ascii_case(attribute))
+            .map(|spec| spec.values.join(" "))
+            .ok_or_else(|| ClientError::NotFound(format!("{} has no '{}' specification", product.part_number, name.trim())))?,
+    })
+}
+
+/// Vendor comparison methods for McmasterClient
+impl crate::client::McmasterClient {
+    /// Compare a part's McMaster-Carr price per piece with each registered vendor's offer
+    pub async fn compare_vendors(&self, product: &str, vendors: &Vendors) -> Result<VendorComparison> {
+        let detail = self.get_product(product).await?;
+        let prices = self.get_price(product).await?;
+
+        // Per piece only when the pack size is known; otherwise leave it to the product page
+        let lowest = prices.iter().min_by(|a, b| a.minimum_quantity.total_cmp(&b.minimum_quantity));
+        let pieces = match lowest {
+            Some(price) if price.unit_of_measure.eq_ignore_ascii_case("each") => Some(1),
+            Some(_) => detail.pack_size(),
+            None => None,
+        };
+        let mcmaster = VendorQuote {
+            vendor: MCMASTER.to_string(),
+            url: Some(format!("https://www.mcmaster.com/{}/", detail.part_number)),
+            price: lowest.zip(pieces).map(|(price, pieces)| price.amount / f64::from(pieces.max(1))),
+            currency: lowest.map(|price| price.currency().to_string()),
+            error: None,
+        };
+
+        let mut quotes = vec![mcmaster];
+        quotes.extend(vendors.quotes(&detail).await);
+        Ok(VendorComparison {
+            part_number: detail.part_number,
+            description: detail.detail_description,
+            quotes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockApi, PART_NUMBER};
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_compare_vendors() {
+        let api = MockApi::start().await;
+        let client = api.logged_in_client().unwrap();
+        Mock::given(method("GET"))
+            .and(path("/quote"))
+            .and(query_param("size", "M3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"price": 0.11, "currency": "USD"})))
+            .mount(api.server())
+            .await;
+
+        let vendors = Vendors::from_config(&[
+            VendorConfig {
+                name: "Bolts Inc".to_string(),
+                search_url: "https://bolts.example/search?q={specs}&mfr={Part}".to_string(),
+                price_url: Some(format!("{}/quote?size={{thread size}}", api.uri())),
+            },
+            VendorConfig { name: "Nuts Inc".to_string(), search_url: "https://nuts.example/{Length}".to_string(), price_url: None },
+        ]);
+        let comparison = client.compare_vendors(PART_NUMBER, &vendors).await.unwrap();
+        let vendor_names: Vec<_> = comparison.quotes.iter().map(|quote| quote.vendor.as_str()).collect();
+        assert_eq!(vendor_names, vec![MCMASTER, "Bolts Inc", "Nuts Inc"]);
+
+        // The fixture's pack size is unknown, so there's no McMaster-Carr price per piece
+        assert_eq!(comparison.quotes[0].price, None);
+        let bolts = &comparison.quotes[1];
+        assert_eq!(bolts.url.as_deref(), Some("https://bolts.example/search?q=M3%20Alloy%20Steel&mfr=91290A115"));
+        assert_eq!((bolts.price, bolts.currency.as_deref()), (Some(0.11), Some("USD")));
+        assert!(comparison.quotes[2].error.as_deref().unwrap().contains("no 'Length' specification"));
+    }
+}
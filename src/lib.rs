@@ -4,16 +4,28 @@
 
 pub mod client;
 pub mod config;
+pub mod integrations;
 pub mod models;
+pub mod rpc;
 pub mod utils;
 
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_support;
+
 // Re-export main types for convenience
 pub use client::McmasterClient;
+pub use client::api::{DiscontinuedPart, PartPrices, RefreshReport, SyncReport};
+pub use client::certificate::CertificateInfo;
+pub use client::subscriptions::TrackedPart;
+pub use client::downloads::{BulkDownloadReport, DownloadResult};
+pub use client::selftest::SelfTestReport;
 pub use models::{
     api::ProductInfo,
     api::{CadFile, CadFormat, LinkItem, ProductResponse},
     auth::{Credentials, LoginRequest, LoginResponse},
+    change::ChangeRecord,
     product::{PriceInfo, ProductDetail, Specification},
+    warning::{Warning, WarningKind},
 };
 pub use utils::error::ClientError;
 pub use utils::output::{OutputFormat, ProductField};
@@ -1,11 +1,31 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use anyhow::{Context, Result};
 use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
 use dirs::{home_dir, config_dir};
 use tokio::fs;
 
 // Import from the new library structure
-use mmcli::{McmasterClient, Credentials, OutputFormat};
+use mmcli::{ClientError, McmasterClient, Credentials, DownloadResult, OutputFormat};
+use mmcli::client::alerts::AlertDirection;
+use mmcli::client::auth::LogoutStatus;
+use mmcli::client::cache::CacheConfig;
+use mmcli::client::certificate::CertificateFormat;
+use mmcli::client::manifest::FileStatus;
+use mmcli::client::metrics::Metrics;
+use mmcli::client::snapshot::Snapshot;
+use mmcli::client::team::TeamRemote;
+use mmcli::models::filter::PartFilter;
+use mmcli::config::Config;
+use mmcli::integrations::label::{Label, LabelFormat};
+use mmcli::integrations::onshape::OnshapeTarget;
+use mmcli::integrations::report::{self, ReportFormat};
+use mmcli::utils::display::{self, decorate, Icon};
+use mmcli::utils::lock::write_atomic;
+use mmcli::utils::output;
+use mmcli::t;
+use mmcli::client::downloads::DEFAULT_DOWNLOAD_JOBS;
 
 
 #[derive(Parser)]
@@ -20,6 +40,46 @@ struct Cli {
     /// Show detailed output including certificate loading and authentication details
     #[arg(short, long, global = true)]
     verbose: bool,
+
+    /// Plain output: no emoji, progress animations or box drawing (also NO_COLOR or `plain` in config.toml)
+    #[arg(long, global = true)]
+    plain: bool,
+
+    /// Never use the network; serve product details and prices from the local cache
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Save every API request/response pair, with credentials removed, into DIR
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Answer API requests from a directory saved with --record instead of the network
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "offline")]
+    replay: Option<PathBuf>,
+
+    /// When the command finishes, write request, cache and download counters to PATH in the Prometheus text format
+    #[arg(long, global = true, value_name = "PATH")]
+    metrics_file: Option<PathBuf>,
+
+    /// Accept any server certificate, for debugging TLS problems only (prefer `ca_bundle` in config.toml)
+    #[arg(long, global = true)]
+    insecure: bool,
+
+    /// Seconds to wait for a connection and TLS handshake; 0 waits forever [default: 30, or `connect_timeout` in config.toml]
+    #[arg(long, global = true, value_name = "SECS")]
+    connect_timeout: Option<u64>,
+
+    /// Seconds a single request may take, including the download; 0 waits forever [default: 300, or `timeout` in config.toml]
+    #[arg(long, global = true, value_name = "SECS")]
+    timeout: Option<u64>,
+
+    /// Abandon the whole command after SECS seconds [default: no limit, or `deadline` in config.toml]
+    #[arg(long, global = true, value_name = "SECS")]
+    deadline: Option<u64>,
+
+    /// Maximum number of concurrent downloads [default: 4, or `jobs` in config.toml]
+    #[arg(short, long, global = true)]
+    jobs: Option<usize>,
     
     #[command(subcommand)]
     command: Commands,
@@ -49,16 +109,33 @@ enum Commands {
     },
     /// Copy certificate to default location
     InitCert {
-        /// Path to source certificate file
+        /// Path to source certificate file (.pfx, .p12 or .pem)
         source: String,
         /// Certificate password for conversion (if needed)
         #[arg(short, long)]
         password: Option<String>,
+        /// Private key file for a PEM certificate that doesn't include its key
+        #[arg(short, long)]
+        key: Option<String>,
+    },
+    /// Inspect the client certificate
+    Cert {
+        #[command(subcommand)]
+        command: CertCommands,
+    },
+    /// Inspect the saved login
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommands,
     },
     /// Add product to subscription
     Add {
         /// Product number
-        product: String,
+        #[arg(required_unless_present = "stdin")]
+        product: Option<String>,
+        /// Read part numbers from stdin instead, one per line (e.g. from `mmc list --part-numbers`)
+        #[arg(long, conflicts_with = "product")]
+        stdin: bool,
     },
     /// Remove product from subscription
     Remove {
@@ -68,27 +145,104 @@ enum Commands {
     /// Get product information
     Info {
         /// Product number
-        product: String,
-        /// Output format
-        #[arg(short, long, default_value_t = OutputFormat::Human)]
-        output: OutputFormat,
+        #[arg(required_unless_present = "stdin")]
+        product: Option<String>,
+        /// Read part numbers from stdin instead, one per line (e.g. from `mmc list --part-numbers`)
+        #[arg(long, conflicts_with = "product")]
+        stdin: bool,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
         /// Comma-separated list of fields to display (default: all)
         #[arg(short, long, default_value = "all")]
         fields: String,
+        /// Also write a QR code linking to the part to FILE (.svg, otherwise PNG); the URL is `qr_url` in config.toml
+        #[arg(long, value_name = "FILE", conflicts_with = "stdin")]
+        qr: Option<PathBuf>,
+    },
+    /// Compare the specifications of two products side by side
+    Diff {
+        /// First product number
+        first: String,
+        /// Second product number
+        second: String,
+        /// Only show specifications that differ
+        #[arg(short, long)]
+        differences_only: bool,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+    /// Suggest cached parts in the same category that can replace a part
+    Alternates {
+        /// Product number
+        product: String,
+        /// Maximum number of suggestions
+        #[arg(short, long, default_value_t = 10)]
+        limit: usize,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+    /// Compare a part's price per piece with other suppliers (`[[vendors]]` in config.toml)
+    Vendors {
+        /// Product number
+        product: String,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+    /// Work with CSV bills of materials
+    Bom {
+        #[command(subcommand)]
+        command: BomCommands,
     },
     /// Get product price
     Price {
         /// Product number
-        product: String,
-        /// Output format
-        #[arg(short, long, default_value_t = OutputFormat::Human)]
-        output: OutputFormat,
+        #[arg(required_unless_present = "stdin")]
+        product: Option<String>,
+        /// Read part numbers from stdin instead, one per line (e.g. from `mmc list --part-numbers`)
+        #[arg(long, conflicts_with = "product")]
+        stdin: bool,
+        /// Pieces needed: pick the price break, round up to whole packs and the minimum order, and total the cost
+        #[arg(short, long, value_name = "N")]
+        qty: Option<f64>,
+        /// Pieces per pack, when the product's specifications don't give it (used with --qty)
+        #[arg(long, value_name = "N", requires = "qty")]
+        pack_size: Option<u32>,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+    /// Get prices for every locally tracked part
+    PriceAll {
+        /// Only parts with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+    /// Alert when a part's price crosses a threshold (checked by price-all and `alerts check`)
+    Alerts {
+        #[command(subcommand)]
+        command: AlertsCommands,
     },
     /// List changes since a date (MM/dd/yyyy or MM/dd/yyyy HH:mm)
     Changes {
         /// Start date to check for changes (MM/dd/yyyy format)
         #[arg(short, long, default_value = "01/01/2024")]
         start: String,
+        /// End date (MM/dd/yyyy format; default: now)
+        #[arg(short, long)]
+        end: Option<String>,
+        /// Only show changes to locally tracked parts
+        #[arg(long)]
+        subscribed_only: bool,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
     },
     /// Download product images
     Image {
@@ -97,59 +251,615 @@ enum Commands {
         /// Output directory (default: ~/Downloads/mmc/{product}/images/)
         #[arg(short, long)]
         output: Option<String>,
+        /// Re-download files even if unchanged since the last download
+        #[arg(long)]
+        force: bool,
     },
     /// Download product CAD files
     Cad {
         /// Product number
-        product: String,
-        /// Output directory (default: ~/Downloads/mmc/{product}/cad/)
+        #[arg(required_unless_present = "stdin")]
+        product: Option<String>,
+        /// Read part numbers from stdin instead, one per line (e.g. from `mmc list --part-numbers`)
+        #[arg(long, conflicts_with = "product")]
+        stdin: bool,
+        /// Output directory (default: ~/Downloads/mmc/{product}/cad/); with --stdin, the root for {output}/{part}/cad/
         #[arg(short, long)]
         output: Option<String>,
-        /// Download DWG files
+        /// Re-download files even if unchanged since the last download
         #[arg(long)]
-        dwg: bool,
-        /// Download STEP files
+        force: bool,
+        /// Unpack CAD files that arrive as ZIP archives, naming the contents after the part
         #[arg(long)]
-        step: bool,
-        /// Download DXF files
+        extract: bool,
+        /// Write the part number and description into the header of downloaded STEP files
         #[arg(long)]
-        dxf: bool,
-        /// Download IGES files
+        embed_metadata: bool,
+        #[command(flatten)]
+        formats: CadFormatArgs,
+    },
+    /// Download CAD files for every locally tracked part
+    CadAll {
+        /// Root output directory; files go to {output}/{part}/cad/ (default: ~/Downloads/mmc/)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Re-download files even if unchanged since the last download
+        #[arg(long)]
+        force: bool,
+        /// Only parts with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Unpack CAD files that arrive as ZIP archives, naming the contents after the part
         #[arg(long)]
-        iges: bool,
-        /// Download SolidWorks files (SLDPRT, SLDDRW)
+        extract: bool,
+        /// Write the part number and description into the header of downloaded STEP files
         #[arg(long)]
-        solidworks: bool,
-        /// Download SAT files
+        embed_metadata: bool,
+        #[command(flatten)]
+        formats: CadFormatArgs,
+    },
+    /// Re-hash a part's downloaded files against their manifests to detect corruption or tampering
+    Verify {
+        /// Product number
+        product: String,
+        /// Root the part was downloaded under, like `cad-all -o` (default: ~/Downloads/mmc/)
+        #[arg(short, long)]
+        root: Option<PathBuf>,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+    /// Build a SolidWorks Design Library folder with SLDPRT files and custom property files
+    SwLibrary {
+        /// Product numbers
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        products: Vec<String>,
+        /// Every locally tracked part
         #[arg(long)]
-        sat: bool,
-        /// Download EDRW files
+        all: bool,
+        /// With --all, only parts with this tag
+        #[arg(short, long, requires = "all")]
+        tag: Option<String>,
+        /// Library root (default: ~/Downloads/mmc/solidworks-library/)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Re-download files even if unchanged since the last download
         #[arg(long)]
-        edrw: bool,
-        /// Download PDF files
+        force: bool,
+    },
+    /// Import a part's STEP model into an Onshape document (API keys from config.toml)
+    Onshape {
+        /// Product number
+        product: String,
+        /// Document ID, or a document URL that includes the workspace
+        #[arg(short, long)]
+        document: String,
+        /// Workspace ID (taken from the document URL if not given)
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+    /// Download a part's STEP model with a JSON metadata sidecar for FreeCAD
+    Freecad {
+        /// Product number
+        product: String,
+        /// Output directory (default: ~/Downloads/mmc/{product}/cad/)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Also copy both files into the parts library set by `library_dir` in config.toml's [freecad] table
+        #[arg(short, long)]
+        library: bool,
+        /// Re-download files even if unchanged since the last download
         #[arg(long)]
-        pdf: bool,
-        /// Download all available CAD formats (default if no specific formats specified)
+        force: bool,
+    },
+    /// Bundle STEP models, datasheets and a metadata manifest into a zip per part
+    Package {
+        /// Product numbers
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        products: Vec<String>,
+        /// Every locally tracked part
         #[arg(long)]
         all: bool,
+        /// With --all, only parts with this tag
+        #[arg(short, long, requires = "all")]
+        tag: Option<String>,
+        /// Output directory (default: ~/Downloads/mmc/packages/)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Put every part in a single NAME.zip instead
+        #[arg(short, long, value_name = "NAME")]
+        bundle: Option<String>,
+        /// Re-download files even if unchanged since the last download
+        #[arg(long)]
+        force: bool,
+    },
+    /// Render a bin label with a QR code, part number, description and key specifications
+    Label {
+        /// Product number
+        product: String,
+        /// Label format: ZPL for Zebra printers, or a PNG or PDF at 203 dpi
+        #[arg(short, long, value_enum, default_value = "zpl")]
+        format: LabelFormat,
+        /// Output file, or - for stdout (default: ~/Downloads/mmc/{product}/labels/{product}.{format})
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Also write a QR code linking to the part to FILE (.svg, otherwise PNG); the URL is `qr_url` in config.toml
+        #[arg(long, value_name = "FILE")]
+        qr: Option<PathBuf>,
+    },
+    /// Write a Markdown or HTML data sheet per part, for pasting into a wiki
+    Report {
+        /// Product numbers
+        #[arg(required_unless_present = "tag", conflicts_with = "tag")]
+        products: Vec<String>,
+        /// Every tracked part with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Document format
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: ReportFormat,
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Static HTML catalog of the tracked parts
+    Site {
+        #[command(subcommand)]
+        command: SiteCommands,
     },
     /// Download product datasheets
+    #[command(args_conflicts_with_subcommands = true)]
     Datasheet {
         /// Product number
-        product: String,
+        #[arg(required = true)]
+        product: Option<String>,
         /// Output directory (default: ~/Downloads/mmc/{product}/datasheets/)
         #[arg(short, long)]
         output: Option<String>,
+        /// Re-download files even if unchanged since the last download
+        #[arg(long)]
+        force: bool,
+        /// Add the datasheet text to the index used by `mmc search` (default: `index_datasheets` in config.toml)
+        #[arg(long)]
+        index: bool,
+        #[command(subcommand)]
+        command: Option<DatasheetCommands>,
+    },
+    /// Open a part's product page in the default browser, or its downloaded datasheet
+    Open {
+        /// Product number
+        product: String,
+        /// Open the datasheet downloaded with `mmc datasheet` instead of the product page
+        #[arg(long)]
+        datasheet: bool,
+        /// Directory the datasheet was downloaded to, like `datasheet -o` (default: ~/Downloads/mmc/{product}/datasheets/)
+        #[arg(short, long, requires = "datasheet")]
+        output: Option<String>,
+        /// Print the URL or path instead of opening it, e.g. over SSH
+        #[arg(long)]
+        print: bool,
+    },
+    /// Find parts by the text of their indexed datasheets (see `datasheet --index`)
+    Search {
+        /// Words to look for; every word must appear
+        #[arg(required = true)]
+        query: Vec<String>,
+        /// Maximum number of results
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+    /// List locally tracked subscriptions (use --verbose to include notes and metadata)
+    List {
+        /// Only parts with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Only parts whose cached category or family name contains this, e.g. "screws"
+        #[arg(long)]
+        category: Option<String>,
+        /// Only parts with this cached specification value (repeatable)
+        #[arg(long, value_name = "ATTRIBUTE=VALUE", value_parser = PartFilter::parse_spec)]
+        spec: Vec<(String, String)>,
+        /// Only parts of this material, like --spec "Material=VALUE"
+        #[arg(long)]
+        material: Option<String>,
+        /// Only parts with this status: active, discontinued, or an exact status name
+        #[arg(long)]
+        status: Option<String>,
+        /// Print only the part numbers, one per line, for piping into other commands
+        #[arg(long, conflicts_with = "output")]
+        part_numbers: bool,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+    /// Show or edit the note and custom metadata on a tracked part
+    Note {
+        /// Product number
+        product: String,
+        /// Note text to set (replaces any existing note)
+        text: Option<String>,
+        /// Remove the note
+        #[arg(long, conflicts_with = "text")]
+        clear: bool,
+        /// Set a metadata value (repeatable)
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        set: Vec<String>,
+        /// Remove a metadata value (repeatable)
+        #[arg(long, value_name = "KEY")]
+        unset: Vec<String>,
+    },
+    /// Tag a locally tracked part with project or group names
+    Tag {
+        /// Product number
+        product: String,
+        /// Tags to add (or remove with --remove)
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Remove the tags instead of adding them
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Map McMaster-Carr part numbers to internal ERP/PLM item numbers
+    Xref {
+        #[command(subcommand)]
+        command: XrefCommands,
+    },
+    /// Revert the last add, remove or import (run again to step further back)
+    Undo {
+        /// Show recent changes instead of undoing one
+        #[arg(long)]
+        list: bool,
     },
-    /// List locally tracked subscriptions
-    List,
     /// Sync local subscriptions with API
-    Sync,
+    Sync {
+        /// Instead, merge tracked parts, tags, notes and metadata with a team's shared file or git repository [default: `team_remote` in config.toml]
+        #[arg(long, value_name = "PATH_OR_URL")]
+        remote: Option<Option<String>>,
+    },
+    /// Fetch and cache product details for every tracked part, for offline use
+    Refresh {
+        /// Only refresh parts cached more than this many days ago (default: `[cache] ttl_hours` in config.toml, else all)
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u64>,
+    },
     /// Import subscriptions from file
     Import {
         /// Path to file containing part numbers (one per line)
         file: String,
     },
+    /// Export tracked parts to a file, or a part to another tool
+    #[command(args_conflicts_with_subcommands = true)]
+    Export {
+        /// Path to write tracked parts with their tags, notes and metadata (same format as `import` reads)
+        #[arg(required = true)]
+        file: Option<String>,
+        #[command(subcommand)]
+        target: Option<ExportTarget>,
+    },
+    /// Archive every cached product's details and prices, or compare two archives
+    #[command(args_conflicts_with_subcommands = true)]
+    Snapshot {
+        /// Where to write the archive (default: a dated zip in the snapshots folder next to the subscription database)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(subcommand)]
+        command: Option<SnapshotCommands>,
+    },
+    /// Check that login, product data and downloads work against the live API
+    Selftest {
+        /// Part number to test with (should be in your subscription)
+        #[arg(long, default_value = "91290A115")]
+        part: String,
+    },
+    /// Serve JSON-RPC 2.0 requests on stdin, one per line, for editors and CAD add-ons
+    Rpc,
+}
+
+#[derive(Subcommand)]
+enum ExportTarget {
+    /// Print KiCad symbol fields (MPN, description, value, datasheet) for a part
+    Kicad {
+        /// Product number
+        product: String,
+        /// Also download the STEP model into this project 3D models directory
+        #[arg(long)]
+        models_dir: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BomCommands {
+    /// Append item number, description, category, unit price, pack size and datasheet URL columns
+    Enrich {
+        /// BOM CSV with a header row
+        file: PathBuf,
+        /// Where to write the enriched CSV (default: {file}-enriched.csv beside the BOM)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Header of the McMaster-Carr part number column (found automatically if not given)
+        #[arg(long)]
+        column: Option<String>,
+    },
+    /// Check that every part exists, is active and subscribed, and meets the minimum order; exits 1 on failure
+    Check {
+        /// BOM CSV with a header row
+        file: PathBuf,
+        /// Header of the McMaster-Carr part number column (found automatically if not given)
+        #[arg(long)]
+        column: Option<String>,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+    /// Total parts across BOMs, round to whole packs, and write a list for the bulk order page
+    Order {
+        /// BOM CSVs with a header row and a quantity column; the same part in several is added up
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Header of the McMaster-Carr part number column (found automatically if not given)
+        #[arg(long)]
+        column: Option<String>,
+        /// Where to write the order list (default: {first file}-order.csv beside the BOM)
+        #[arg(long)]
+        order_file: Option<PathBuf>,
+        /// Output format for the priced parts (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AlertsCommands {
+    /// Alert when the price at the smallest price break goes above or below a threshold
+    #[command(group = clap::ArgGroup::new("threshold").required(true).multiple(true))]
+    Add {
+        /// Product number
+        product: String,
+        /// Alert when the price rises above this
+        #[arg(long, group = "threshold", value_name = "PRICE")]
+        above: Option<f64>,
+        /// Alert when the price falls below this
+        #[arg(long, group = "threshold", value_name = "PRICE")]
+        below: Option<f64>,
+    },
+    /// Remove a part's alerts
+    Remove {
+        /// Product number
+        product: String,
+    },
+    /// List alert rules with the price each was last checked against
+    List {
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+    /// Fetch prices for every part with an alert and report the ones that crossed their threshold
+    Check,
+}
+
+#[derive(Subcommand)]
+enum DatasheetCommands {
+    /// Merge downloaded datasheets into one PDF with a bookmark per part, e.g. for a design review
+    Merge {
+        /// Product numbers, in the order to merge them
+        #[arg(required_unless_present_any = ["tag", "bom"], conflicts_with_all = ["tag", "bom"])]
+        products: Vec<String>,
+        /// Every tracked part with this tag
+        #[arg(short, long, conflicts_with = "bom")]
+        tag: Option<String>,
+        /// The parts in a CSV BOM, in BOM order
+        #[arg(long, value_name = "FILE")]
+        bom: Option<PathBuf>,
+        /// Merged PDF to write
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum SiteCommands {
+    /// Render the tracked parts into DIR: an index by category and tag, and a page per part
+    Build {
+        /// Output directory
+        dir: PathBuf,
+        /// Only parts with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SnapshotCommands {
+    /// Show which parts were added, removed or changed between two snapshots
+    Diff {
+        /// Earlier snapshot archive
+        from: PathBuf,
+        /// Later snapshot archive
+        to: PathBuf,
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+}
+
+#[derive(Subcommand)]
+enum XrefCommands {
+    /// Show a part's item number
+    Get {
+        /// Product number
+        product: String,
+    },
+    /// Map a part to an item number, replacing any existing mapping
+    Set {
+        /// Product number
+        product: String,
+        /// Internal ERP/PLM item number
+        item_number: String,
+    },
+    /// Remove a part's mapping
+    Unset {
+        /// Product number
+        product: String,
+    },
+    /// List every mapping
+    List {
+        /// Output format (default: human, or `output` in config.toml)
+        #[arg(short, long)]
+        output: Option<OutputFormat>,
+    },
+    /// Load mappings from a CSV with part number and item number columns (e.g. an ERP export)
+    Import {
+        /// CSV with a header row; columns are found by name, e.g. "McMaster PN" and "Item Number"
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum CertCommands {
+    /// Show subject, issuer and expiry of the loaded certificate
+    Status {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuthCommands {
+    /// Show whether a token is saved and when it expires, the certificate and the credentials file in use
+    Status {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// CAD format selection flags shared by the CAD download commands
+#[derive(Args)]
+struct CadFormatArgs {
+    /// Download DWG files
+    #[arg(long)]
+    dwg: bool,
+    /// Download STEP files
+    #[arg(long)]
+    step: bool,
+    /// Download DXF files
+    #[arg(long)]
+    dxf: bool,
+    /// Download IGES files
+    #[arg(long)]
+    iges: bool,
+    /// Download SolidWorks files (SLDPRT, SLDDRW)
+    #[arg(long)]
+    solidworks: bool,
+    /// Download SAT files
+    #[arg(long)]
+    sat: bool,
+    /// Download EDRW files
+    #[arg(long)]
+    edrw: bool,
+    /// Download PDF files
+    #[arg(long)]
+    pdf: bool,
+    /// Download all available CAD formats (default if no formats are specified here or in config.toml)
+    #[arg(long)]
+    all: bool,
+}
+
+impl CadFormatArgs {
+    /// Collect selected formats, falling back to the configured defaults, and whether to download everything
+    fn selected(&self, defaults: &[String]) -> (Vec<String>, bool) {
+        let mut formats = Vec::new();
+        if self.dwg { formats.push("dwg"); }
+        if self.step { formats.push("step"); }
+        if self.dxf { formats.push("dxf"); }
+        if self.iges { formats.push("iges"); }
+        if self.solidworks { formats.push("solidworks"); }
+        if self.sat { formats.push("sat"); }
+        if self.edrw { formats.push("edrw"); }
+        if self.pdf { formats.push("pdf"); }
+        let mut formats: Vec<String> = formats.into_iter().map(String::from).collect();
+
+        if formats.is_empty() && !self.all {
+            formats = defaults.to_vec();
+        }
+
+        // If no specific formats selected or --all is specified, download all
+        let download_all = self.all || formats.is_empty();
+        (formats, download_all)
+    }
+}
+
+/// Process exit codes, so scripts can react to failures without parsing stderr
+///
+/// Code 2 is left to clap, which uses it for command-line usage errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Exit {
+    Success = 0,
+    Failure = 1, // Any error without a more specific code
+    Auth = 3, // Not logged in, token rejected, or certificate problem
+    NotSubscribed = 4,
+    NotFound = 5,
+    Network = 6, // Connection failure or rate limiting; retrying may help
+    PartialDownload = 7, // Some files were downloaded but others failed
+    Cancelled = 130, // Stopped by Ctrl-C before finishing, as shells report SIGINT
+}
+
+impl Exit {
+    /// Pick the exit code for an error based on the underlying `ClientError`
+    fn for_error(error: &anyhow::Error) -> Self {
+        let Some(client_error) = error.chain().find_map(|e| e.downcast_ref::<ClientError>()) else {
+            return Exit::Failure;
+        };
+
+        match client_error {
+            ClientError::NotAuthenticated
+            | ClientError::AuthExpired
+            | ClientError::Auth(_)
+            | ClientError::Certificate(_) => Exit::Auth,
+            ClientError::NotSubscribed(_) => Exit::NotSubscribed,
+            ClientError::NotFound(_) | ClientError::Api { status: 404, .. } => Exit::NotFound,
+            ClientError::Network(_)
+            | ClientError::DeadlineExceeded(_)
+            | ClientError::RateLimited { .. }
+            | ClientError::Offline(_) => Exit::Network,
+            _ => Exit::Failure,
+        }
+    }
+
+    /// Success if every file downloaded (or was unchanged), otherwise a partial download
+    fn for_downloads(results: &[DownloadResult]) -> Self {
+        if results.iter().all(DownloadResult::is_success) {
+            Exit::Success
+        } else {
+            Exit::PartialDownload
+        }
+    }
+}
+
+impl From<Exit> for ExitCode {
+    fn from(exit: Exit) -> Self {
+        ExitCode::from(exit as u8)
+    }
+}
+
+/// Cancel the client's bulk operation on the first Ctrl-C, so it can report what finished,
+/// and quit at once on the second
+fn cancel_on_ctrl_c(client: &McmasterClient) {
+    let cancellation = client.cancellation().clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        cancellation.cancel();
+        eprintln!("{}", decorate(Icon::Warning, t!("cancel.requested")));
+        if tokio::signal::ctrl_c().await.is_ok() {
+            std::process::exit(Exit::Cancelled as i32);
+        }
+    });
 }
 
 async fn load_credentials_from_file(path: &str) -> Result<Credentials> {
@@ -175,45 +885,16 @@ async fn load_credentials_from_file(path: &str) -> Result<Credentials> {
     Ok(credentials)
 }
 
-async fn load_default_credentials() -> Result<Credentials> {
-    // Try XDG config directory first (~/.config/mmc/)
-    if let Some(config_dir) = config_dir() {
-        let mut creds_path = config_dir;
-        creds_path.push("mmc");
-        creds_path.push("credentials.toml");
-        
-        if creds_path.exists() {
-            return load_credentials_from_file(creds_path.to_string_lossy().as_ref()).await;
-        }
-        
-        // Try JSON in config dir
-        creds_path.set_extension("json");
-        if creds_path.exists() {
-            return load_credentials_from_file(creds_path.to_string_lossy().as_ref()).await;
-        }
-    }
-    
-    // Fallback to legacy location (~/.mmcli/) for backward compatibility
-    if let Some(home) = home_dir() {
-        let mut creds_path = home;
-        creds_path.push(".mmcli");
-        creds_path.push("credentials.toml");
-        
-        if creds_path.exists() {
-            return load_credentials_from_file(creds_path.to_string_lossy().as_ref()).await;
-        }
-        
-        // Try JSON in legacy location
-        creds_path.set_extension("json");
-        if creds_path.exists() {
-            return load_credentials_from_file(creds_path.to_string_lossy().as_ref()).await;
-        }
-    }
-
-    Err(anyhow::anyhow!("No default credentials file found in ~/.config/mmc/ or ~/.mmcli/"))
+/// The first credentials file found in ~/.config/mmc/, then the legacy ~/.mmcli/
+fn default_credentials_path() -> Option<PathBuf> {
+    let dirs = [config_dir().map(|dir| dir.join("mmc")), home_dir().map(|dir| dir.join(".mmcli"))];
+    dirs.into_iter()
+        .flatten()
+        .flat_map(|dir| [dir.join("credentials.toml"), dir.join("credentials.json")])
+        .find(|path| path.exists())
 }
 
-async fn init_certificate(source_path: &str, _password: Option<&str>) -> Result<()> {
+async fn init_certificate(source_path: &str, _password: Option<&str>, key_path: Option<&str>) -> Result<()> {
     let source = PathBuf::from(source_path);
     if !source.exists() {
         return Err(anyhow::anyhow!("Source certificate file not found: {}", source_path));
@@ -234,10 +915,13 @@ async fn init_certificate(source_path: &str, _password: Option<&str>) -> Result<
         .context("Failed to create config directory")?;
 
     // Determine target filename
-    let target_filename = if source_path.ends_with(".pfx") || source_path.ends_with(".p12") {
-        "certificate.pfx"
-    } else {
-        return Err(anyhow::anyhow!("Source file must be a .pfx or .p12 certificate file"));
+    let data = fs::read(&source).await.context("Failed to read certificate file")?;
+    let target_filename = match CertificateFormat::detect(&source, &data) {
+        CertificateFormat::Pkcs12 if source_path.ends_with(".pfx") || source_path.ends_with(".p12") => "certificate.pfx",
+        CertificateFormat::Pkcs12 => {
+            return Err(anyhow::anyhow!("Source file must be a .pfx, .p12 or .pem certificate file"));
+        }
+        CertificateFormat::Pem => "certificate.pem",
     };
 
     let target = target_dir.join(target_filename);
@@ -247,37 +931,196 @@ async fn init_certificate(source_path: &str, _password: Option<&str>) -> Result<
         .await
         .context("Failed to copy certificate file")?;
 
-    println!("Certificate copied to: {}", target.display());
-    println!("Certificate will now be auto-discovered by mmcli.");
-    println!("You can omit certificate_path from your credentials file.");
+    // A separate PEM key goes next to the certificate, where it's found automatically
+    if let Some(key_path) = key_path {
+        let key_target = target.with_extension("key");
+        fs::copy(key_path, &key_target)
+            .await
+            .context("Failed to copy private key file")?;
+        println!("{}", t!("cert.key_copied", path = key_target.display()));
+    }
+
+    println!("{}", t!("cert.copied", path = target.display()));
+    println!("{}", t!("cert.auto_discovered"));
+    println!("{}", t!("cert.omit_path"));
+
+    Ok(())
+}
+
+/// Age past which `refresh` re-fetches a part: `--older-than` days, else the `[cache]` TTL
+///
+/// Values too large for a duration are rejected rather than overflowing.
+fn refresh_max_age(older_than_days: Option<u64>, cache: &CacheConfig) -> Result<Option<chrono::Duration>, ClientError> {
+    match older_than_days {
+        Some(days) => i64::try_from(days).ok().and_then(chrono::Duration::try_days).map(Some)
+            .ok_or_else(|| ClientError::Generic(format!("--older-than {} is too many days", days))),
+        None => Ok(cache.ttl()),
+    }
+}
+
+/// Part numbers piped in for `--stdin`: the first word of each line, skipping blank lines and `#` comments
+fn read_stdin_parts() -> std::io::Result<Vec<String>> {
+    let mut parts = Vec::new();
+    for line in std::io::stdin().lines() {
+        let line = line?;
+        if let Some(part) = line.split_whitespace().next().filter(|word| !word.starts_with('#')) {
+            parts.push(part.to_string());
+        }
+    }
+    Ok(parts)
+}
+
+/// Run `action` on each part in turn, returning its results and the (part, error) pairs that failed
+///
+/// A failed part doesn't stop the batch, except for an authentication error, which every
+/// later part would hit too.
+async fn run_part_batch<'a, T, F, Fut>(client: &'a McmasterClient, parts: Vec<String>, action: F) -> Result<(Vec<T>, Vec<(String, String)>)>
+where
+    F: Fn(&'a McmasterClient, String) -> Fut,
+    Fut: std::future::Future<Output = Result<T, ClientError>> + 'a,
+{
+    let mut done = Vec::new();
+    let mut failed = Vec::new();
+    for part in parts {
+        match action(client, part.clone()).await {
+            Ok(result) => done.push(result),
+            Err(e @ (ClientError::NotAuthenticated | ClientError::AuthExpired)) => return Err(e.into()),
+            Err(e) => failed.push((part, e.to_string())),
+        }
+    }
+    Ok((done, failed))
+}
 
+/// Print the parts a batch couldn't handle; any failure fails the command
+fn finish_part_batch(failed: &[(String, String)]) -> Exit {
+    display::print_part_failures(failed);
+    if failed.is_empty() {
+        Exit::Success
+    } else {
+        Exit::Failure
+    }
+}
+
+/// Write a QR code linking to a part for `--qr`, saying where it went unless output must stay machine-readable
+fn write_part_qr(config: &Config, part_number: &str, path: &std::path::Path, announce: bool) -> Result<()> {
+    let url = mmcli::utils::qr::part_url(config.qr_url(), part_number);
+    mmcli::utils::qr::write_qr(&url, path)?;
+    if announce {
+        println!("{}", decorate(Icon::File, t!("qr.written", url = url, path = path.display())));
+    }
     Ok(())
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     let cli = Cli::parse();
+    display::set_plain_output(cli.plain || display::plain_output_from_env());
+
+    let metrics_file = cli.metrics_file.clone();
+    let metrics = Arc::new(Metrics::default());
+    let result = run(cli, metrics.clone()).await;
+
+    if let Some(path) = metrics_file {
+        if let Err(e) = write_atomic(&path, metrics.render(result.is_ok()).as_bytes()) {
+            eprintln!("{}", decorate(Icon::Warning, format!("Could not write metrics to {}: {}", path.display(), e)));
+        }
+    }
+
+    match result {
+        Ok(exit) => exit.into(),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            Exit::for_error(&e).into()
+        }
+    }
+}
+
+async fn run(cli: Cli, metrics: Arc<Metrics>) -> Result<Exit> {
+    // Defaults from config.toml; command-line flags override them
+    let mut config = Config::load()?;
+    config.network.insecure = cli.insecure;
+    config.network.connect_timeout = cli.connect_timeout.unwrap_or(config.network.connect_timeout);
+    config.network.timeout = cli.timeout.unwrap_or(config.network.timeout);
+    config.network.deadline = cli.deadline.or(config.network.deadline);
+
+    let verbose = is_verbose(&cli, &config);
+
+    // Dropping the command at the deadline abandons any requests still in flight
+    let mut client = None;
+    let result = match config.network.deadline() {
+        Some(deadline) => tokio::time::timeout(deadline, run_command(cli, config, metrics, &mut client))
+            .await
+            .unwrap_or_else(|_| Err(ClientError::DeadlineExceeded(deadline.as_secs()).into())),
+        None => run_command(cli, config, metrics, &mut client).await,
+    };
+
+    // Warnings matter most when the command failed, so they come before the error
+    if let Some(client) = &client {
+        display::print_warnings(&client.take_warnings(), verbose);
+    }
+    result
+}
+
+/// Whether to show detailed output; stdout carries nothing but JSON-RPC responses in rpc mode
+fn is_verbose(cli: &Cli, config: &Config) -> bool {
+    (cli.verbose || config.verbose) && !matches!(cli.command, Commands::Rpc)
+}
+
+/// Build the client into `slot`, so its warnings outlive a failed command, and run the command
+async fn run_command(cli: Cli, config: Config, metrics: Arc<Metrics>, slot: &mut Option<McmasterClient>) -> Result<Exit> {
+    let mut exit = Exit::Success;
+
+    let rpc = matches!(cli.command, Commands::Rpc);
+    let verbose = is_verbose(&cli, &config);
+    if config.plain {
+        display::set_plain_output(true);
+    }
     
     // Load credentials first to create client with certificate
-    let credentials = if let Some(creds_path) = &cli.credentials {
-        Some(load_credentials_from_file(creds_path).await?)
-    } else {
-        // Try to load default credentials
-        load_default_credentials().await.ok()
+    let credentials_file = cli.credentials.as_ref().map(PathBuf::from).or_else(default_credentials_path);
+    let credentials = match (&cli.credentials, &credentials_file) {
+        (Some(creds_path), _) => Some(load_credentials_from_file(creds_path).await?),
+        // Default credentials are optional; commands that need them say so later
+        (None, Some(path)) => load_credentials_from_file(path.to_string_lossy().as_ref()).await.ok(),
+        (None, None) => None,
     };
     
-    // Create client with quiet mode by default, verbose when requested
-    let mut client = if cli.verbose {
-        // Show detailed certificate and authentication messages
-        McmasterClient::new_with_credentials(credentials)?
-    } else {
-        // Clean output by default
-        McmasterClient::new_with_credentials_quiet(credentials)?
+    // Create client with quiet mode by default; verbose shows certificate and authentication messages
+    let mut certificate_error = None;
+    let client = match McmasterClient::new_with_network(credentials.clone(), &config.network, !verbose) {
+        Ok(client) => client,
+        // `auth status` reports a certificate that won't load instead of failing on it
+        Err(e @ ClientError::Certificate(_)) if matches!(cli.command, Commands::Auth { .. }) => {
+            certificate_error = Some(e.to_string());
+            McmasterClient::new_with_network(None, &config.network, !verbose)?
+        }
+        Err(e) => return Err(e.into()),
     };
+    let client = slot.insert(client);
+
+    client.set_download_jobs(cli.jobs.or(config.jobs).unwrap_or(DEFAULT_DOWNLOAD_JOBS));
+    client.set_download_dir(config.download_dir());
+    if let Some(currency) = &config.currency {
+        client.set_currency(currency);
+    }
+    client.set_rate_limit(config.rate_limit.requests_per_second, config.rate_limit.burst);
+    client.set_dxf_postprocess(config.postprocess.dxf.clone());
+    client.set_auto_login(config.auto_login());
+    client.set_alert_command(config.alert_command.clone());
+    client.set_show_progress(!display::is_plain_output() && !rpc);
+    client.set_offline(cli.offline);
+    client.set_metrics(metrics);
 
     // Load existing token if available
     client.load_token().await?;
 
+    if let Some(dir) = &cli.record {
+        client.set_record_dir(dir)?;
+    }
+    if let Some(dir) = &cli.replay {
+        client.set_replay_dir(dir)?;
+    }
+
     match cli.command {
         Commands::Login { username, password } => {
             match (username, password) {
@@ -294,9 +1137,16 @@ async fn main() -> Result<()> {
                     ));
                 }
             }
+            println!("{}", decorate(Icon::Success, t!("auth.login_success")));
         }
         Commands::Logout => {
-            client.logout().await?;
+            match client.logout().await? {
+                LogoutStatus::LoggedOut => println!("{}", decorate(Icon::Success, t!("auth.logout_success"))),
+                LogoutStatus::RequestFailed => {
+                    eprintln!("{}", decorate(Icon::Warning, t!("auth.logout_failed")));
+                }
+                LogoutStatus::NoSession => println!("{}", decorate(Icon::Info, t!("auth.no_session"))),
+            }
         }
         Commands::InitCredentials { path, json } => {
             let template_path = match path {
@@ -333,59 +1183,636 @@ async fn main() -> Result<()> {
                 template_path
             };
 
-            client.save_credentials_template(&expanded_path).await?;
+            let saved_path = client.save_credentials_template(&expanded_path).await?;
+            println!("{}", decorate(Icon::Success, t!("auth.credentials_saved", path = saved_path.display())));
         }
-        Commands::InitCert { source, password } => {
-            init_certificate(&source, password.as_deref()).await?;
+        Commands::InitCert { source, password, key } => {
+            init_certificate(&source, password.as_deref(), key.as_deref()).await?;
         }
-        Commands::Add { product } => {
-            client.add_product(&product).await?;
+        Commands::Cert { command: CertCommands::Status { json } } => {
+            let info = client.certificate_status()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(info)?);
+            } else {
+                display::print_certificate_status(info);
+            }
+            if info.is_expired(chrono::Utc::now()) {
+                exit = Exit::Auth;
+            }
+        }
+        Commands::Auth { command: AuthCommands::Status { json } } => {
+            let mut status = client.auth_status(credentials.as_ref(), credentials_file.as_deref());
+            status.certificate_error = certificate_error;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&status)?);
+            } else {
+                display::print_auth_status(&status);
+            }
+            if !status.is_usable(chrono::Utc::now()) {
+                exit = Exit::Auth;
+            }
+        }
+        Commands::Add { product: Some(product), .. } => {
+            let detail = client.add_product(&product).await?;
+            display::print_added(&product, &detail);
+        }
+        Commands::Add { product: None, .. } => {
+            let (_, failed) = run_part_batch(client, read_stdin_parts()?, |client, product| async move {
+                let detail = client.add_product(&product).await?;
+                display::print_added(&product, &detail);
+                Ok(())
+            })
+            .await?;
+            exit = finish_part_batch(&failed);
         }
         Commands::Remove { product } => {
             client.remove_product(&product).await?;
+            println!("{}", decorate(Icon::Success, t!("product.removed", product = product)));
         }
-        Commands::Info { product, output, fields } => {
-            client.get_product(&product, output, &fields).await?;
-        }
-        Commands::Price { product, output } => {
-            client.get_price(&product, output).await?;
-        }
-        Commands::Changes { start } => {
-            client.get_changes(&start).await?;
-        }
-        Commands::Image { product, output } => {
-            client.download_images(&product, output.as_deref()).await?;
-        }
-        Commands::Cad { product, output, dwg, step, dxf, iges, solidworks, sat, edrw, pdf, all } => {
-            // Collect selected formats
-            let mut formats = Vec::new();
-            if dwg { formats.push("dwg"); }
-            if step { formats.push("step"); }
-            if dxf { formats.push("dxf"); }
-            if iges { formats.push("iges"); }
-            if solidworks { formats.push("solidworks"); }
-            if sat { formats.push("sat"); }
-            if edrw { formats.push("edrw"); }
-            if pdf { formats.push("pdf"); }
-            
-            // If no specific formats selected or --all is specified, download all
-            let download_all = all || formats.is_empty();
-            
-            client.download_cad(&product, output.as_deref(), &formats, download_all).await?;
+        Commands::Info { product: None, output, fields, .. } => {
+            let output = config.output_format(output);
+            let (products, failed) = run_part_batch(client, read_stdin_parts()?, |client, product| async move {
+                client.get_product(&product).await
+            })
+            .await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&products)?),
+                OutputFormat::Csv => output::products_rows(&products, &fields).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::products_rows(&products, &fields).to_table()),
+                OutputFormat::Human => products.iter().for_each(|detail| display::print_product(detail, &fields)),
+            }
+            exit = finish_part_batch(&failed);
+        }
+        Commands::Info { product: Some(product), output, fields, qr, .. } => {
+            let output = config.output_format(output);
+            let detail = client.get_product(&product).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&detail)?),
+                OutputFormat::Csv => output::write_product_csv(std::io::stdout(), &detail, &fields)?,
+                OutputFormat::Table => println!("{}", output::product_rows(&detail, &fields).to_table()),
+                OutputFormat::Human => display::print_product(&detail, &fields),
+            }
+            if let Some(qr) = qr {
+                write_part_qr(&config, &detail.part_number, &qr, output == OutputFormat::Human)?;
+            }
+        }
+        Commands::Diff { first, second, differences_only, output } => {
+            let output = config.output_format(output);
+            let diff = client.compare_products(&first, &second).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+                OutputFormat::Csv => output::product_diff_rows(&diff, differences_only).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::product_diff_rows(&diff, differences_only).to_table()),
+                OutputFormat::Human => display::print_product_diff(&diff, differences_only),
+            }
+        }
+        Commands::Alternates { product, limit, output } => {
+            let output = config.output_format(output);
+            let (original, alternates) = client.find_alternates(&product, limit).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&alternates)?),
+                OutputFormat::Csv => output::alternate_rows(&alternates).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::alternate_rows(&alternates).to_table()),
+                OutputFormat::Human => display::print_alternates(&original, &alternates),
+            }
+            if alternates.is_empty() {
+                exit = Exit::NotFound;
+            }
+        }
+        Commands::Vendors { product, output } => {
+            let output = config.output_format(output);
+            let vendors = mmcli::integrations::vendors::Vendors::from_config(&config.vendors);
+            if vendors.is_empty() {
+                return Err(ClientError::Config("No vendors to compare with; add [[vendors]] tables to config.toml".to_string()).into());
+            }
+            let comparison = client.compare_vendors(&product, &vendors).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&comparison)?),
+                OutputFormat::Csv => output::vendor_rows(&comparison).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::vendor_rows(&comparison).to_table()),
+                OutputFormat::Human => display::print_vendor_comparison(&comparison),
+            }
+            if comparison.quotes.iter().any(|quote| quote.error.is_some()) {
+                exit = Exit::Failure;
+            }
+        }
+        Commands::Bom { command: BomCommands::Enrich { file, output, column } } => {
+            let bom = mmcli::models::bom::Bom::read(&file, column.as_deref())?;
+            let enrichment = client.enrich_bom(&bom).await?;
+            let output = output.unwrap_or_else(|| {
+                let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+                file.with_file_name(format!("{}-enriched.csv", stem))
+            });
+            let mut csv = Vec::new();
+            enrichment.bom.write_csv(&mut csv)?;
+            write_atomic(&output, &csv)?;
+            display::print_bom_enrichment(&enrichment, &output);
+            if !enrichment.failed.is_empty() {
+                exit = Exit::Failure;
+            }
         }
-        Commands::Datasheet { product, output } => {
-            client.download_datasheets(&product, output.as_deref()).await?;
+        Commands::Bom { command: BomCommands::Check { file, column, output } } => {
+            let output = config.output_format(output);
+            let bom = mmcli::models::bom::Bom::read(&file, column.as_deref())?;
+            let report = client.check_bom(&bom).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+                OutputFormat::Csv => output::bom_check_rows(&report).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::bom_check_rows(&report).to_table()),
+                OutputFormat::Human => display::print_bom_check(&report),
+            }
+            if !report.passed() {
+                exit = Exit::Failure;
+            }
         }
-        Commands::List => {
-            client.list_subscriptions()?;
+        Commands::Bom { command: BomCommands::Order { files, column, order_file, output } } => {
+            let output = config.output_format(output);
+            let boms = files.iter()
+                .map(|file| mmcli::models::bom::Bom::read(file, column.as_deref()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let order = client.order_bom(&boms).await?;
+            let order_file = order_file.unwrap_or_else(|| {
+                let stem = files[0].file_stem().unwrap_or_default().to_string_lossy();
+                files[0].with_file_name(format!("{}-order.csv", stem))
+            });
+            let mut csv = Vec::new();
+            order.write_order_csv(&mut csv)?;
+            write_atomic(&order_file, &csv)?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&order)?),
+                OutputFormat::Csv => output::purchase_order_rows(&order).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::purchase_order_rows(&order).to_table()),
+                OutputFormat::Human => display::print_purchase_order(&order, &order_file),
+            }
+            if !order.failed.is_empty() {
+                exit = Exit::Failure;
+            }
         }
-        Commands::Sync => {
-            client.sync_subscriptions().await?;
+        Commands::Price { product: None, qty: Some(quantity), pack_size, output, .. } => {
+            let output = config.output_format(output);
+            let (quotes, failed) = run_part_batch(client, read_stdin_parts()?, |client, product| async move {
+                client.quote_price(&product, quantity, pack_size).await
+            })
+            .await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&quotes)?),
+                OutputFormat::Csv => output::quotes_rows(&quotes).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::quotes_rows(&quotes).to_table()),
+                OutputFormat::Human => quotes.iter().for_each(display::print_price_quote),
+            }
+            exit = finish_part_batch(&failed);
+        }
+        Commands::Price { product: None, qty: None, output, .. } => {
+            let output = config.output_format(output);
+            let results = client.get_prices(&read_stdin_parts()?).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+                OutputFormat::Csv => output::write_price_all_csv(std::io::stdout(), &results)?,
+                OutputFormat::Table => println!("{}", output::price_all_rows(&results).to_table()),
+                OutputFormat::Human => display::print_price_all(&results),
+            }
+            if results.iter().any(|result| result.error.is_some()) {
+                exit = Exit::Failure;
+            }
+        }
+        Commands::Alerts { command: AlertsCommands::Add { product, above, below } } => {
+            for (direction, threshold, key) in [(AlertDirection::Above, above, "alerts.added.above"), (AlertDirection::Below, below, "alerts.added.below")] {
+                if let Some(threshold) = threshold {
+                    client.add_alert(&product, direction, threshold)?;
+                    println!("{}", decorate(Icon::Success, t!(key, product = product.to_uppercase(), threshold = threshold)));
+                }
+            }
+        }
+        Commands::Alerts { command: AlertsCommands::Remove { product } } => {
+            let removed = client.remove_alerts(&product)?;
+            println!("{}", decorate(Icon::Success, t!("alerts.removed", count = removed, product = product.to_uppercase())));
+        }
+        Commands::Alerts { command: AlertsCommands::List { output } } => {
+            let output = config.output_format(output);
+            let alerts = client.alerts()?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&alerts)?),
+                OutputFormat::Csv => output::alert_rows(&alerts).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::alert_rows(&alerts).to_table()),
+                OutputFormat::Human => display::print_alerts(&alerts),
+            }
+        }
+        Commands::Alerts { command: AlertsCommands::Check } => {
+            let (results, triggers) = client.check_alerts().await?;
+            if results.is_empty() {
+                println!("{}", decorate(Icon::Empty, t!("alerts.empty")));
+            } else if triggers.is_empty() {
+                println!("{}", decorate(Icon::Success, t!("alerts.quiet", count = results.len())));
+            }
+            display::print_alert_triggers(&triggers);
+            for result in &results {
+                if let Some(error) = &result.error {
+                    eprintln!("{}", decorate(Icon::Error, format!("{}: {}", result.part_number, error)));
+                    exit = Exit::Failure;
+                }
+            }
+        }
+        Commands::Price { product: Some(product), qty: Some(quantity), pack_size, output, .. } => {
+            let output = config.output_format(output);
+            let quote = client.quote_price(&product, quantity, pack_size).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&quote)?),
+                OutputFormat::Csv => output::quote_rows(&quote).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::quote_rows(&quote).to_table()),
+                OutputFormat::Human => {
+                    display::print_prices(&product, &client.get_price(&product).await?);
+                    display::print_price_quote(&quote);
+                }
+            }
+        }
+        Commands::Price { product: Some(product), qty: None, output, .. } => {
+            let output = config.output_format(output);
+            let prices = client.get_price(&product).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&prices)?),
+                OutputFormat::Csv => output::write_prices_csv(std::io::stdout(), &product, &prices)?,
+                OutputFormat::Table => println!("{}", output::price_rows(&product, &prices).to_table()),
+                OutputFormat::Human => display::print_prices(&product, &prices),
+            }
+        }
+        Commands::PriceAll { tag, output } => {
+            let output = config.output_format(output);
+            let (results, triggers) = client.get_price_all_with_alerts(tag.as_deref()).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+                OutputFormat::Csv => output::write_price_all_csv(std::io::stdout(), &results)?,
+                OutputFormat::Table => println!("{}", output::price_all_rows(&results).to_table()),
+                OutputFormat::Human => display::print_price_all(&results),
+            }
+            display::print_alert_triggers(&triggers);
+            if results.iter().any(|result| result.error.is_some()) {
+                exit = Exit::Failure;
+            }
+        }
+        Commands::Changes { start, end, subscribed_only, output } => {
+            let output = config.output_format(output);
+            let changes = client.get_changes(&start, end.as_deref(), subscribed_only).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&changes)?),
+                OutputFormat::Csv => output::write_changes_csv(std::io::stdout(), &changes)?,
+                OutputFormat::Table => println!("{}", output::change_rows(&changes).to_table()),
+                OutputFormat::Human => display::print_changes(&changes),
+            }
+        }
+        Commands::Image { product, output, force } => {
+            client.set_force_download(force);
+            let results = client.download_images(&product, output.as_deref()).await?;
+            display::print_download_summary("download.kind.images", &product, &results);
+            exit = Exit::for_downloads(&results);
+        }
+        Commands::Cad { product: None, output, force, extract, embed_metadata, formats, .. } => {
+            let (formats, download_all) = formats.selected(&config.cad_formats);
+            let formats: Vec<&str> = formats.iter().map(String::as_str).collect();
+            client.set_force_download(force);
+            client.set_extract_archives(extract);
+            client.set_embed_metadata(embed_metadata);
+            let report = client.download_cad_parts(&read_stdin_parts()?, output.as_deref(), &formats, download_all).await?;
+            display::print_bulk_download_report(&report);
+            if !report.failures.is_empty() {
+                exit = Exit::PartialDownload;
+            }
+        }
+        Commands::Cad { product: Some(product), output, force, extract, embed_metadata, formats, .. } => {
+            let (formats, download_all) = formats.selected(&config.cad_formats);
+            let formats: Vec<&str> = formats.iter().map(String::as_str).collect();
+            client.set_force_download(force);
+            client.set_extract_archives(extract);
+            client.set_embed_metadata(embed_metadata);
+            let results = client.download_cad(&product, output.as_deref(), &formats, download_all).await?;
+            display::print_download_summary("download.kind.cad", &product, &results);
+            exit = Exit::for_downloads(&results);
+        }
+        Commands::CadAll { output, force, extract, embed_metadata, tag, formats } => {
+            let (formats, download_all) = formats.selected(&config.cad_formats);
+            let formats: Vec<&str> = formats.iter().map(String::as_str).collect();
+            client.set_force_download(force);
+            client.set_extract_archives(extract);
+            client.set_embed_metadata(embed_metadata);
+            cancel_on_ctrl_c(client);
+            let report = client.download_cad_all(output.as_deref(), &formats, download_all, tag.as_deref()).await?;
+            display::print_bulk_download_report(&report);
+            if !report.remaining.is_empty() {
+                exit = Exit::Cancelled;
+            } else if !report.failures.is_empty() {
+                exit = Exit::PartialDownload;
+            }
+        }
+        Commands::Verify { product, root, output } => {
+            let output = config.output_format(output);
+            let checks = client.verify_downloads(&product, root.as_deref()).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&checks)?),
+                OutputFormat::Csv => output::file_check_rows(&checks).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::file_check_rows(&checks).to_table()),
+                OutputFormat::Human => display::print_file_checks(&checks),
+            }
+            if checks.iter().any(|check| check.status != FileStatus::Ok) {
+                exit = Exit::Failure;
+            }
+        }
+        Commands::SwLibrary { products, all, tag, output, force } => {
+            let parts = if all {
+                client.list_tracked_parts(tag.as_deref())?.into_iter().map(|part| part.part_number).collect()
+            } else {
+                products
+            };
+            client.set_force_download(force);
+            let report = client.build_solidworks_library(&parts, output.as_deref().map(std::path::Path::new)).await?;
+            display::print_solidworks_library_report(&report);
+            if !report.downloads.failures.is_empty() {
+                exit = Exit::PartialDownload;
+            }
+        }
+        Commands::Onshape { product, document, workspace } => {
+            let onshape = config.onshape.as_ref()
+                .ok_or_else(|| ClientError::Config("Add an [onshape] table with access_key and secret_key to config.toml".to_string()))?;
+            let target = OnshapeTarget::parse(&document, workspace.as_deref())?;
+            let import = client.import_to_onshape(&product, onshape, &target).await?;
+            println!("{}", decorate(Icon::Success, t!("onshape.imported", part = import.part_number, name = import.name, element = import.element_id)));
+        }
+        Commands::Freecad { product, output, library, force } => {
+            let library_dir = if library {
+                let freecad = config.freecad.as_ref()
+                    .ok_or_else(|| ClientError::Config("Add a [freecad] table with library_dir to config.toml".to_string()))?;
+                Some(freecad.library_dir())
+            } else {
+                None
+            };
+            client.set_force_download(force);
+            let export = client.export_freecad(&product, output.as_deref(), library_dir.as_deref()).await?;
+            display::print_download_summary("download.kind.cad", &product, &export.downloads);
+            println!("{}", decorate(Icon::File, t!("freecad.sidecar", path = export.sidecar.display())));
+            if let Some(library_dir) = &library_dir {
+                println!("{}", decorate(Icon::Success, t!("freecad.copied", count = export.library_files.len(), path = library_dir.display())));
+            }
+            exit = Exit::for_downloads(&export.downloads);
+        }
+        Commands::Package { products, all, tag, output, bundle, force } => {
+            let parts = if all {
+                client.list_tracked_parts(tag.as_deref())?.into_iter().map(|part| part.part_number).collect()
+            } else {
+                products
+            };
+            client.set_force_download(force);
+            let report = client.package_parts(&parts, output.as_deref().map(std::path::Path::new), bundle.as_deref()).await?;
+            display::print_package_report(&report);
+            if !report.downloads.failures.is_empty() {
+                exit = Exit::PartialDownload;
+            }
+        }
+        Commands::Label { product, format, output, qr } if output.as_deref() == Some(std::path::Path::new("-")) => {
+            use std::io::Write;
+            let detail = client.get_product(&product).await?;
+            std::io::stdout().write_all(&Label::from_product(&detail).render(format)?)?;
+            if let Some(qr) = qr {
+                write_part_qr(&config, &detail.part_number, &qr, false)?;
+            }
+        }
+        Commands::Label { product, format, output, qr } => {
+            let path = client.write_label(&product, format, output.as_deref()).await?;
+            println!("{}", decorate(Icon::File, t!("label.written", path = path.display())));
+            if let Some(qr) = qr {
+                write_part_qr(&config, &product.trim().to_uppercase(), &qr, true)?;
+            }
+        }
+        Commands::Report { products, tag, format, output } => {
+            let parts = match tag {
+                Some(tag) => client.list_tracked_parts(Some(&tag))?.into_iter().map(|part| part.part_number).collect(),
+                None => products,
+            };
+            let (reports, failed) = run_part_batch(client, parts, |client, product| async move {
+                client.part_report(&product).await
+            })
+            .await?;
+            let document = report::render(&reports, format);
+            match output {
+                Some(path) => {
+                    write_atomic(&path, document.as_bytes())?;
+                    println!("{}", decorate(Icon::File, t!("report.written", count = reports.len(), path = path.display())));
+                }
+                None => print!("{}", document),
+            }
+            exit = finish_part_batch(&failed);
+        }
+        Commands::Site { command: SiteCommands::Build { dir, tag } } => {
+            let report = client.build_site(&dir, tag.as_deref()).await?;
+            println!("{}", decorate(Icon::File, t!("site.written", count = report.pages, path = report.dir.join("index.html").display())));
+            exit = finish_part_batch(&report.failures);
+        }
+        Commands::Datasheet { command: Some(DatasheetCommands::Merge { products, tag, bom, output }), .. } => {
+            let parts = match (tag, bom) {
+                (Some(tag), _) => client.list_tracked_parts(Some(&tag))?.into_iter().map(|part| part.part_number).collect(),
+                (None, Some(bom)) => mmcli::models::bom::Bom::read(&bom, None)?.part_numbers(),
+                (None, None) => products,
+            };
+            let report = client.merge_datasheets(&parts, &output)?;
+            display::print_datasheet_merge(&report);
+            if !report.failed.is_empty() {
+                exit = Exit::Failure;
+            }
+        }
+        Commands::Datasheet { product, output, force, index, command: None } => {
+            let product = product.expect("clap requires a product without a subcommand");
+            client.set_force_download(force);
+            let results = client.download_datasheets(&product, output.as_deref()).await?;
+            display::print_download_summary("download.kind.datasheets", &product, &results);
+            if index || config.index_datasheets {
+                let indexed = client.index_datasheets(&product, &results).await;
+                display::print_datasheets_indexed(indexed);
+            }
+            exit = Exit::for_downloads(&results);
+        }
+        Commands::Open { product, datasheet, output, print } => {
+            let target = if datasheet {
+                client.downloaded_datasheet(&product, output.as_deref())?.display().to_string()
+            } else {
+                mmcli::utils::qr::part_url(config.qr_url(), &product)
+            };
+            if print {
+                println!("{}", target);
+            } else {
+                mmcli::utils::open::open_in_default_app(&target)?;
+                println!("{}", decorate(Icon::Success, t!("open.opened", target = target)));
+            }
+        }
+        Commands::Search { query, limit, output } => {
+            let output = config.output_format(output);
+            let matches = client.search_datasheets(&query.join(" "), limit)?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&matches)?),
+                OutputFormat::Csv => output::write_datasheet_matches_csv(std::io::stdout(), &matches)?,
+                OutputFormat::Table => println!("{}", output::datasheet_match_rows(&matches).to_table()),
+                OutputFormat::Human => display::print_datasheet_matches(&matches),
+            }
+            if matches.is_empty() {
+                exit = Exit::NotFound;
+            }
+        }
+        Commands::List { tag, category, mut spec, material, status, part_numbers, output } => {
+            let output = config.output_format(output);
+            spec.extend(material.map(|material| ("Material".to_string(), material)));
+            let filter = PartFilter { category, specs: spec, status };
+            let parts = client.find_tracked_parts(tag.as_deref(), &filter)?;
+            match output {
+                _ if part_numbers => {
+                    for part in &parts {
+                        println!("{}", part.part_number);
+                    }
+                }
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&parts)?),
+                OutputFormat::Csv => output::write_tracked_parts_csv(std::io::stdout(), &parts)?,
+                OutputFormat::Table => println!("{}", output::tracked_part_rows(&parts).to_table()),
+                OutputFormat::Human if parts.is_empty() && !filter.is_empty() => {
+                    println!("{}", decorate(Icon::Empty, t!("list.no_match")));
+                }
+                OutputFormat::Human => display::print_subscriptions(&client.subscription_file_path()?, &parts, verbose),
+            }
+        }
+        Commands::Note { product, text, clear, set, unset } => {
+            if text.is_some() || clear {
+                client.set_part_note(&product, text.as_deref())?;
+            }
+            for pair in &set {
+                let (key, value) = pair.split_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("Metadata must be given as KEY=VALUE, got '{}'", pair))?;
+                client.set_part_metadata(&product, key, Some(value))?;
+            }
+            for key in &unset {
+                client.set_part_metadata(&product, key, None)?;
+            }
+            display::print_tracked_part(&client.tracked_part(&product)?);
+        }
+        Commands::Tag { product, tags, remove } => {
+            let current = if remove {
+                client.untag_part(&product, &tags)?
+            } else {
+                client.tag_part(&product, &tags)?
+            };
+            display::print_tags(&product.to_uppercase(), &current);
+        }
+        Commands::Refresh { older_than } => {
+            let max_age = refresh_max_age(older_than, &config.cache)?;
+            cancel_on_ctrl_c(client);
+            let report = client.refresh_cache(max_age).await?;
+            display::print_refresh_report(&report);
+            if !report.remaining.is_empty() {
+                exit = Exit::Cancelled;
+            } else if !report.failed.is_empty() {
+                exit = Exit::Failure;
+            }
+        }
+        Commands::Sync { remote: Some(remote) } => {
+            let remote = remote.or_else(|| config.team_remote.clone())
+                .ok_or_else(|| ClientError::Config("No remote given; pass --remote PATH_OR_URL or set team_remote in config.toml".to_string()))?;
+            let report = client.sync_team(&TeamRemote::parse(&remote))?;
+            display::print_team_sync_report(&report);
+        }
+        Commands::Xref { command: XrefCommands::Get { product } } => {
+            match client.item_number(&product)? {
+                Some(item_number) => println!("{}", item_number),
+                None => {
+                    eprintln!("{}", decorate(Icon::Empty, t!("xref.none", product = product.to_uppercase())));
+                    exit = Exit::NotFound;
+                }
+            }
+        }
+        Commands::Xref { command: XrefCommands::Set { product, item_number } } => {
+            client.set_item_number(&product, Some(&item_number))?;
+            println!("{}", decorate(Icon::Success, t!("xref.set", product = product.to_uppercase(), item = item_number.trim())));
+        }
+        Commands::Xref { command: XrefCommands::Unset { product } } => {
+            if client.set_item_number(&product, None)? {
+                println!("{}", decorate(Icon::Success, t!("xref.unset", product = product.to_uppercase())));
+            } else {
+                println!("{}", decorate(Icon::Empty, t!("xref.none", product = product.to_uppercase())));
+            }
+        }
+        Commands::Xref { command: XrefCommands::List { output } } => {
+            let output = config.output_format(output);
+            let crosswalk = client.crosswalk()?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&crosswalk)?),
+                OutputFormat::Csv => output::crosswalk_rows(&crosswalk).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::crosswalk_rows(&crosswalk).to_table()),
+                OutputFormat::Human => display::print_crosswalk(&crosswalk),
+            }
+        }
+        Commands::Xref { command: XrefCommands::Import { file } } => {
+            let changed = client.import_crosswalk(&file)?;
+            println!("{}", decorate(Icon::Success, t!("xref.imported", count = changed, file = file.display())));
+        }
+        Commands::Undo { list: true } => {
+            display::print_journal(&client.journal().entries()?);
+        }
+        Commands::Undo { list: false } => {
+            match client.undo_last().await? {
+                Some(report) => {
+                    display::print_undo_report(&report);
+                    if !report.failed.is_empty() {
+                        exit = Exit::Failure;
+                    }
+                }
+                None => println!("{}", decorate(Icon::Empty, t!("undo.none"))),
+            }
+        }
+        Commands::Sync { remote: None } => {
+            cancel_on_ctrl_c(client);
+            let report = client.sync_subscriptions().await?;
+            display::print_sync_report(&report);
+            if !report.remaining.is_empty() {
+                exit = Exit::Cancelled;
+            }
         }
         Commands::Import { file } => {
-            client.import_subscriptions(&file)?;
+            let imported_count = client.import_subscriptions(&file)?;
+            if verbose {
+                println!("{}", decorate(Icon::Download, t!("import.done", count = imported_count, file = file)));
+            }
+        }
+        Commands::Export { target: Some(ExportTarget::Kicad { product, models_dir }), .. } => {
+            let export = client.export_kicad(&product, models_dir.as_deref().map(std::path::Path::new)).await?;
+            if models_dir.is_some() {
+                display::print_download_summary("download.kind.cad", &product, &export.downloads);
+                exit = Exit::for_downloads(&export.downloads);
+            }
+            println!("{}", export.fields.to_sexpr());
+        }
+        Commands::Export { file, target: None } => {
+            let file = file.expect("clap requires a file without a subcommand");
+            let exported_count = client.export_subscriptions(&file)?;
+            println!("{}", decorate(Icon::Success, t!("export.done", count = exported_count, file = file)));
+        }
+        Commands::Snapshot { output, command: None } => {
+            let (snapshot, path) = client.take_snapshot(output.as_deref())?;
+            println!("{}", decorate(Icon::Success, t!("snapshot.done", count = snapshot.parts.len(), file = path.display())));
+        }
+        Commands::Snapshot { command: Some(SnapshotCommands::Diff { from, to, output }), .. } => {
+            let output = config.output_format(output);
+            let diff = Snapshot::read(&from)?.diff(&Snapshot::read(&to)?);
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+                OutputFormat::Csv => output::snapshot_diff_rows(&diff).write_csv(std::io::stdout())?,
+                OutputFormat::Table => println!("{}", output::snapshot_diff_rows(&diff).to_table()),
+                OutputFormat::Human => display::print_snapshot_diff(&diff),
+            }
+        }
+        Commands::Selftest { part } => {
+            let report = client.selftest(&part).await;
+            display::print_selftest_report(&report);
+            if !report.passed() {
+                exit = Exit::Failure;
+            }
+        }
+        Commands::Rpc => {
+            let input = tokio::io::BufReader::new(tokio::io::stdin());
+            mmcli::rpc::serve(client, &config, input, tokio::io::stdout()).await?;
         }
     }
 
-    Ok(())
+    Ok(exit)
 }
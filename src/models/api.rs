@@ -14,6 +14,8 @@ pub struct LinkItem {
 /// Product API response containing links
 #[derive(Debug, Deserialize)]
 pub struct ProductResponse {
+    #[serde(rename = "DetailDescription", default)]
+    pub detail_description: String,
     #[serde(rename = "Links")]
     pub links: Option<Vec<LinkItem>>,
 }
@@ -73,6 +75,7 @@ impl CadFormat {
 
 /// Product links for downloads
 pub struct ProductLinks {
+    pub description: String, // Product description from the same response, e.g. for STEP headers
     pub images: Vec<String>,
     pub cad: Vec<CadFile>,
     pub datasheets: Vec<String>,
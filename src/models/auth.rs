@@ -1,5 +1,6 @@
 //! Authentication models
 
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Login request payload
@@ -17,10 +18,19 @@ pub struct LoginResponse {
     #[serde(rename = "AuthToken")]
     pub token: String,
     #[serde(rename = "ExpirationTS")]
-    #[allow(dead_code)]
     pub expiration: Option<String>,
 }
 
+/// Parse a login response's ExpirationTS, which is ISO 8601 with or without a UTC offset
+pub fn parse_expiration(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    DateTime::parse_from_rfc3339(raw)
+        .map(|at| at.with_timezone(&Utc))
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%.f").ok().map(|at| at.and_utc()))
+        .or_else(|| NaiveDateTime::parse_from_str(raw, "%m/%d/%Y %I:%M:%S %p").ok().map(|at| at.and_utc()))
+}
+
 /// Error response from the API
 #[derive(Debug, Deserialize)]
 pub struct ErrorResponse {
@@ -40,5 +50,6 @@ pub struct Credentials {
     pub password: String,
     pub certificate_path: Option<String>,
     pub certificate_password: Option<String>,
+    pub certificate_key_path: Option<String>, // Private key for a PEM certificate kept in its own file
     pub subscriptions_file: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,141 @@
+//! Bills of materials read from CSV
+
+use crate::utils::error::{ClientError, Result};
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Header names recognized as the McMaster-Carr part number column, most specific first
+const PART_COLUMN_NAMES: &[&str] = &[
+    "mcmaster part number", "mcmaster-carr part number", "mcmaster pn", "mcmaster-carr pn", "mcmaster part", "mcmaster-carr", "mcmaster",
+    "part number", "part no", "part #", "part", "pn", "mpn",
+];
+
+/// Header names recognized as the quantity column
+const QUANTITY_COLUMN_NAMES: &[&str] = &["quantity", "qty", "qty per assembly", "count"];
+
+/// A CSV bill of materials, kept as text so enriched copies write back every original column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bom {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub line_numbers: Vec<u64>, // Line in the file where each row starts
+    pub part_column: usize,
+    pub quantity_column: Option<usize>,
+}
+
+impl Bom {
+    /// Read a BOM file; `column` names the part number column, otherwise it's found by header
+    pub fn read(path: &Path, column: Option<&str>) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Self::from_reader(file, column)
+    }
+
+    /// Parse a BOM from CSV with a header row
+    pub fn from_reader<R: Read>(mut reader: R, column: Option<&str>) -> Result<Self> {
+        // Read it all up front so line numbers can count the blank lines the CSV reader skips
+        let mut text = Vec::new();
+        reader.read_to_end(&mut text)?;
+        let mut csv = csv::ReaderBuilder::new().flexible(true).trim(csv::Trim::All).from_reader(text.as_slice());
+        let headers: Vec<String> = csv.headers().map_err(csv_error)?.iter().map(str::to_string).collect();
+        let part_column = match column {
+            Some(column) => find_column(&headers, &[column])
+                .ok_or_else(|| ClientError::Parse(format!("No column named '{}'", column)))?,
+            None => find_column(&headers, PART_COLUMN_NAMES)
+                .ok_or_else(|| ClientError::Parse("No part number column found; name it with --column".to_string()))?,
+        };
+
+        let quantity_column = find_column(&headers, QUANTITY_COLUMN_NAMES);
+
+        let mut rows = Vec::new();
+        let mut line_numbers = Vec::new();
+        for record in csv.records() {
+            let record = record.map_err(csv_error)?;
+            let mut row: Vec<String> = record.iter().map(str::to_string).collect();
+            if row.iter().all(String::is_empty) {
+                continue;
+            }
+            row.resize(headers.len().max(row.len()), String::new());
+            rows.push(row);
+            // The reported position can be the start of blank lines skipped before the record
+            let mut offset = record.position().map_or(0, |position| position.byte() as usize);
+            while matches!(text.get(offset), Some(b'\r' | b'\n')) {
+                offset += 1;
+            }
+            line_numbers.push(text[..offset].iter().filter(|&&byte| byte == b'\n').count() as u64 + 1);
+        }
+        Ok(Bom { headers, rows, line_numbers, part_column, quantity_column })
+    }
+
+    /// The McMaster-Carr part number on a row, upper-cased; `None` when the cell is blank
+    pub fn part_number(&self, row: &[String]) -> Option<String> {
+        row.get(self.part_column)
+            .map(|cell| cell.trim().to_uppercase())
+            .filter(|cell| !cell.is_empty())
+    }
+
+    /// The quantity on a row: `None` without a quantity column, `Some(Err(cell))` when it isn't a number
+    pub fn quantity(&self, row: &[String]) -> Option<std::result::Result<f64, String>> {
+        let cell = row.get(self.quantity_column?).map(|cell| cell.trim()).unwrap_or_default();
+        Some(cell.parse::<f64>().ok().filter(|quantity| quantity.is_finite()).ok_or_else(|| cell.to_string()))
+    }
+
+    /// Distinct part numbers in the order they first appear
+    pub fn part_numbers(&self) -> Vec<String> {
+        let mut parts: Vec<String> = Vec::new();
+        for part in self.rows.iter().filter_map(|row| self.part_number(row)) {
+            if !parts.contains(&part) {
+                parts.push(part);
+            }
+        }
+        parts
+    }
+
+    /// Write the BOM back out as CSV
+    pub fn write_csv<W: Write>(&self, writer: W) -> csv::Result<()> {
+        let mut csv = csv::WriterBuilder::new().flexible(true).from_writer(writer);
+        csv.write_record(&self.headers)?;
+        for row in &self.rows {
+            csv.write_record(row)?;
+        }
+        csv.flush()?;
+        Ok(())
+    }
+}
+
+/// Index of the first header matching one of `names`, ignoring case, spacing and punctuation
+pub(crate) fn find_column(headers: &[String], names: &[&str]) -> Option<usize> {
+    let normalize = |name: &str| name.chars().filter(|c| c.is_alphanumeric() || *c == '#').collect::<String>().to_lowercase();
+    names.iter().find_map(|name| {
+        let name = normalize(name);
+        headers.iter().position(|header| normalize(header) == name)
+    })
+}
+
+fn csv_error(error: csv::Error) -> ClientError {
+    ClientError::Parse(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_bom() {
+        let csv = "Ref,Qty,McMaster-Carr P/N\nS1,4, 91290a115\n\nS2,2,91290A115\nW1,8,\nN1,4,90591A111\n";
+        let bom = Bom::from_reader(csv.as_bytes(), None).unwrap();
+        assert_eq!(bom.part_column, 2);
+        assert_eq!(bom.quantity_column, Some(1));
+        assert_eq!(bom.rows.len(), 4);
+        assert_eq!(bom.line_numbers, vec![2, 4, 5, 6]);
+        assert_eq!(bom.quantity(&bom.rows[1]), Some(Ok(2.0)));
+        assert_eq!(bom.part_number(&bom.rows[0]).as_deref(), Some("91290A115"));
+        assert_eq!(bom.part_number(&bom.rows[2]), None);
+        assert_eq!(bom.part_numbers(), vec!["91290A115", "90591A111"]);
+
+        let csv = "Item,Vendor Code\n1,91290A115\n";
+        assert!(Bom::from_reader(csv.as_bytes(), None).is_err());
+        let bom = Bom::from_reader("Part,Qty\n91290A115,two\n".as_bytes(), None).unwrap();
+        assert_eq!(bom.quantity(&bom.rows[0]), Some(Err("two".to_string())));
+        assert_eq!(Bom::from_reader(csv.as_bytes(), Some("vendor code")).unwrap().part_column, 1);
+    }
+}
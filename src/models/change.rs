@@ -0,0 +1,106 @@
+//! Product change feed models
+
+use serde::{Deserialize, Serialize};
+
+/// A product that changed in the McMaster-Carr catalog
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangeRecord {
+    pub part_number: String,
+    pub link: Option<String>, // API path of the changed product, e.g. "/v1/products/91290A115"
+    pub change_date: Option<String>,
+    pub change_type: Option<String>,
+}
+
+/// Changes response body, either a bare list or wrapped in an object
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ChangesResponse {
+    List(Vec<RawChange>),
+    Wrapped {
+        #[serde(rename = "Changes", alias = "Links")]
+        changes: Vec<RawChange>,
+    },
+}
+
+/// A single entry in the changes feed as returned by the API
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum RawChange {
+    /// Just the product link
+    Link(String),
+    Record {
+        #[serde(rename = "PartNumber")]
+        part_number: Option<String>,
+        #[serde(rename = "Link", alias = "Value", alias = "URL")]
+        link: Option<String>,
+        #[serde(rename = "ChangeDate", alias = "Date")]
+        change_date: Option<String>,
+        #[serde(rename = "ChangeType", alias = "Type", alias = "Key")]
+        change_type: Option<String>,
+    },
+}
+
+impl ChangesResponse {
+    /// Convert the response into change records, dropping entries without a part number
+    pub fn into_records(self) -> Vec<ChangeRecord> {
+        let changes = match self {
+            ChangesResponse::List(changes) => changes,
+            ChangesResponse::Wrapped { changes } => changes,
+        };
+        changes.into_iter().filter_map(RawChange::into_record).collect()
+    }
+}
+
+impl RawChange {
+    fn into_record(self) -> Option<ChangeRecord> {
+        let (part_number, link, change_date, change_type) = match self {
+            RawChange::Link(link) => (None, Some(link), None, None),
+            RawChange::Record { part_number, link, change_date, change_type } => {
+                (part_number, link, change_date, change_type)
+            }
+        };
+
+        let part_number = part_number
+            .or_else(|| link.as_deref().and_then(part_number_from_link))?
+            .trim()
+            .to_uppercase();
+        if part_number.is_empty() {
+            return None;
+        }
+
+        Some(ChangeRecord {
+            part_number,
+            link,
+            change_date,
+            change_type,
+        })
+    }
+}
+
+/// Take the part number from the last segment of a product link
+fn part_number_from_link(link: &str) -> Option<String> {
+    link.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_response_shapes() {
+        let list = r#"["/v1/products/91290a115", {"PartNumber": "92141A008", "ChangeDate": "08/20/2025"}]"#;
+        let records = serde_json::from_str::<ChangesResponse>(list).unwrap().into_records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].part_number, "91290A115");
+        assert_eq!(records[1].change_date.as_deref(), Some("08/20/2025"));
+
+        let wrapped = r#"{"Links": [{"Key": "Updated", "Value": "/v1/products/98164A133"}]}"#;
+        let records = serde_json::from_str::<ChangesResponse>(wrapped).unwrap().into_records();
+        assert_eq!(records[0].part_number, "98164A133");
+        assert_eq!(records[0].change_type.as_deref(), Some("Updated"));
+    }
+}
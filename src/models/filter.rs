@@ -0,0 +1,88 @@
+//! Filters over cached product details, for `mmc list`
+
+use crate::models::product::ProductDetail;
+
+/// Conditions a part's cached details must all meet; text comparisons ignore case
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartFilter {
+    pub category: Option<String>, // Part of the category or family name, e.g. "screws"
+    pub specs: Vec<(String, String)>, // (attribute, value) pairs that must all be present
+    pub status: Option<String>, // "active", "discontinued" (anything not active), or an exact status
+}
+
+impl PartFilter {
+    /// Whether there's nothing to filter on, so parts don't need cached details
+    pub fn is_empty(&self) -> bool {
+        self.category.is_none() && self.specs.is_empty() && self.status.is_none()
+    }
+
+    /// Parse a `--spec` argument written as `Attribute=Value`
+    pub fn parse_spec(spec: &str) -> Result<(String, String), String> {
+        match spec.split_once('=') {
+            Some((attribute, value)) if !attribute.trim().is_empty() && !value.trim().is_empty() => {
+                Ok((attribute.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(format!("expected ATTRIBUTE=VALUE, got '{}'", spec)),
+        }
+    }
+
+    /// Whether a product meets every condition
+    pub fn matches(&self, detail: &ProductDetail) -> bool {
+        let category = self.category.as_deref().is_none_or(|category| {
+            let category = category.trim().to_lowercase();
+            [&detail.product_category, &detail.family_description]
+                .iter()
+                .any(|name| name.to_lowercase().contains(&category))
+        });
+
+        let specs = self.specs.iter().all(|(attribute, value)| {
+            detail.specifications.iter().any(|spec| {
+                spec.attribute.eq_ignore_ascii_case(attribute)
+                    && spec.values.iter().any(|candidate| candidate.trim().eq_ignore_ascii_case(value))
+            })
+        });
+
+        let status = self.status.as_deref().is_none_or(|status| match status.trim().to_lowercase().as_str() {
+            "active" => detail.is_active(),
+            "discontinued" | "inactive" => !detail.is_active(),
+            status => detail.product_status.trim().eq_ignore_ascii_case(status),
+        });
+
+        category && specs && status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::product::Specification;
+
+    #[test]
+    fn test_part_filter() {
+        let detail = ProductDetail {
+            part_number: "91290A115".to_string(),
+            detail_description: "Socket Head Screw".to_string(),
+            family_description: "Socket Head Screws".to_string(),
+            product_category: "Screws".to_string(),
+            product_status: "Active".to_string(),
+            specifications: vec![
+                Specification { attribute: "Thread Size".to_string(), values: vec!["M3".to_string()] },
+                Specification { attribute: "Material".to_string(), values: vec!["Alloy Steel".to_string()] },
+            ],
+            item_number: None,
+        };
+
+        assert!(PartFilter::default().is_empty());
+        assert!(PartFilter::default().matches(&detail));
+        let filter = PartFilter {
+            category: Some("screws".to_string()),
+            specs: vec![PartFilter::parse_spec("thread size=m3").unwrap(), ("Material".to_string(), "alloy steel".to_string())],
+            status: Some("active".to_string()),
+        };
+        assert!(filter.matches(&detail));
+        assert!(!PartFilter { status: Some("discontinued".to_string()), ..filter.clone() }.matches(&detail));
+        assert!(!PartFilter { specs: vec![("Thread Size".to_string(), "M4".to_string())], ..filter.clone() }.matches(&detail));
+        assert!(!PartFilter { category: Some("nuts".to_string()), ..filter }.matches(&detail));
+        assert!(PartFilter::parse_spec("Thread Size").is_err());
+    }
+}
@@ -5,9 +5,15 @@
 
 pub mod api;
 pub mod auth;
+pub mod bom;
+pub mod change;
+pub mod filter;
 pub mod product;
+pub mod warning;
 
 pub use api::{ProductResponse, LinkItem, CadFile, CadFormat, ProductLinks};
 pub use auth::{Credentials, LoginRequest, LoginResponse, ErrorResponse};
+pub use change::ChangeRecord;
 pub use product::{ProductDetail, Specification, PriceInfo};
+pub use warning::{Warning, WarningKind};
 pub use api::ProductInfo;
\ No newline at end of file
@@ -1,6 +1,8 @@
 //! Product data models
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::models::warning::{Warning, WarningKind};
 
 /// Product pricing information
 #[derive(Debug, Deserialize, Serialize)]
@@ -11,6 +13,19 @@ pub struct PriceInfo {
     pub minimum_quantity: f64,
     #[serde(rename = "UnitOfMeasure")]
     pub unit_of_measure: String,
+    /// ISO 4217 code; filled in by the client when the API doesn't report one
+    #[serde(rename = "Currency", default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+}
+
+/// Currency assumed when neither the API nor config.toml gives one
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+impl PriceInfo {
+    /// ISO 4217 currency code of the amount
+    pub fn currency(&self) -> &str {
+        self.currency.as_deref().unwrap_or(DEFAULT_CURRENCY)
+    }
 }
 
 /// Complete product details including specifications
@@ -26,8 +41,217 @@ pub struct ProductDetail {
     pub product_category: String,
     #[serde(rename = "ProductStatus")]
     pub product_status: String,
-    #[serde(rename = "Specifications", default)]
+    #[serde(rename = "Specifications", default, deserialize_with = "null_as_default")]
     pub specifications: Vec<Specification>,
+    /// Internal ERP/PLM item number, filled in by the client from the crosswalk
+    #[serde(rename = "ItemNumber", default, skip_serializing_if = "Option::is_none")]
+    pub item_number: Option<String>,
+}
+
+impl ProductDetail {
+    /// Clean up specification data returned by the API
+    ///
+    /// Drops blank values and specs left without any values, merges duplicated
+    /// attributes into the first occurrence, and sorts specs by attribute name so
+    /// output is stable between runs. Returns a warning for each fix that was applied.
+    pub fn sanitize(&mut self) -> Vec<Warning> {
+        let mut messages = Vec::new();
+        let mut cleaned: Vec<Specification> = Vec::with_capacity(self.specifications.len());
+
+        for mut spec in std::mem::take(&mut self.specifications) {
+            spec.attribute = spec.attribute.trim().to_string();
+            if spec.attribute.is_empty() {
+                messages.push("Dropped specification with empty attribute name".to_string());
+                continue;
+            }
+
+            let before = spec.values.len();
+            spec.values = spec.values.into_iter()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect();
+            if spec.values.len() != before {
+                messages.push(format!("Dropped {} empty value(s) from '{}'", before - spec.values.len(), spec.attribute));
+            }
+
+            if let Some(existing) = cleaned.iter_mut().find(|s| s.attribute.eq_ignore_ascii_case(&spec.attribute)) {
+                messages.push(format!("Merged duplicate specification '{}'", spec.attribute));
+                for value in spec.values {
+                    if !existing.values.contains(&value) {
+                        existing.values.push(value);
+                    }
+                }
+                continue;
+            }
+
+            cleaned.push(spec);
+        }
+
+        cleaned.retain(|spec| {
+            if spec.values.is_empty() {
+                messages.push(format!("Dropped specification '{}' with no values", spec.attribute));
+                false
+            } else {
+                true
+            }
+        });
+
+        self.specifications = cleaned;
+        self.sort_specifications();
+        messages.into_iter()
+            .map(|message| Warning::new(WarningKind::MalformedSpecification, &self.part_number, message))
+            .collect()
+    }
+
+    /// Whether the product is still active in the catalog
+    pub fn is_active(&self) -> bool {
+        self.product_status.trim().eq_ignore_ascii_case("Active")
+    }
+
+    /// Replacement part number, if the specifications name one
+    pub fn replacement_part(&self) -> Option<&str> {
+        const REPLACEMENT_ATTRIBUTES: &[&str] = &["replacement", "replaced by", "superseded by", "replacement part number"];
+
+        self.specifications.iter()
+            .find(|spec| REPLACEMENT_ATTRIBUTES.iter().any(|name| spec.attribute.eq_ignore_ascii_case(name)))
+            .and_then(|spec| spec.values.first())
+            .map(String::as_str)
+    }
+
+    /// Pieces per unit of measure (e.g. screws per pack), if the specifications give it
+    pub fn pack_size(&self) -> Option<u32> {
+        const PACK_ATTRIBUTES: &[&str] = &["pack quantity", "package quantity", "quantity per pack", "packaging", "packs of"];
+
+        self.specifications.iter()
+            .filter(|spec| PACK_ATTRIBUTES.iter().any(|name| spec.attribute.eq_ignore_ascii_case(name)))
+            .flat_map(|spec| spec.values.iter())
+            .find_map(|value| first_number(value))
+    }
+
+    /// Sort specifications by attribute name (case-insensitive) for deterministic output
+    pub fn sort_specifications(&mut self) {
+        self.specifications.sort_by(|a, b| {
+            a.attribute.to_lowercase().cmp(&b.attribute.to_lowercase())
+                .then_with(|| a.attribute.cmp(&b.attribute))
+        });
+    }
+}
+
+/// Cost of ordering a quantity of pieces, from the price breaks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceQuote {
+    pub part_number: String,
+    pub quantity: f64, // Pieces needed
+    pub pack_size: Option<u32>, // Pieces per unit of measure; unknown means quantity is counted in units
+    pub units: f64, // Units of measure to order, rounded up to whole units and the minimum order
+    pub unit_of_measure: String,
+    pub break_quantity: f64, // Minimum quantity of the price break that applies
+    pub unit_price: f64,
+    pub total: f64,
+    pub currency: String, // ISO 4217 code
+}
+
+impl PriceQuote {
+    /// Pick the price break for `quantity` pieces; `None` when there are no price breaks
+    pub fn new(part_number: &str, prices: &[PriceInfo], quantity: f64, pack_size: Option<u32>) -> Option<Self> {
+        let minimum = prices.iter().map(|price| price.minimum_quantity).min_by(f64::total_cmp)?;
+        let needed = match pack_size {
+            Some(size) => quantity / f64::from(size.max(1)),
+            None => quantity,
+        };
+        let units = needed.ceil().max(minimum);
+
+        let price = prices.iter()
+            .filter(|price| price.minimum_quantity <= units)
+            .max_by(|a, b| a.minimum_quantity.total_cmp(&b.minimum_quantity))?;
+
+        Some(PriceQuote {
+            part_number: part_number.to_string(),
+            quantity,
+            pack_size,
+            units,
+            unit_of_measure: price.unit_of_measure.clone(),
+            break_quantity: price.minimum_quantity,
+            unit_price: price.amount,
+            total: units * price.amount,
+            currency: price.currency().to_string(),
+        })
+    }
+}
+
+/// Specifications of two products side by side
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProductDiff {
+    pub first: String, // Part numbers being compared
+    pub second: String,
+    pub specifications: Vec<SpecComparison>,
+}
+
+/// One specification attribute in both products; empty values mean the product doesn't list it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpecComparison {
+    pub attribute: String,
+    pub first: Vec<String>,
+    pub second: Vec<String>,
+    pub differs: bool,
+}
+
+impl ProductDiff {
+    /// Pair up the specifications of two products by attribute name (case-insensitive)
+    ///
+    /// Attributes keep the order of the first product, followed by those only the second lists.
+    /// Values are compared without regard to order.
+    pub fn new(first: &ProductDetail, second: &ProductDetail) -> Self {
+        let find = |product: &ProductDetail, attribute: &str| {
+            product.specifications.iter()
+                .find(|spec| spec.attribute.eq_ignore_ascii_case(attribute))
+                .map(|spec| spec.values.clone())
+                .unwrap_or_default()
+        };
+
+        let attributes = first.specifications.iter()
+            .chain(second.specifications.iter().filter(|spec| {
+                !first.specifications.iter().any(|other| other.attribute.eq_ignore_ascii_case(&spec.attribute))
+            }))
+            .map(|spec| spec.attribute.clone());
+
+        let specifications = attributes
+            .map(|attribute| {
+                let (first, second) = (find(first, &attribute), find(second, &attribute));
+                let differs = !same_values(&first, &second);
+                SpecComparison { attribute, first, second, differs }
+            })
+            .collect();
+
+        ProductDiff {
+            first: first.part_number.clone(),
+            second: second.part_number.clone(),
+            specifications,
+        }
+    }
+
+    /// Specifications whose values differ between the two products
+    pub fn differences(&self) -> impl Iterator<Item = &SpecComparison> {
+        self.specifications.iter().filter(|spec| spec.differs)
+    }
+}
+
+fn same_values(a: &[String], b: &[String]) -> bool {
+    let normalize = |values: &[String]| {
+        let mut values: Vec<String> = values.iter().map(|value| value.to_lowercase()).collect();
+        values.sort();
+        values
+    };
+    normalize(a) == normalize(b)
+}
+
+/// First whole number in text like "Packs of 100"
+fn first_number(text: &str) -> Option<u32> {
+    text.split(|c: char| !c.is_ascii_digit() && c != ',')
+        .map(|digits| digits.replace(',', ""))
+        .find(|digits| !digits.is_empty())
+        .and_then(|digits| digits.parse().ok())
+        .filter(|&count| count > 0)
 }
 
 /// Product specification attribute and values
@@ -35,6 +259,137 @@ pub struct ProductDetail {
 pub struct Specification {
     #[serde(rename = "Attribute")]
     pub attribute: String,
-    #[serde(rename = "Values")]
+    #[serde(rename = "Values", default, deserialize_with = "null_as_default")]
     pub values: Vec<String>,
+}
+
+/// Deserialize a JSON `null` as the type's default value
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_quote() {
+        let prices: Vec<PriceInfo> = serde_json::from_str(
+            r#"[{"Amount": 9.5, "MinimumQuantity": 1, "UnitOfMeasure": "Pack"}, {"Amount": 8.0, "MinimumQuantity": 5, "UnitOfMeasure": "Pack"}]"#,
+        ).unwrap();
+
+        // 250 screws in packs of 100 is 3 packs at the 1+ price
+        let quote = PriceQuote::new("91290A115", &prices, 250.0, Some(100)).unwrap();
+        assert_eq!((quote.units, quote.break_quantity, quote.unit_price, quote.total), (3.0, 1.0, 9.5, 28.5));
+        assert_eq!(quote.currency, "USD");
+
+        // Enough packs reach the next break
+        let quote = PriceQuote::new("91290A115", &prices, 450.0, Some(100)).unwrap();
+        assert_eq!((quote.units, quote.unit_price, quote.total), (5.0, 8.0, 40.0));
+
+        // Without a pack size the quantity is in units of measure, rounded up to the minimum
+        let prices: Vec<PriceInfo> = serde_json::from_str(r#"[{"Amount": 2.0, "MinimumQuantity": 10, "UnitOfMeasure": "Each"}]"#).unwrap();
+        let quote = PriceQuote::new("1234K5", &prices, 3.0, None).unwrap();
+        assert_eq!((quote.units, quote.total), (10.0, 20.0));
+        assert!(PriceQuote::new("1234K5", &[], 3.0, None).is_none());
+
+        assert_eq!(first_number("Packs of 1,000"), Some(1000));
+        assert_eq!(first_number("Bag"), None);
+    }
+
+    #[test]
+    fn test_product_diff() {
+        let first: ProductDetail = serde_json::from_str(r#"{
+            "PartNumber": "91290A115", "DetailDescription": "M3 x 10 mm", "FamilyDescription": "Socket Head Screws",
+            "ProductCategory": "Screws", "ProductStatus": "Active",
+            "Specifications": [
+                {"Attribute": "Length", "Values": ["10 mm"]},
+                {"Attribute": "Material", "Values": ["Alloy Steel", "Black-Oxide"]},
+                {"Attribute": "Thread Size", "Values": ["M3"]}
+            ]
+        }"#).unwrap();
+        let second: ProductDetail = serde_json::from_str(r#"{
+            "PartNumber": "91290A116", "DetailDescription": "M3 x 12 mm", "FamilyDescription": "Socket Head Screws",
+            "ProductCategory": "Screws", "ProductStatus": "Active",
+            "Specifications": [
+                {"Attribute": "Drive Size", "Values": ["2.5 mm"]},
+                {"Attribute": "length", "Values": ["12 mm"]},
+                {"Attribute": "Material", "Values": ["black-oxide", "alloy steel"]},
+                {"Attribute": "Thread Size", "Values": ["M3"]}
+            ]
+        }"#).unwrap();
+
+        let diff = ProductDiff::new(&first, &second);
+        let attributes: Vec<&str> = diff.specifications.iter().map(|spec| spec.attribute.as_str()).collect();
+        assert_eq!(attributes, vec!["Length", "Material", "Thread Size", "Drive Size"]);
+
+        let differences: Vec<&SpecComparison> = diff.differences().collect();
+        assert_eq!(differences.len(), 2);
+        assert_eq!((differences[0].first.as_slice(), differences[0].second.as_slice()), (&["10 mm".to_string()][..], &["12 mm".to_string()][..]));
+        assert!(differences[1].first.is_empty());
+        assert_eq!(differences[1].second, vec!["2.5 mm"]);
+    }
+
+    #[test]
+    fn test_sanitize_specifications() {
+        let json = r#"{
+            "PartNumber": "91290A115",
+            "DetailDescription": "Socket Head Screw",
+            "FamilyDescription": "Socket Head Screws",
+            "ProductCategory": "Screws",
+            "ProductStatus": "Active",
+            "Specifications": [
+                {"Attribute": "Material", "Values": ["Alloy Steel"]},
+                {"Attribute": "Finish", "Values": ["", "  "]},
+                {"Attribute": "Thread Size", "Values": null},
+                {"Attribute": "material", "Values": ["Alloy Steel", "Black-Oxide"]},
+                {"Attribute": "Length", "Values": [" 10 mm "]}
+            ]
+        }"#;
+
+        let mut product: ProductDetail = serde_json::from_str(json).unwrap();
+        let warnings = product.sanitize();
+
+        let attributes: Vec<&str> = product.specifications.iter().map(|s| s.attribute.as_str()).collect();
+        assert_eq!(attributes, vec!["Length", "Material"]);
+        assert_eq!(product.specifications[0].values, vec!["10 mm"]);
+        assert_eq!(product.specifications[1].values, vec!["Alloy Steel", "Black-Oxide"]);
+        assert!(warnings.iter().any(|w| w.message.contains("Merged duplicate specification 'material'")));
+        assert!(warnings.iter().any(|w| w.message.contains("'Thread Size' with no values")));
+    }
+
+    #[test]
+    fn test_discontinued_replacement() {
+        let json = r#"{
+            "PartNumber": "91290A115",
+            "DetailDescription": "Socket Head Screw",
+            "FamilyDescription": "Socket Head Screws",
+            "ProductCategory": "Screws",
+            "ProductStatus": "Discontinued",
+            "Specifications": [{"Attribute": "Replaced By", "Values": ["91290A116"]}]
+        }"#;
+
+        let product: ProductDetail = serde_json::from_str(json).unwrap();
+        assert!(!product.is_active());
+        assert_eq!(product.replacement_part(), Some("91290A116"));
+    }
+
+    #[test]
+    fn test_null_specifications() {
+        let json = r#"{
+            "PartNumber": "91290A115",
+            "DetailDescription": "Socket Head Screw",
+            "FamilyDescription": "Socket Head Screws",
+            "ProductCategory": "Screws",
+            "ProductStatus": "Active",
+            "Specifications": null
+        }"#;
+
+        let product: ProductDetail = serde_json::from_str(json).unwrap();
+        assert!(product.specifications.is_empty());
+    }
 }
\ No newline at end of file
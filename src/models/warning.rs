@@ -0,0 +1,88 @@
+//! Non-fatal warnings collected during client operations
+
+use serde::Serialize;
+use std::fmt;
+
+/// What kind of problem a warning describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// Malformed specification data was cleaned up (blank values, duplicates, missing values)
+    MalformedSpecification,
+    /// The API returned a link key that isn't recognized as an image, CAD file or datasheet
+    UnknownLinkKey,
+    /// The authentication token couldn't be saved for later sessions
+    TokenNotSaved,
+    /// A new token was obtained by logging in with the stored credentials (only with --verbose)
+    TokenRenewed,
+    /// A download manifest couldn't be updated
+    ManifestNotSaved,
+    /// The local subscription list couldn't be updated to match the API
+    TrackingNotSaved,
+    /// Local subscription tracking was recovered after a panic while it was in use
+    TrackingRecovered,
+    /// Product data couldn't be saved to the local cache
+    CacheNotSaved,
+    /// The client certificate has expired or expires soon
+    CertificateExpiring,
+    /// Server certificates aren't being validated (`--insecure`)
+    CertificateValidationDisabled,
+    /// A downloaded datasheet's text couldn't be added to the search index
+    DatasheetNotIndexed,
+    /// Parts were left out of a filtered list because they have no cached details
+    NotCached,
+    /// A download turned out to be a ZIP archive and was saved without unpacking
+    ArchiveNotExtracted,
+    /// Part metadata couldn't be written into a downloaded STEP file's header
+    MetadataNotEmbedded,
+    /// A downloaded drawing couldn't be post-processed per `[postprocess]` and was kept as downloaded
+    NotPostprocessed,
+    /// A part's internal item number couldn't be read from the crosswalk
+    CrosswalkUnavailable,
+    /// The `alert_command` hook failed for a price alert
+    AlertHookFailed,
+}
+
+impl WarningKind {
+    /// Whether the warning is printed even without --verbose
+    pub fn always_shown(self) -> bool {
+        matches!(self, WarningKind::CertificateExpiring | WarningKind::CertificateValidationDisabled | WarningKind::NotCached | WarningKind::ArchiveNotExtracted)
+    }
+}
+
+/// A problem that didn't stop the operation but may affect its results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub subject: Option<String>, // Part number or file the warning is about
+    pub message: String,
+}
+
+impl Warning {
+    /// Create a warning about a specific part or file
+    pub fn new(kind: WarningKind, subject: impl Into<String>, message: impl Into<String>) -> Self {
+        Warning {
+            kind,
+            subject: Some(subject.into()),
+            message: message.into(),
+        }
+    }
+
+    /// Create a warning that isn't tied to a part or file
+    pub fn general(kind: WarningKind, message: impl Into<String>) -> Self {
+        Warning {
+            kind,
+            subject: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.subject {
+            Some(subject) => write!(f, "{}: {}", subject, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
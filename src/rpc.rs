@@ -0,0 +1,354 @@
+//! JSON-RPC 2.0 interface for driving mmc from another program
+//!
+//! `mmc rpc` reads one request (or batch) per line on stdin and writes one response per
+//! line on stdout, so editors and CAD add-ons can keep a single client running as a
+//! subprocess. Parameters are passed by name; results are the same JSON the CLI prints
+//! with `--output json`. Client warnings are sent as `warning` notifications ahead of
+//! the response they belong to.
+
+use crate::client::McmasterClient;
+use crate::config::Config;
+use crate::utils::error::ClientError;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Version of the method set below; bumped when a method or its parameters change incompatibly
+pub const RPC_VERSION: u32 = 1;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const CLIENT_ERROR: i64 = -32000; // The call failed; `data.kind` is ClientError::kind()
+
+/// Methods `mmc rpc` answers, as listed by the `methods` method
+pub const METHODS: &[&str] = &[
+    "version", "methods", "login", "info", "price", "quote", "diff", "alternates",
+    "search", "list", "add", "remove", "image", "cad", "datasheet",
+];
+
+/// A JSON-RPC error object
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError { code, message: message.into(), data: None }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut error = json!({"code": self.code, "message": self.message});
+        if let Some(data) = &self.data {
+            error["data"] = data.clone();
+        }
+        error
+    }
+}
+
+impl From<ClientError> for RpcError {
+    fn from(error: ClientError) -> Self {
+        RpcError {
+            code: CLIENT_ERROR,
+            message: error.to_string(),
+            data: Some(json!({"kind": error.kind()})),
+        }
+    }
+}
+
+impl From<serde_json::Error> for RpcError {
+    fn from(error: serde_json::Error) -> Self {
+        RpcError::new(INVALID_PARAMS, format!("Invalid params: {}", error))
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+    id: Option<Value>, // Absent for notifications, which get no response
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PartParams {
+    part: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LoginParams {
+    username: Option<String>,
+    password: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QuoteParams {
+    part: String,
+    quantity: f64,
+    pack_size: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DiffParams {
+    first: String,
+    second: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AlternatesParams {
+    part: String,
+    #[serde(default = "default_alternates_limit")]
+    limit: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SearchParams {
+    query: String,
+    #[serde(default = "default_search_limit")]
+    limit: usize,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ListParams {
+    tag: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DownloadParams {
+    part: String,
+    output: Option<String>,
+    #[serde(default)]
+    force: bool,
+    #[serde(default)]
+    formats: Vec<String>, // cad only; default: `cad_formats` in config.toml, or everything
+    #[serde(default)]
+    all: bool, // cad only
+    #[serde(default)]
+    extract: bool, // cad only
+    #[serde(default)]
+    embed_metadata: bool, // cad only
+    #[serde(default)]
+    index: bool, // datasheet only
+}
+
+fn default_alternates_limit() -> usize {
+    10
+}
+
+fn default_search_limit() -> usize {
+    20
+}
+
+/// Answer requests from `input` until it ends, writing responses to `output`
+pub async fn serve<R, W>(client: &mut McmasterClient, config: &Config, input: R, mut output: W) -> io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = input.lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = handle_line(client, config, &line).await;
+        for warning in client.take_warnings() {
+            let notification = json!({"jsonrpc": "2.0", "method": "warning", "params": warning});
+            write_line(&mut output, &notification).await?;
+        }
+        if let Some(response) = response {
+            write_line(&mut output, &response).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(output: &mut W, value: &Value) -> io::Result<()> {
+    let mut line = value.to_string();
+    line.push('\n');
+    output.write_all(line.as_bytes()).await?;
+    output.flush().await
+}
+
+/// Handle one line of input: a request, a notification, or a batch of them
+async fn handle_line(client: &mut McmasterClient, config: &Config, line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(error_response(Value::Null, RpcError::new(PARSE_ERROR, format!("Parse error: {}", e)))),
+    };
+
+    match message {
+        Value::Array(batch) if batch.is_empty() => {
+            Some(error_response(Value::Null, RpcError::new(INVALID_REQUEST, "Invalid request: empty batch")))
+        }
+        Value::Array(batch) => {
+            let mut responses = Vec::new();
+            for message in batch {
+                responses.extend(handle_message(client, config, message).await);
+            }
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        message => handle_message(client, config, message).await,
+    }
+}
+
+async fn handle_message(client: &mut McmasterClient, config: &Config, message: Value) -> Option<Value> {
+    let request = match serde_json::from_value::<Request>(message) {
+        Ok(request) if request.jsonrpc == "2.0" => request,
+        _ => return Some(error_response(Value::Null, RpcError::new(INVALID_REQUEST, "Invalid request"))),
+    };
+
+    let result = call(client, config, &request.method, request.params.unwrap_or(Value::Null)).await;
+    let id = request.id?;
+    Some(match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "result": result, "id": id}),
+        Err(error) => error_response(id, error),
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({"jsonrpc": "2.0", "error": error.to_json(), "id": id})
+}
+
+/// Named parameters, treating missing params as an empty object
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    if !params.is_object() {
+        return Err(RpcError::new(INVALID_PARAMS, "Invalid params: pass parameters by name"));
+    }
+    Ok(serde_json::from_value(params)?)
+}
+
+async fn call(client: &mut McmasterClient, config: &Config, method: &str, raw: Value) -> Result<Value, RpcError> {
+    let result = match method {
+        "version" => json!({"mmc": env!("CARGO_PKG_VERSION"), "rpc": RPC_VERSION}),
+        "methods" => json!(METHODS),
+        "login" => {
+            let LoginParams { username, password } = params(raw)?;
+            match (username, password) {
+                (Some(username), Some(password)) => client.login(username, password).await?,
+                (None, None) => client.login_with_stored_credentials().await?,
+                _ => return Err(RpcError::new(INVALID_PARAMS, "Invalid params: give both username and password, or neither")),
+            }
+            Value::Null
+        }
+        "info" => {
+            let PartParams { part } = params(raw)?;
+            serde_json::to_value(client.get_product(&part).await?)?
+        }
+        "price" => {
+            let PartParams { part } = params(raw)?;
+            serde_json::to_value(client.get_price(&part).await?)?
+        }
+        "quote" => {
+            let QuoteParams { part, quantity, pack_size } = params(raw)?;
+            serde_json::to_value(client.quote_price(&part, quantity, pack_size).await?)?
+        }
+        "diff" => {
+            let DiffParams { first, second } = params(raw)?;
+            serde_json::to_value(client.compare_products(&first, &second).await?)?
+        }
+        "alternates" => {
+            let AlternatesParams { part, limit } = params(raw)?;
+            serde_json::to_value(client.find_alternates(&part, limit).await?.1)?
+        }
+        "search" => {
+            let SearchParams { query, limit } = params(raw)?;
+            serde_json::to_value(client.search_datasheets(&query, limit)?)?
+        }
+        "list" => {
+            let ListParams { tag } = params(raw)?;
+            serde_json::to_value(client.list_tracked_parts(tag.as_deref())?)?
+        }
+        "add" => {
+            let PartParams { part } = params(raw)?;
+            serde_json::to_value(client.add_product(&part).await?)?
+        }
+        "remove" => {
+            let PartParams { part } = params(raw)?;
+            client.remove_product(&part).await?;
+            Value::Null
+        }
+        "image" | "cad" | "datasheet" => {
+            let download: DownloadParams = params(raw)?;
+            client.set_force_download(download.force);
+            client.set_extract_archives(download.extract);
+            client.set_embed_metadata(download.embed_metadata);
+            let output = download.output.as_deref();
+            let results = match method {
+                "image" => client.download_images(&download.part, output).await?,
+                "cad" => {
+                    let formats = if download.formats.is_empty() && !download.all { &config.cad_formats } else { &download.formats };
+                    let formats: Vec<&str> = formats.iter().map(String::as_str).collect();
+                    client.download_cad(&download.part, output, &formats, download.all || formats.is_empty()).await?
+                }
+                _ => {
+                    let results = client.download_datasheets(&download.part, output).await?;
+                    if download.index || config.index_datasheets {
+                        client.index_datasheets(&download.part, &results).await;
+                    }
+                    results
+                }
+            };
+            serde_json::to_value(results)?
+        }
+        _ => return Err(RpcError::new(METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    };
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockApi, PART_NUMBER};
+
+    #[tokio::test]
+    async fn test_serve_requests() {
+        let api = MockApi::start().await;
+        let mut client = api.logged_in_client().unwrap();
+
+        let input = [
+            format!(r#"{{"jsonrpc": "2.0", "method": "info", "params": {{"part": "{}"}}, "id": 1}}"#, PART_NUMBER),
+            r#"{"jsonrpc": "2.0", "method": "price", "params": {"part": "92141A008"}, "id": "a"}"#.to_string(),
+            r#"[{"jsonrpc": "2.0", "method": "version", "id": 2}, {"jsonrpc": "2.0", "method": "methods"}]"#.to_string(),
+            r#"{"jsonrpc": "2.0", "method": "info", "params": ["91290A115"], "id": 3}"#.to_string(),
+            r#"{"jsonrpc": "2.0", "method": "bogus", "id": 4}"#.to_string(),
+            "not json".to_string(),
+        ].join("\n");
+        let mut output = Vec::new();
+        serve(&mut client, &Config::default(), input.as_bytes(), &mut output).await.unwrap();
+
+        let responses: Vec<Value> = String::from_utf8(output).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 6);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["PartNumber"], PART_NUMBER);
+        assert_eq!(responses[1]["id"], "a");
+        assert_eq!(responses[1]["error"]["code"], CLIENT_ERROR);
+        assert!(responses[1]["error"]["data"]["kind"].is_string());
+        // The notification in the batch gets no response
+        assert_eq!(responses[2].as_array().unwrap().len(), 1);
+        assert_eq!(responses[2][0]["result"]["rpc"], RPC_VERSION);
+        assert_eq!(responses[3]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(responses[4]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[5]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[5]["id"], Value::Null);
+    }
+}
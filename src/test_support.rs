@@ -0,0 +1,198 @@
+//! Fake McMaster-Carr API for integration tests
+//!
+//! Enabled with the `test-util` feature. [`MockApi`] starts a local server with canned
+//! login, product, price and CAD download responses for [`PART_NUMBER`], and hands out
+//! clients pointed at it whose token, subscriptions, cache and downloads live in a
+//! temporary directory, so tests need no credentials and never touch the real config.
+
+use crate::client::auth::Session;
+use crate::client::cache::ProductCache;
+use crate::client::subscriptions::SubscriptionManager;
+use crate::client::McmasterClient;
+use crate::utils::error::Result;
+use serde_json::{json, Value};
+use std::path::Path;
+use tempfile::TempDir;
+use wiremock::matchers::{body_partial_json, header, method, path, HeaderExactMatcher};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Part number served by the fixtures
+pub const PART_NUMBER: &str = "91290A115";
+
+/// Token returned by the login fixture and required by every other endpoint
+pub const TOKEN: &str = "test-token";
+
+/// Username and password accepted by the login fixture
+pub const USERNAME: &str = "test@example.com";
+pub const PASSWORD: &str = "test-password";
+
+/// Contents of the STEP file served for [`PART_NUMBER`]
+pub const STEP_FILE: &[u8] = b"ISO-10303-21;\nEND-ISO-10303-21;\n";
+
+/// Product details, including the download links, for [`PART_NUMBER`]
+pub fn product_fixture() -> Value {
+    json!({
+        "PartNumber": PART_NUMBER,
+        "DetailDescription": "M3 x 0.5 mm Thread, 10 mm Long",
+        "FamilyDescription": "Black-Oxide Alloy Steel Socket Head Screws",
+        "ProductCategory": "Screws",
+        "ProductStatus": "Active",
+        "Specifications": [
+            {"Attribute": "Thread Size", "Values": ["M3"]},
+            {"Attribute": "Material", "Values": ["Alloy Steel"]}
+        ],
+        "Links": [
+            {"Key": "3-D STEP", "Value": format!("/v1/cad/{}.step", PART_NUMBER)}
+        ]
+    })
+}
+
+/// Price breaks for [`PART_NUMBER`]
+pub fn price_fixture() -> Value {
+    json!([
+        {"Amount": 9.5, "MinimumQuantity": 1, "UnitOfMeasure": "Pack"},
+        {"Amount": 8.75, "MinimumQuantity": 10, "UnitOfMeasure": "Pack"}
+    ])
+}
+
+fn authorized() -> HeaderExactMatcher {
+    header("Authorization", format!("Bearer {}", TOKEN).as_str())
+}
+
+/// A running fake API with its own scratch directory
+pub struct MockApi {
+    server: MockServer,
+    data_dir: TempDir,
+}
+
+impl MockApi {
+    /// Start a server with the standard fixtures mounted
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/login"))
+            .and(body_partial_json(json!({"UserName": USERNAME, "Password": PASSWORD})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "AuthToken": TOKEN,
+                "ExpirationTS": "2099-01-01T00:00:00Z"
+            })))
+            .mount(&server)
+            .await;
+        // Anything that didn't match above is a wrong username or password
+        Mock::given(method("POST"))
+            .and(path("/v1/login"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(json!({"ErrorMessage": "Invalid credentials"})))
+            .with_priority(10)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/v1/logout"))
+            .and(authorized())
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/v1/products"))
+            .and(authorized())
+            .respond_with(ResponseTemplate::new(200).set_body_json(product_fixture()))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/v1/products"))
+            .and(authorized())
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/products/{}", PART_NUMBER)))
+            .and(authorized())
+            .respond_with(ResponseTemplate::new(200).set_body_json(product_fixture()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/products/{}/price", PART_NUMBER)))
+            .and(authorized())
+            .respond_with(ResponseTemplate::new(200).set_body_json(price_fixture()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/cad/{}.step", PART_NUMBER)))
+            .and(authorized())
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(STEP_FILE))
+            .mount(&server)
+            .await;
+
+        MockApi {
+            server,
+            data_dir: TempDir::new().expect("Failed to create mock API data directory"),
+        }
+    }
+
+    /// The underlying server, for mounting extra responses
+    pub fn server(&self) -> &MockServer {
+        &self.server
+    }
+
+    /// Base URL of the fake API
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Scratch directory holding the clients' token, databases and downloads
+    pub fn data_dir(&self) -> &Path {
+        self.data_dir.path()
+    }
+
+    /// A client pointed at the fake API, not yet logged in
+    pub fn client(&self) -> Result<McmasterClient> {
+        let dir = self.data_dir.path();
+        let subscriptions = SubscriptionManager::open(dir.join("subscriptions.db"), None)?;
+        let cache = ProductCache::open(dir.join("products.db"))?;
+
+        let mut client = McmasterClient::from_parts(reqwest::Client::new(), None, true, subscriptions, cache);
+        client.set_base_url(&self.uri());
+        client.token_path = dir.join("token");
+        client.set_download_dir(Some(dir.join("downloads")));
+        client.set_show_progress(false);
+        Ok(client)
+    }
+
+    /// A client pointed at the fake API that already holds a valid token
+    pub fn logged_in_client(&self) -> Result<McmasterClient> {
+        let mut client = self.client()?;
+        client.session = std::sync::Mutex::new(Some(Session::new(TOKEN.to_string(), None)));
+        Ok(client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::error::ClientError;
+
+    #[tokio::test]
+    async fn test_client_against_mock_api() {
+        let api = MockApi::start().await;
+
+        let mut client = api.client().unwrap();
+        assert!(matches!(client.get_product(PART_NUMBER).await, Err(ClientError::NotAuthenticated)));
+        assert!(matches!(client.login(USERNAME.to_string(), "wrong".to_string()).await, Err(ClientError::Auth(_))));
+        client.login(USERNAME.to_string(), PASSWORD.to_string()).await.unwrap();
+        assert_eq!(std::fs::read_to_string(api.data_dir().join("token")).unwrap(), TOKEN);
+
+        let product = client.get_product(PART_NUMBER).await.unwrap();
+        assert_eq!(product.detail_description, "M3 x 0.5 mm Thread, 10 mm Long");
+        let prices = client.get_price(PART_NUMBER).await.unwrap();
+        assert_eq!(prices.len(), 2);
+
+        let downloads = client.download_cad(PART_NUMBER, None, &["step"], false).await.unwrap();
+        assert_eq!(downloads.len(), 1);
+        assert!(downloads[0].is_success());
+        assert_eq!(std::fs::read(&downloads[0].file_path).unwrap(), STEP_FILE);
+
+        let other = api.logged_in_client().unwrap();
+        assert!(other.get_product("92141A008").await.is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//! Detecting and unpacking ZIP archives that some CAD links return instead of a bare file
+
+use crate::utils::error::{ClientError, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Signature at the start of a ZIP file's first local header
+const ZIP_MAGIC: &[u8; 4] = b"PK\x03\x04";
+
+/// Whether a file's contents are a ZIP archive, whatever its extension says
+pub fn is_zip(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    File::open(path).and_then(|mut file| file.read_exact(&mut magic)).is_ok() && &magic == ZIP_MAGIC
+}
+
+/// Unpack an archive's files into `dir`, flattening folders and naming them after `stem`
+///
+/// Names follow the download naming scheme: `{stem}.{ext}`, or `{stem}_1.{ext}`,
+/// `{stem}_2.{ext}`, ... when several files share an extension. Folder entries, dotfiles
+/// and `__MACOSX` metadata are skipped. Returns the written paths in archive order.
+pub fn extract_flat(archive: &Path, dir: &Path, stem: &str) -> Result<Vec<PathBuf>> {
+    let zip_error = |e: zip::result::ZipError| ClientError::Parse(format!("Could not read ZIP archive '{}': {}", archive.display(), e));
+    let mut zip = zip::ZipArchive::new(File::open(archive)?).map_err(zip_error)?;
+
+    // Pick the archive's files and their extensions before naming anything
+    let mut files = Vec::new();
+    for index in 0..zip.len() {
+        let entry = zip.by_index(index).map_err(zip_error)?;
+        let Some(name) = entry.enclosed_name().and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned())) else {
+            continue;
+        };
+        if entry.is_dir() || name.starts_with('.') || entry.name().starts_with("__MACOSX/") {
+            continue;
+        }
+        let extension = Path::new(&name).extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+        files.push((index, extension));
+    }
+
+    let mut totals: HashMap<&str, usize> = HashMap::new();
+    for (_, extension) in &files {
+        *totals.entry(extension.as_str()).or_default() += 1;
+    }
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut written = Vec::new();
+    for (index, extension) in &files {
+        let count = seen.entry(extension.as_str()).or_default();
+        *count += 1;
+        let base = if totals[extension.as_str()] == 1 { stem.to_string() } else { format!("{}_{}", stem, count) };
+        let path = if extension.is_empty() { dir.join(base) } else { dir.join(format!("{}.{}", base, extension)) };
+
+        // Write next to the target and rename, since the archive itself may be replaced
+        let temp = dir.join(format!(".{}.unzip", path.file_name().unwrap_or_default().to_string_lossy()));
+        let mut entry = zip.by_index(*index).map_err(zip_error)?;
+        std::io::copy(&mut entry, &mut File::create(&temp)?)?;
+        written.push((temp, path));
+    }
+
+    let mut paths = Vec::new();
+    for (temp, path) in written {
+        std::fs::rename(&temp, &path)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_extract_flat() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("91290A115.step");
+        let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+        for (name, content) in [
+            ("model/Socket Screw.STEP", "step"),
+            ("__MACOSX/model/._Socket Screw.STEP", "junk"),
+            ("drawings/top.dxf", "top"),
+            ("drawings/side.dxf", "side"),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        assert!(is_zip(&archive));
+        let paths = extract_flat(&archive, dir.path(), "91290A115").unwrap();
+        let names: Vec<_> = paths.iter().map(|path| path.file_name().unwrap().to_str().unwrap()).collect();
+        assert_eq!(names, vec!["91290A115.step", "91290A115_1.dxf", "91290A115_2.dxf"]);
+        assert_eq!(std::fs::read_to_string(&archive).unwrap(), "step");
+        assert!(!is_zip(&archive));
+        assert_eq!(std::fs::read_to_string(dir.path().join("91290A115_2.dxf")).unwrap(), "side");
+    }
+}
@@ -0,0 +1,931 @@
+//! Human-readable presentation of client results
+//!
+//! Client methods return typed data; these helpers render it for the terminal.
+
+use crate::client::alerts::{AlertDirection, AlertRule, AlertTrigger};
+use crate::client::alternates::Alternate;
+use crate::client::api::{PartPrices, RefreshReport, SyncReport};
+use crate::client::auth::AuthStatus;
+use crate::client::bom::{BomCheckReport, BomEnrichment, PurchaseOrder};
+use crate::client::cache::DatasheetMatch;
+use crate::client::certificate::CertificateInfo;
+use crate::client::crosswalk::CrosswalkEntry;
+use crate::client::datasheet_merge::MergeReport;
+use crate::client::downloads::{BulkDownloadReport, DownloadResult};
+use crate::client::journal::{JournalEntry, UndoReport};
+use crate::client::manifest::{FileCheck, FileStatus};
+use crate::client::selftest::{SelfTestReport, StepStatus};
+use crate::client::snapshot::SnapshotDiff;
+use crate::client::subscriptions::TrackedPart;
+use crate::client::team::TeamSyncReport;
+use crate::integrations::package::PackageReport;
+use crate::integrations::solidworks::SolidworksLibraryReport;
+use crate::integrations::vendors::{VendorComparison, VendorQuote};
+use crate::models::change::ChangeRecord;
+use crate::models::product::{PriceInfo, PriceQuote, ProductDetail, ProductDiff};
+use crate::models::warning::Warning;
+use crate::t;
+use crate::utils::i18n::catalog;
+use crate::utils::output::ProductField;
+use chrono::Utc;
+use std::fmt;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static PLAIN_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Switch plain output on or off (no emoji, no progress animations)
+pub fn set_plain_output(plain: bool) {
+    PLAIN_OUTPUT.store(plain, Ordering::Relaxed);
+}
+
+/// Whether plain output is enabled
+pub fn is_plain_output() -> bool {
+    PLAIN_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Whether the environment asks for plain output: a non-empty `NO_COLOR` (https://no-color.org)
+pub fn plain_output_from_env() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+/// Line prefixes used in human-readable output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Icon {
+    Success,
+    Error,
+    Warning,
+    Info,
+    Hint,
+    Part,
+    Description,
+    Family,
+    Category,
+    Status,
+    Specs,
+    Price,
+    File,
+    Empty,
+    Download,
+    Skipped,
+    Summary,
+    Key,
+}
+
+impl Icon {
+    /// Emoji prefix, including trailing spacing
+    fn emoji(self) -> &'static str {
+        match self {
+            Icon::Success => "✅ ",
+            Icon::Error => "❌ ",
+            Icon::Warning => "⚠️  ",
+            Icon::Info => "ℹ️  ",
+            Icon::Hint => "💡 ",
+            Icon::Part => "📦 ",
+            Icon::Description => "📝 ",
+            Icon::Family => "🏷️ ",
+            Icon::Category => "📂 ",
+            Icon::Status => "🔄 ",
+            Icon::Specs => "🔧 ",
+            Icon::Price => "💰 ",
+            Icon::File => "📁 ",
+            Icon::Empty => "📭 ",
+            Icon::Download => "📥 ",
+            Icon::Skipped => "⏭️  ",
+            Icon::Summary => "📊 ",
+            Icon::Key => "🔑 ",
+        }
+    }
+
+    /// Text label used in plain mode; purely decorative icons have none
+    fn plain_label(self) -> Option<String> {
+        match self {
+            Icon::Success => Some(t!("label.success")),
+            Icon::Error => Some(t!("label.error")),
+            Icon::Warning => Some(t!("label.warning")),
+            Icon::Info => Some(t!("label.info")),
+            Icon::Hint => Some(t!("label.hint")),
+            Icon::Skipped => Some(t!("label.skipped")),
+            _ => None,
+        }
+    }
+}
+
+/// Prefix a line of text with an icon (or its plain-text label)
+pub fn decorate(icon: Icon, text: impl fmt::Display) -> String {
+    if is_plain_output() {
+        match icon.plain_label() {
+            Some(label) => format!("{} {}", label, text),
+            None => text.to_string(),
+        }
+    } else {
+        format!("{}{}", icon.emoji(), text)
+    }
+}
+
+/// Bullet used for list items
+pub fn bullet() -> &'static str {
+    if is_plain_output() { "-" } else { "•" }
+}
+
+/// Print product information, limited to the requested fields
+pub fn print_product(product: &ProductDetail, fields_str: &str) {
+    let fields = ProductField::parse_fields(fields_str);
+    
+    for field in fields {
+        match field {
+            ProductField::PartNumber => {
+                println!("{}", decorate(Icon::Part, format!("{}: {}", t!("field.part_number"), product.part_number)));
+            }
+            ProductField::DetailDescription => {
+                println!("{}", decorate(Icon::Description, format!("{}: {}", t!("field.description"), product.detail_description)));
+            }
+            ProductField::FamilyDescription => {
+                println!("{}", decorate(Icon::Family, format!("{}: {}", t!("field.family"), product.family_description)));
+            }
+            ProductField::Category => {
+                println!("{}", decorate(Icon::Category, format!("{}: {}", t!("field.category"), product.product_category)));
+            }
+            ProductField::Status => {
+                println!("{}", decorate(Icon::Status, format!("{}: {}", t!("field.status"), product.product_status)));
+            }
+            ProductField::ItemNumber => print_item_number(product),
+            ProductField::AllSpecs => {
+                println!("{}", decorate(Icon::Specs, format!("{}:", t!("field.specifications"))));
+                for spec in &product.specifications {
+                    println!("  {} {}: {}", bullet(), spec.attribute, spec.values.join(", "));
+                }
+            }
+            ProductField::Specification(spec_name) => {
+                if let Some(spec) = product.specifications.iter()
+                    .find(|s| s.attribute.eq_ignore_ascii_case(&spec_name)) {
+                    println!("{}", decorate(Icon::Specs, format!("{}: {}", spec.attribute, spec.values.join(", "))));
+                }
+            }
+            ProductField::BasicInfo => {
+                println!("{}", decorate(Icon::Part, format!("{}: {}", t!("field.part_number"), product.part_number)));
+                println!("{}", decorate(Icon::Description, format!("{}: {}", t!("field.description"), product.detail_description)));
+                println!("{}", decorate(Icon::Family, format!("{}: {}", t!("field.family"), product.family_description)));
+                println!("{}", decorate(Icon::Category, format!("{}: {}", t!("field.category"), product.product_category)));
+                println!("{}", decorate(Icon::Status, format!("{}: {}", t!("field.status"), product.product_status)));
+                print_item_number(product);
+            }
+        }
+    }
+}
+
+/// Print a product's internal item number, if the crosswalk has one
+fn print_item_number(product: &ProductDetail) {
+    if let Some(item_number) = &product.item_number {
+        println!("{}", decorate(Icon::Part, format!("{}: {}", t!("field.item_number"), item_number)));
+    }
+}
+
+/// Print confirmation for a product added to the subscription
+pub fn print_added(product: &str, detail: &ProductDetail) {
+    println!("{}", decorate(Icon::Success, t!("product.added", product = product)));
+    println!("   {} - {}", detail.detail_description, detail.family_description);
+}
+
+/// Print price breaks for a product
+pub fn print_prices(product: &str, price_infos: &[PriceInfo]) {
+    println!("{}", decorate(Icon::Price, t!("price.header", product = product)));
+    let Some(first) = price_infos.first() else {
+        return;
+    };
+    let unit = catalog().unit_name(&first.unit_of_measure);
+    for price_info in price_infos {
+        let qty_str = format!("{}+", format_quantity(price_info.minimum_quantity));
+        println!("   {}", t!(
+            "price.break",
+            quantity = format!("{:<8}", qty_str),
+            price = catalog().format_money(price_info.amount, price_info.currency(), 4),
+            unit = unit,
+        ));
+    }
+}
+
+/// Print what to order for a quantity and what it costs
+pub fn print_price_quote(quote: &PriceQuote) {
+    let unit = catalog().unit_name(&quote.unit_of_measure);
+    match quote.pack_size {
+        Some(1) => {}
+        Some(size) => println!("   {}", t!("price.quote.packs", quantity = format_quantity(quote.quantity), size = size, unit = unit)),
+        None => println!("{}", decorate(Icon::Hint, t!("price.quote.pack_unknown", unit = unit))),
+    }
+    println!("   {}", t!(
+        "price.quote.order",
+        units = format_quantity(quote.units),
+        unit = unit,
+        price = catalog().format_money(quote.unit_price, &quote.currency, 4),
+        minimum = format_quantity(quote.break_quantity),
+    ));
+    println!("{}", decorate(Icon::Summary, t!("price.quote.total", total = catalog().format_money(quote.total, &quote.currency, 2))));
+}
+
+/// A quantity without a trailing `.0` when it's whole
+fn format_quantity(quantity: f64) -> String {
+    if quantity == quantity.floor() {
+        format!("{}", quantity as i64)
+    } else {
+        format!("{}", quantity)
+    }
+}
+
+/// Print changed products, one per line
+pub fn print_changes(changes: &[ChangeRecord]) {
+    if changes.is_empty() {
+        println!("{}", decorate(Icon::Empty, t!("changes.empty")));
+        return;
+    }
+
+    println!("{}", decorate(Icon::Status, t!("changes.header", count = changes.len())));
+    for change in changes {
+        let mut line = format!("  {} {}", bullet(), change.part_number);
+        if let Some(date) = &change.change_date {
+            line.push_str(&format!("  {}", date));
+        }
+        if let Some(change_type) = &change.change_type {
+            line.push_str(&format!("  ({})", change_type));
+        }
+        println!("{}", line);
+    }
+}
+
+/// Print locally tracked subscriptions
+///
+/// With `verbose`, each part's note and metadata are shown below it.
+pub fn print_subscriptions(file_path: &Path, parts: &[TrackedPart], verbose: bool) {
+    println!("{}", decorate(Icon::File, t!("list.file", path = file_path.display())));
+    
+    if parts.is_empty() {
+        println!("{}", decorate(Icon::Empty, t!("list.empty")));
+        println!("{}", decorate(Icon::Hint, t!("list.hint")));
+    } else {
+        println!("{}", decorate(Icon::Part, t!("list.header", count = parts.len())));
+        for part in parts {
+            if part.tags.is_empty() {
+                println!("  {} {}", bullet(), part.part_number);
+            } else {
+                println!("  {} {}  [{}]", bullet(), part.part_number, part.tags.join(", "));
+            }
+            if verbose {
+                print_annotations(part, "      ");
+            }
+        }
+    }
+}
+
+/// Print a tracked part's tags, note and metadata
+pub fn print_tracked_part(part: &TrackedPart) {
+    println!("{}", decorate(Icon::Part, &part.part_number));
+    if !part.tags.is_empty() {
+        println!("   {}: {}", t!("note.tags"), part.tags.join(", "));
+    }
+    if part.note.is_none() && part.metadata.is_empty() {
+        println!("   {}", t!("note.none"));
+    }
+    print_annotations(part, "   ");
+}
+
+fn print_annotations(part: &TrackedPart, indent: &str) {
+    if let Some(item_number) = &part.item_number {
+        println!("{}{}: {}", indent, t!("field.item_number"), item_number);
+    }
+    if let Some(note) = &part.note {
+        println!("{}{}: {}", indent, t!("note.note"), note);
+    }
+    for (key, value) in &part.metadata {
+        println!("{}{} = {}", indent, key, value);
+    }
+}
+
+/// Print a part's tags after tagging or untagging it
+pub fn print_tags(product: &str, tags: &[String]) {
+    if tags.is_empty() {
+        println!("{}", decorate(Icon::Success, t!("tag.none", product = product)));
+    } else {
+        println!("{}", decorate(Icon::Success, t!("tag.current", product = product, tags = tags.join(", "))));
+    }
+}
+
+/// Print the item number crosswalk
+pub fn print_crosswalk(crosswalk: &[CrosswalkEntry]) {
+    if crosswalk.is_empty() {
+        println!("{}", decorate(Icon::Empty, t!("xref.empty")));
+        return;
+    }
+    println!("{}", decorate(Icon::Summary, t!("xref.header", count = crosswalk.len())));
+    for entry in crosswalk {
+        println!("  {} {}: {}", bullet(), entry.part_number, entry.item_number);
+    }
+}
+
+/// Print alert rules
+pub fn print_alerts(alerts: &[AlertRule]) {
+    if alerts.is_empty() {
+        println!("{}", decorate(Icon::Empty, t!("alerts.none")));
+        return;
+    }
+    println!("{}", decorate(Icon::Summary, t!("alerts.header", count = alerts.len())));
+    for alert in alerts {
+        let rule = match alert.direction {
+            AlertDirection::Above => t!("alerts.rule.above", threshold = alert.threshold),
+            AlertDirection::Below => t!("alerts.rule.below", threshold = alert.threshold),
+        };
+        let last = alert.last_price.map(|price| format!(" ({})", t!("alerts.last", price = price))).unwrap_or_default();
+        println!("  {} {} {}{}", bullet(), alert.part_number, rule, last);
+    }
+}
+
+/// Print the alerts that fired, on stderr so machine-readable output stays clean
+pub fn print_alert_triggers(triggers: &[AlertTrigger]) {
+    for trigger in triggers {
+        let price = format!("{:.2} {}", trigger.price, trigger.currency);
+        let message = match trigger.rule.direction {
+            AlertDirection::Above => t!("alerts.fired.above", product = trigger.rule.part_number, price = price, unit = trigger.unit_of_measure.to_lowercase(), threshold = trigger.rule.threshold),
+            AlertDirection::Below => t!("alerts.fired.below", product = trigger.rule.part_number, price = price, unit = trigger.unit_of_measure.to_lowercase(), threshold = trigger.rule.threshold),
+        };
+        eprintln!("{}", decorate(Icon::Warning, message));
+    }
+}
+
+/// Print price breaks for several parts
+pub fn print_price_all(results: &[PartPrices]) {
+    if results.is_empty() {
+        println!("{}", decorate(Icon::Empty, t!("bulk.empty")));
+        return;
+    }
+
+    for result in results {
+        match &result.error {
+            Some(error) => println!("{}", decorate(Icon::Error, format!("{}: {}", result.part_number, error))),
+            None => print_prices(&result.part_number, &result.prices),
+        }
+    }
+}
+
+/// Print warnings collected during a command to stderr
+///
+/// Unless `verbose`, only a count is shown so routine data cleanup doesn't clutter output.
+pub fn print_warnings(warnings: &[Warning], verbose: bool) {
+    if warnings.is_empty() {
+        return;
+    }
+
+    let (shown, hidden): (Vec<&Warning>, Vec<&Warning>) = warnings
+        .iter()
+        .partition(|warning| verbose || warning.kind.always_shown());
+
+    for warning in shown {
+        eprintln!("{}", decorate(Icon::Warning, warning));
+    }
+    if !hidden.is_empty() {
+        eprintln!("{}", decorate(Icon::Warning, t!("warnings.hidden", count = hidden.len())));
+    }
+}
+
+/// Print parts that failed in a `--stdin` run, on stderr so piped output stays clean
+pub fn print_part_failures(failed: &[(String, String)]) {
+    if failed.is_empty() {
+        return;
+    }
+    eprintln!("{}", decorate(Icon::Error, t!("stdin.failed", count = failed.len())));
+    for (part, error) in failed {
+        eprintln!("  {} {}: {}", bullet(), part, error);
+    }
+}
+
+/// Print the subject, issuer and validity of the client certificate
+pub fn print_certificate_status(info: &CertificateInfo) {
+    let now = Utc::now();
+    println!("{}", decorate(Icon::Key, t!("cert.status.header", path = info.path.display())));
+    println!("  {}: {}", t!("cert.status.subject"), info.subject);
+    println!("  {}: {}", t!("cert.status.issuer"), info.issuer);
+    println!("  {}: {}", t!("cert.status.valid_from"), info.not_before.format("%Y-%m-%d %H:%M UTC"));
+    println!("  {}: {}", t!("cert.status.expires"), info.not_after.format("%Y-%m-%d %H:%M UTC"));
+
+    if info.is_expired(now) {
+        println!("{}", decorate(Icon::Error, t!("cert.status.expired")));
+    } else if info.expires_soon(now) {
+        println!("{}", decorate(Icon::Warning, t!("cert.status.expires_soon", days = info.days_until_expiry(now))));
+    } else {
+        println!("{}", decorate(Icon::Success, t!("cert.status.valid", days = info.days_until_expiry(now))));
+    }
+}
+
+/// Print the saved token, certificate and credentials, ending with whether API commands will work
+pub fn print_auth_status(status: &AuthStatus) {
+    let now = Utc::now();
+    let none = t!("auth.status.none");
+    let token = match &status.token_file {
+        Some(path) => path.display().to_string(),
+        None => none.clone(),
+    };
+    println!("{}", decorate(Icon::Key, t!("auth.status.header")));
+    println!("  {}: {}", t!("auth.status.token"), token);
+    if status.token_file.is_some() {
+        let expires = match (status.expires, &status.expiration) {
+            (Some(expires), _) => expires.format("%Y-%m-%d %H:%M UTC").to_string(),
+            (None, Some(raw)) => raw.clone(),
+            (None, None) => t!("auth.status.unknown"),
+        };
+        println!("  {}: {}", t!("auth.status.expires"), expires);
+    }
+    let certificate = match (&status.certificate, &status.certificate_error) {
+        (_, Some(error)) => error.clone(),
+        (Some(info), None) => t!("auth.status.certificate_detail", path = info.path.display(), date = info.not_after.format("%Y-%m-%d")),
+        (None, None) => none.clone(),
+    };
+    println!("  {}: {}", t!("auth.status.certificate"), certificate);
+    let credentials = match (&status.credentials_file, &status.username) {
+        (Some(path), Some(username)) => format!("{} ({})", path.display(), username),
+        (None, Some(username)) => username.clone(),
+        _ => none,
+    };
+    println!("  {}: {}", t!("auth.status.credentials"), credentials);
+    println!("  {}: {}", t!("auth.status.auto_login"), t!(if status.auto_login { "auth.status.on" } else { "auth.status.off" }));
+
+    if status.token_file.is_some() && status.token_expired(now) {
+        println!("{}", decorate(Icon::Error, t!("auth.status.expired")));
+    } else if let Some(expires) = status.expires.filter(|_| status.token_file.is_some()) {
+        println!("{}", decorate(Icon::Success, t!("auth.status.valid", hours = (expires - now).num_hours())));
+    } else if status.token_file.is_some() {
+        println!("{}", decorate(Icon::Success, t!("auth.status.saved")));
+    }
+    if status.certificate_error.is_some() {
+        println!("{}", decorate(Icon::Error, t!("auth.status.certificate_error")));
+    } else if !status.is_usable(now) {
+        println!("{}", decorate(Icon::Hint, t!("auth.status.login_hint")));
+    } else if status.token_file.is_none() || status.token_expired(now) {
+        println!("{}", decorate(Icon::Info, t!("auth.status.auto_login_hint")));
+    }
+}
+
+/// Print each self-test step with pass/fail status and an overall verdict
+pub fn print_selftest_report(report: &SelfTestReport) {
+    println!("{}", decorate(Icon::Part, t!("selftest.header", part = report.part)));
+    for step in &report.steps {
+        let icon = match step.status {
+            StepStatus::Passed => Icon::Success,
+            StepStatus::Failed => Icon::Error,
+            StepStatus::Skipped => Icon::Skipped,
+        };
+        let name = t!(&format!("selftest.step.{}", step.name));
+        println!("  {}", decorate(icon, format!("{}: {}", name, step.detail)));
+    }
+
+    let failed = report.steps.iter().filter(|step| step.status == StepStatus::Failed).count();
+    if failed == 0 {
+        println!("{}", decorate(Icon::Success, t!("selftest.passed")));
+    } else {
+        println!("{}", decorate(Icon::Error, t!("selftest.failed", count = failed)));
+    }
+}
+
+/// Print the outcome of a product cache refresh
+pub fn print_refresh_report(report: &RefreshReport) {
+    if report.refreshed.is_empty() && report.fresh.is_empty() && report.failed.is_empty() && report.remaining.is_empty() {
+        println!("{}", decorate(Icon::Empty, t!("refresh.empty")));
+        return;
+    }
+
+    println!("{}", decorate(Icon::Success, t!("refresh.refreshed", count = report.refreshed.len())));
+    if !report.fresh.is_empty() {
+        println!("{}", decorate(Icon::Skipped, t!("refresh.fresh", count = report.fresh.len())));
+    }
+    if !report.failed.is_empty() {
+        println!("{}", decorate(Icon::Error, t!("refresh.failed", count = report.failed.len())));
+        for (part, error) in &report.failed {
+            println!("  {} {}: {}", bullet(), part, error);
+        }
+    }
+    print_remaining(&report.remaining);
+}
+
+/// Print the parts a cancelled operation didn't get to
+fn print_remaining(remaining: &[String]) {
+    if remaining.is_empty() {
+        return;
+    }
+    println!("{}", decorate(Icon::Warning, t!("cancel.remaining", count = remaining.len())));
+    for part in remaining {
+        println!("  {} {}", bullet(), part);
+    }
+}
+
+/// Print where an enriched BOM went and which parts couldn't be looked up
+pub fn print_bom_enrichment(enrichment: &BomEnrichment, path: &Path) {
+    println!("{}", decorate(Icon::Success, t!("bom.enriched", count = enrichment.enriched, path = path.display())));
+    if !enrichment.failed.is_empty() {
+        println!("{}", decorate(Icon::Error, t!("bom.failed", count = enrichment.failed.len())));
+        for (part, error) in &enrichment.failed {
+            println!("  {} {}: {}", bullet(), part, error);
+        }
+    }
+}
+
+/// Print the rows `mmc bom check` failed, with their problems, and a pass/fail summary
+pub fn print_bom_check(report: &BomCheckReport) {
+    for line in report.failures() {
+        let part = line.part_number.as_deref().unwrap_or("-");
+        println!("{}", decorate(Icon::Error, t!("bom.check.line", line = line.line, part = part)));
+        for problem in &line.problems {
+            println!("  {} {}", bullet(), problem);
+        }
+    }
+    if !report.quantities_checked {
+        println!("{}", decorate(Icon::Warning, t!("bom.check.no_quantities")));
+    }
+
+    let failed = report.failures().count();
+    if failed == 0 {
+        println!("{}", decorate(Icon::Success, t!("bom.check.passed", count = report.lines.len())));
+    } else {
+        println!("{}", decorate(Icon::Summary, t!("bom.check.failed", failed = failed, count = report.lines.len())));
+    }
+}
+
+/// Print each part to order with its packs and cost, the order total, and where the list went
+pub fn print_purchase_order(order: &PurchaseOrder, path: &Path) {
+    for line in &order.lines {
+        println!("{}", decorate(Icon::Part, t!(
+            "bom.order.line",
+            part = line.part_number,
+            quantity = format_quantity(line.quantity),
+            units = format_quantity(line.units),
+            unit = catalog().unit_name(&line.unit_of_measure),
+            total = catalog().format_money(line.total, &line.currency, 2),
+        )));
+    }
+    let unknown: Vec<&str> = order.lines.iter()
+        .filter(|line| line.pack_size.is_none())
+        .map(|line| line.part_number.as_str())
+        .collect();
+    if !unknown.is_empty() {
+        println!("{}", decorate(Icon::Hint, t!("bom.order.pack_unknown", parts = unknown.join(", "))));
+    }
+    for (currency, total) in &order.totals {
+        println!("{}", decorate(Icon::Summary, t!("bom.order.total", count = order.lines.len(), total = catalog().format_money(*total, currency, 2))));
+    }
+    if !order.failed.is_empty() {
+        println!("{}", decorate(Icon::Error, t!("bom.failed", count = order.failed.len())));
+        for (part, error) in &order.failed {
+            println!("  {} {}: {}", bullet(), part, error);
+        }
+    }
+    println!("{}", decorate(Icon::File, t!("bom.order.written", path = path.display())));
+}
+
+/// Print two products' specifications in columns, marking the rows that differ
+pub fn print_product_diff(diff: &ProductDiff, differences_only: bool) {
+    println!("{}", decorate(Icon::Specs, t!("diff.header", first = diff.first, second = diff.second)));
+
+    let marker = if is_plain_output() { "*" } else { "≠" };
+    let missing = t!("diff.missing");
+    let join = |values: &[String]| if values.is_empty() { missing.clone() } else { values.join(", ") };
+    let rows: Vec<_> = diff.specifications.iter()
+        .filter(|spec| spec.differs || !differences_only)
+        .map(|spec| (spec.differs, spec.attribute.as_str(), join(&spec.first), join(&spec.second)))
+        .collect();
+
+    let attribute_width = rows.iter().map(|row| row.1.chars().count()).max().unwrap_or(0);
+    let first_width = rows.iter().map(|row| row.2.chars().count()).chain([diff.first.chars().count()]).max().unwrap_or(0);
+    if !rows.is_empty() {
+        println!("    {:attribute_width$}  {:first_width$}  {}", "", diff.first, diff.second);
+    }
+    for (differs, attribute, first, second) in &rows {
+        let marker = if *differs { marker } else { " " };
+        println!("  {} {:attribute_width$}  {:first_width$}  {}", marker, attribute, first, second);
+    }
+
+    let differing = diff.differences().count();
+    if differing == 0 {
+        println!("{}", decorate(Icon::Success, t!("diff.identical")));
+    } else {
+        println!("{}", decorate(Icon::Summary, t!("diff.summary", count = differing, total = diff.specifications.len())));
+    }
+}
+
+/// Print the parts added, removed and changed between two snapshots
+pub fn print_snapshot_diff(diff: &SnapshotDiff) {
+    let date = |at: chrono::DateTime<Utc>| at.format("%Y-%m-%d %H:%M").to_string();
+    println!("{}", decorate(Icon::Specs, t!("snapshot.header", from = date(diff.from), to = date(diff.to))));
+    if diff.is_empty() {
+        println!("{}", decorate(Icon::Success, t!("snapshot.identical")));
+        return;
+    }
+
+    let missing = t!("diff.missing");
+    let value = |value: &str| if value.is_empty() { missing.clone() } else { value.to_string() };
+    for part in &diff.added {
+        println!("  + {}", part);
+    }
+    for part in &diff.removed {
+        println!("  - {}", part);
+    }
+    for part in &diff.changed {
+        println!("  ~ {}", part.part_number);
+        for change in &part.changes {
+            println!("      {}: {} -> {}", change.field, value(&change.before), value(&change.after));
+        }
+    }
+    println!("{}", decorate(Icon::Summary, t!("snapshot.summary", added = diff.added.len(), removed = diff.removed.len(), changed = diff.changed.len())));
+}
+
+/// Print alternates for a part with what sets each apart from it
+pub fn print_alternates(original: &ProductDetail, alternates: &[Alternate]) {
+    if alternates.is_empty() {
+        println!("{}", decorate(Icon::Empty, t!("alternates.none", product = original.part_number, category = original.product_category)));
+        println!("{}", decorate(Icon::Hint, t!("alternates.hint")));
+        return;
+    }
+
+    println!("{}", decorate(Icon::Specs, t!("alternates.header", product = original.part_number, category = original.product_category)));
+    let missing = t!("diff.missing");
+    for alternate in alternates {
+        let similarity = format!("{:.0}%", alternate.similarity * 100.0);
+        println!("{}", decorate(Icon::Part, t!("alternates.entry", product = alternate.part_number, description = alternate.description, similarity = similarity)));
+        for spec in &alternate.differences {
+            let value = |values: &[String]| if values.is_empty() { missing.clone() } else { values.join(", ") };
+            println!("  {} {}: {} -> {}", bullet(), spec.attribute, value(&spec.first), value(&spec.second));
+        }
+    }
+}
+
+/// Print each supplier's price per piece for a part, cheapest first
+pub fn print_vendor_comparison(comparison: &VendorComparison) {
+    println!("{}", decorate(Icon::Part, format!("{} - {}", comparison.part_number, comparison.description)));
+    let mut quotes: Vec<&VendorQuote> = comparison.quotes.iter().collect();
+    quotes.sort_by(|a, b| match (a.price, b.price) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    for quote in quotes {
+        let price = match (&quote.error, quote.price) {
+            (Some(error), _) => error.clone(),
+            (None, Some(price)) => t!("vendors.price", price = format!("{:.4}", price), currency = quote.currency.as_deref().unwrap_or_default()),
+            (None, None) => t!("vendors.no_price"),
+        };
+        let icon = if quote.error.is_some() { Icon::Error } else { Icon::Price };
+        println!("{}", decorate(icon, format!("{}: {}", quote.vendor, price)));
+        if let Some(url) = &quote.url {
+            println!("   {}", url);
+        }
+    }
+}
+
+/// Print how many downloaded datasheets were added to the search index
+pub fn print_datasheets_indexed(count: usize) {
+    println!("{}", decorate(Icon::Info, t!("search.indexed", count = count)));
+}
+
+/// Print what `mmc datasheet merge` wrote and the parts it couldn't include
+pub fn print_datasheet_merge(report: &MergeReport) {
+    println!("{}", decorate(Icon::File, t!("merge.written", pages = report.pages, count = report.merged.len(), path = report.path.display())));
+    if !report.missing.is_empty() {
+        eprintln!("{}", decorate(Icon::Warning, t!("merge.missing", count = report.missing.len(), parts = report.missing.join(", "))));
+    }
+    for (file, error) in &report.failed {
+        eprintln!("{}", decorate(Icon::Error, t!("merge.unreadable", file = file, error = error)));
+    }
+}
+
+/// Print datasheet search results with the matching text
+pub fn print_datasheet_matches(matches: &[DatasheetMatch]) {
+    if matches.is_empty() {
+        println!("{}", decorate(Icon::Empty, t!("search.none")));
+        return;
+    }
+
+    for datasheet in matches {
+        println!("{}", decorate(Icon::Part, format!("{}  {}", datasheet.part_number, datasheet.file.display())));
+        println!("   {}", datasheet.snippet);
+    }
+}
+
+/// Print the outcome of a subscription sync
+pub fn print_sync_report(report: &SyncReport) {
+    if report.verified.is_empty() && report.not_found.is_empty() && report.remaining.is_empty() {
+        println!("{}", decorate(Icon::Empty, t!("sync.empty")));
+        return;
+    }
+
+    println!("{}", decorate(Icon::Success, t!("sync.verified", count = report.verified.len())));
+    
+    if !report.not_found.is_empty() {
+        println!("{}", decorate(Icon::Error, t!("sync.not_found", count = report.not_found.len())));
+        for part in &report.not_found {
+            println!("  {} {}", bullet(), part);
+        }
+    }
+
+    if !report.discontinued.is_empty() {
+        println!("{}", decorate(Icon::Warning, t!("sync.discontinued", count = report.discontinued.len())));
+        for part in &report.discontinued {
+            match &part.replacement {
+                Some(replacement) => println!("  {} {} ({}) - {}", bullet(), part.part_number, part.status, t!("sync.replacement", part = replacement)),
+                None => println!("  {} {} ({})", bullet(), part.part_number, part.status),
+            }
+        }
+        if let Some(path) = &report.discontinued_report {
+            println!("{}", decorate(Icon::File, t!("sync.discontinued_report", path = path.display())));
+        }
+    }
+    print_remaining(&report.remaining);
+}
+
+/// Print the most recent journal entries, newest first
+pub fn print_journal(entries: &[JournalEntry]) {
+    const SHOWN: usize = 20;
+    if entries.is_empty() {
+        println!("{}", decorate(Icon::Empty, t!("undo.empty_journal")));
+        return;
+    }
+    println!("{}", decorate(Icon::Summary, t!("undo.history", count = entries.len().min(SHOWN))));
+    for entry in entries.iter().rev().take(SHOWN) {
+        let undoes = entry.undoes.map(|id| format!(" #{}", id)).unwrap_or_default();
+        println!(
+            "  {} #{} {} {}{}: {}",
+            bullet(),
+            entry.id,
+            entry.at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+            t!(entry.operation.message_key()),
+            undoes,
+            entry.parts.join(", ")
+        );
+    }
+}
+
+/// Print what `mmc undo` reverted
+pub fn print_undo_report(report: &UndoReport) {
+    let operation = t!(report.entry.operation.message_key());
+    let time = report.entry.at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+    if report.failed.is_empty() {
+        println!("{}", decorate(Icon::Success, t!("undo.done", operation = operation, count = report.reverted.len(), time = time)));
+    } else {
+        println!("{}", decorate(Icon::Error, t!("undo.partial", operation = operation, count = report.failed.len(), time = time)));
+        for (part, error) in &report.failed {
+            println!("  {} {}: {}", bullet(), part, error);
+        }
+    }
+}
+
+/// Print what `mmc sync --remote` pulled, pushed and removed
+pub fn print_team_sync_report(report: &TeamSyncReport) {
+    println!("{}", decorate(Icon::Success, t!("sync.team.synced", count = report.total, remote = report.remote)));
+    let sections = [
+        (Icon::Download, "sync.team.pulled", &report.pulled),
+        (Icon::Status, "sync.team.pushed", &report.pushed),
+        (Icon::Info, "sync.team.removed", &report.removed),
+        (Icon::Warning, "sync.team.conflicts", &report.conflicts),
+    ];
+    for (icon, key, parts) in sections.into_iter().filter(|(_, _, parts)| !parts.is_empty()) {
+        println!("{}", decorate(icon, t!(key, count = parts.len())));
+        for part in parts {
+            println!("  {} {}", bullet(), part);
+        }
+    }
+}
+
+/// Print a summary table of download results
+///
+/// `kind` is the catalog key for the kind of file, e.g. "download.kind.cad".
+pub fn print_download_summary(kind: &str, product: &str, results: &[DownloadResult]) {
+    let kind = t!(kind);
+    if results.is_empty() {
+        println!("{}", decorate(Icon::Info, t!("download.none", kind = kind, product = product)));
+        return;
+    }
+
+    if let Some(dir) = results[0].file_path.parent() {
+        println!("{}", decorate(Icon::Download, t!("download.saved_to", kind = kind, path = dir.display())));
+    }
+    print_download_results(results);
+}
+
+/// Print one line per download result followed by totals
+pub fn print_download_results(results: &[DownloadResult]) {
+    let width = results.iter().map(|r| r.filename.len()).max().unwrap_or(0);
+
+    for result in results {
+        match &result.error {
+            None if result.skipped => {
+                println!("  {}", decorate(Icon::Skipped, format!("{:<width$}  {}", result.filename, t!("download.skipped"), width = width)));
+            }
+            None => {
+                let mut notes: Vec<String> = result.label.iter().cloned().collect();
+                if !result.extracted.is_empty() {
+                    notes.push(t!("download.extracted", count = result.extracted.len()));
+                }
+                let detail = if notes.is_empty() {
+                    format!("{:>10}", format_bytes(result.bytes))
+                } else {
+                    format!("{:>10}  ({})", format_bytes(result.bytes), notes.join(", "))
+                };
+                println!("  {}", decorate(Icon::Success, format!("{:<width$}  {}", result.filename, detail, width = width)));
+            }
+            Some(e) => {
+                eprintln!("  {}", decorate(Icon::Error, format!("{:<width$}  {}", result.filename, e, width = width)));
+            }
+        }
+    }
+
+    let skipped = results.iter().filter(|r| r.skipped).count();
+    let succeeded = results.iter().filter(|r| r.is_success()).count() - skipped;
+    let failed = results.len() - succeeded - skipped;
+    let total_bytes: u64 = results.iter().filter(|r| !r.skipped).map(|r| r.bytes).sum();
+    let mut summary = t!("download.summary", count = succeeded, size = format_bytes(total_bytes));
+    if skipped > 0 {
+        summary.push_str(&t!("download.summary_unchanged", count = skipped));
+    }
+    if failed > 0 {
+        summary.push_str(&t!("download.summary_failed", count = failed));
+    }
+    println!("{}", decorate(Icon::Summary, summary));
+}
+
+/// Print the outcome of a bulk download across tracked parts
+pub fn print_bulk_download_report(report: &BulkDownloadReport) {
+    if report.total_parts == 0 {
+        println!("{}", decorate(Icon::Empty, t!("bulk.empty")));
+        return;
+    }
+
+    if !report.results.is_empty() {
+        print_download_results(&report.results);
+    }
+
+    println!("{}", decorate(Icon::Success, t!("bulk.done", succeeded = report.succeeded_parts(), total = report.total_parts)));
+    if !report.no_files.is_empty() {
+        println!("{}", decorate(Icon::Info, t!("bulk.no_files", count = report.no_files.len())));
+        for part in &report.no_files {
+            println!("  {} {}", bullet(), part);
+        }
+    }
+    if !report.failures.is_empty() {
+        println!("{}", decorate(Icon::Error, t!("bulk.failures")));
+        for (part, error) in &report.failures {
+            println!("  {} {}: {}", bullet(), part, error);
+        }
+    }
+    print_remaining(&report.remaining);
+}
+
+/// Print each re-hashed download and how many still match their manifests
+pub fn print_file_checks(checks: &[FileCheck]) {
+    for check in checks {
+        let path = check.file_path.display();
+        match check.status {
+            FileStatus::Ok => println!("  {}", decorate(Icon::Success, path)),
+            FileStatus::Modified => println!("  {}", decorate(Icon::Error, t!(
+                "verify.modified",
+                path = path,
+                expected = &check.expected_sha256[..12.min(check.expected_sha256.len())],
+                actual = check.actual_sha256.as_deref().map_or("", |hash| &hash[..12.min(hash.len())])
+            ))),
+            FileStatus::Missing => println!("  {}", decorate(Icon::Warning, t!("verify.missing", path = path))),
+        }
+    }
+    let ok = checks.iter().filter(|check| check.status == FileStatus::Ok).count();
+    let icon = if ok == checks.len() { Icon::Success } else { Icon::Error };
+    println!("{}", decorate(icon, t!("verify.summary", ok = ok, total = checks.len())));
+}
+
+/// Print the outcome of building a SolidWorks Design Library
+pub fn print_solidworks_library_report(report: &SolidworksLibraryReport) {
+    print_bulk_download_report(&report.downloads);
+    if !report.property_files.is_empty() {
+        println!("{}", decorate(Icon::File, t!("swlibrary.properties", count = report.property_files.len())));
+    }
+    if report.downloads.total_parts > 0 {
+        println!("{}", decorate(Icon::Info, t!("swlibrary.root", path = report.root.display())));
+    }
+}
+
+/// Print the downloads behind `mmc package` and the archives it wrote
+pub fn print_package_report(report: &PackageReport) {
+    print_bulk_download_report(&report.downloads);
+    for archive in &report.archives {
+        println!("{}", decorate(Icon::File, t!("package.written", path = archive.display())));
+    }
+}
+
+/// Format a byte count for display (e.g. "1.4 MB")
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
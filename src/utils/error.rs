@@ -3,44 +3,175 @@
 use std::error::Error;
 use std::fmt;
 
+use crate::models::auth::ErrorResponse;
+
+/// Result type returned by all public client operations
+pub type Result<T> = std::result::Result<T, ClientError>;
+
 /// Custom error type for McMaster-Carr CLI operations
 #[derive(Debug)]
 pub enum ClientError {
-    /// HTTP request failed
-    Request(reqwest::Error),
+    /// No token is available; the user needs to login
+    NotAuthenticated,
+    /// The API rejected the token (expired or revoked)
+    AuthExpired,
+    /// Authentication error (e.g. login rejected)
+    Auth(String),
+    /// The product exists but isn't in the account's subscription
+    NotSubscribed(String),
+    /// The requested resource doesn't exist
+    NotFound(String),
+    /// The API is throttling requests
+    RateLimited {
+        retry_after: Option<u64>, // Seconds, when the API says how long to wait
+    },
+    /// Client certificate could not be found or loaded
+    Certificate(String),
+    /// A response or local file could not be parsed
+    Parse(String),
+    /// Network failure (connection, TLS, timeout)
+    Network(reqwest::Error),
+    /// The command ran past its `--deadline`
+    DeadlineExceeded(u64),
     /// JSON serialization/deserialization failed
     Json(serde_json::Error),
     /// File I/O error
     Io(std::io::Error),
-    /// Authentication error
-    Auth(String),
-    /// API error with message
-    Api(String),
+    /// Local subscription or cache database error
+    Database(rusqlite::Error),
+    /// The operation needs the network but --offline was given, or the cache lacks the data
+    Offline(String),
+    /// API error with HTTP status and message
+    Api {
+        status: u16,
+        message: String,
+    },
     /// Configuration error
     Config(String),
     /// Generic error with message
     Generic(String),
 }
 
+impl ClientError {
+    /// Build an error from a failed API response body
+    ///
+    /// `context` describes the operation, e.g. "Failed to get price".
+    pub fn from_response(status: u16, body: &str, context: &str) -> Self {
+        let message = Self::response_message(body);
+
+        match status {
+            401 | 403 => ClientError::AuthExpired,
+            429 => ClientError::RateLimited { retry_after: None },
+            _ => ClientError::Api {
+                status,
+                message: format!("{}: {}", context, message),
+            },
+        }
+    }
+
+    /// Extract the error message from an API error body (or return the raw body)
+    pub fn response_message(body: &str) -> String {
+        match serde_json::from_str::<ErrorResponse>(body) {
+            Ok(error_response) => error_response.error_message.unwrap_or("Unknown error".to_string()),
+            Err(_) => body.to_string(),
+        }
+    }
+
+    /// Stable snake_case name of the error variant, for machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ClientError::NotAuthenticated => "not_authenticated",
+            ClientError::AuthExpired => "auth_expired",
+            ClientError::Auth(_) => "auth",
+            ClientError::NotSubscribed(_) => "not_subscribed",
+            ClientError::NotFound(_) => "not_found",
+            ClientError::RateLimited { .. } => "rate_limited",
+            ClientError::Certificate(_) => "certificate",
+            ClientError::Parse(_) => "parse",
+            ClientError::Network(_) => "network",
+            ClientError::DeadlineExceeded(_) => "deadline_exceeded",
+            ClientError::Json(_) => "json",
+            ClientError::Io(_) => "io",
+            ClientError::Database(_) => "database",
+            ClientError::Offline(_) => "offline",
+            ClientError::Api { .. } => "api",
+            ClientError::Config(_) => "config",
+            ClientError::Generic(_) => "generic",
+        }
+    }
+
+    /// Read a failed response and convert it into an error
+    pub(crate) async fn from_http(response: reqwest::Response, context: &str) -> Self {
+        let status = response.status().as_u16();
+        let retry_after = response.headers()
+            .get("Retry-After")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok());
+
+        let body = match response.text().await {
+            Ok(body) => body,
+            Err(e) => return ClientError::Network(e),
+        };
+
+        match ClientError::from_response(status, &body, context) {
+            ClientError::RateLimited { .. } => ClientError::RateLimited { retry_after },
+            other => other,
+        }
+    }
+}
+
 impl fmt::Display for ClientError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ClientError::Request(e) => write!(f, "Request error: {}", e),
+            ClientError::NotAuthenticated => write!(f, "Not authenticated. Please login first with 'mmc login'"),
+            ClientError::AuthExpired => write!(f, "Authentication token expired or was rejected. Please login again with 'mmc login'"),
+            ClientError::Auth(msg) => write!(f, "Authentication error: {}", msg),
+            ClientError::NotSubscribed(product) => write!(
+                f,
+                "Product {} is not in your subscription. Add it with 'mmc add {}'",
+                product, product
+            ),
+            ClientError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            ClientError::RateLimited { retry_after: Some(seconds) } => {
+                write!(f, "Rate limited by the API; retry after {} seconds", seconds)
+            }
+            ClientError::RateLimited { retry_after: None } => write!(f, "Rate limited by the API; try again later"),
+            ClientError::Certificate(msg) => write!(f, "Certificate error: {}", msg),
+            ClientError::Parse(msg) => write!(f, "Parse error: {}", msg),
+            ClientError::Network(e) => write!(f, "Network error: {}", e),
+            ClientError::DeadlineExceeded(seconds) => {
+                write!(f, "Gave up after the {} second deadline; raise --deadline or `deadline` in config.toml", seconds)
+            }
             ClientError::Json(e) => write!(f, "JSON error: {}", e),
             ClientError::Io(e) => write!(f, "I/O error: {}", e),
-            ClientError::Auth(msg) => write!(f, "Authentication error: {}", msg),
-            ClientError::Api(msg) => write!(f, "API error: {}", msg),
+            ClientError::Database(e) => write!(f, "Local database error: {}", e),
+            ClientError::Offline(msg) => write!(f, "Offline: {}", msg),
+            ClientError::Api { message, .. } => write!(f, "{}", message),
             ClientError::Config(msg) => write!(f, "Configuration error: {}", msg),
             ClientError::Generic(msg) => write!(f, "Error: {}", msg),
         }
     }
 }
 
-impl Error for ClientError {}
+impl Error for ClientError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ClientError::Network(e) => Some(e),
+            ClientError::Json(e) => Some(e),
+            ClientError::Io(e) => Some(e),
+            ClientError::Database(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl From<reqwest::Error> for ClientError {
     fn from(err: reqwest::Error) -> Self {
-        ClientError::Request(err)
+        if err.is_decode() {
+            ClientError::Parse(err.to_string())
+        } else {
+            ClientError::Network(err)
+        }
     }
 }
 
@@ -54,4 +185,30 @@ impl From<std::io::Error> for ClientError {
     fn from(err: std::io::Error) -> Self {
         ClientError::Io(err)
     }
-}
\ No newline at end of file
+}
+
+impl From<rusqlite::Error> for ClientError {
+    fn from(err: rusqlite::Error) -> Self {
+        ClientError::Database(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_response_maps_status_codes() {
+        assert!(matches!(ClientError::from_response(401, "", "Failed"), ClientError::AuthExpired));
+        assert!(matches!(ClientError::from_response(429, "", "Failed"), ClientError::RateLimited { .. }));
+
+        let body = r#"{"ErrorCode": "X", "ErrorMessage": "Bad part number", "ErrorDescription": null}"#;
+        match ClientError::from_response(400, body, "Failed to get price") {
+            ClientError::Api { status, message } => {
+                assert_eq!(status, 400);
+                assert_eq!(message, "Failed to get price: Bad part number");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Message catalog for user-facing strings
+//!
+//! Messages are looked up by key in the active locale, falling back to the
+//! built-in English catalog. Additional locales can be dropped into
+//! `~/.config/mmc/locales/<lang>.toml` to add or override translations.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::config::paths::get_config_dir;
+
+/// Built-in catalogs, keyed by language code
+const BUILTIN_CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../../locales/en.toml")),
+    ("es", include_str!("../../locales/es.toml")),
+];
+
+/// Language used when no locale is configured or a key is missing
+pub const DEFAULT_LOCALE: &str = "en";
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// A set of translated messages for one locale
+#[derive(Debug)]
+pub struct Catalog {
+    locale: String,
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// Load the catalog for a locale (e.g. "es" or "es_MX.UTF-8")
+    pub fn load(locale: &str) -> Self {
+        let language = normalize_locale(locale);
+        let fallback = parse_catalog(builtin_catalog(DEFAULT_LOCALE).unwrap_or_default());
+
+        let mut messages = builtin_catalog(&language)
+            .map(parse_catalog)
+            .unwrap_or_default();
+
+        // User catalogs extend or override the built-in translations
+        let user_path = get_config_dir().join("locales").join(format!("{}.toml", language));
+        if let Ok(content) = std::fs::read_to_string(user_path) {
+            messages.extend(parse_catalog(&content));
+        }
+
+        Catalog {
+            locale: language,
+            messages,
+            fallback,
+        }
+    }
+
+    /// The language code this catalog was loaded for
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Look up a message, falling back to English and then to the key itself
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.messages.get(key)
+            .or_else(|| self.fallback.get(key))
+            .map(String::as_str)
+            .unwrap_or(key)
+    }
+
+    /// A message for this locale or English, if either defines it
+    fn lookup(&self, key: &str) -> Option<&str> {
+        self.messages.get(key)
+            .or_else(|| self.fallback.get(key))
+            .map(String::as_str)
+    }
+
+    /// Format money for this locale, e.g. "$1,234.50" in English or "1.234,50 US$" in Spanish
+    ///
+    /// Currencies without a `money.symbol.<code>` message are shown by ISO code.
+    pub fn format_money(&self, amount: f64, currency: &str, decimals: usize) -> String {
+        let fixed = format!("{:.*}", decimals, amount.abs());
+        let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+
+        let mut number = String::new();
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                number.push_str(self.get("money.group"));
+            }
+            number.push(digit);
+        }
+        if !fraction.is_empty() {
+            number.push_str(self.get("money.decimal"));
+            number.push_str(fraction);
+        }
+        let sign = if amount < 0.0 { "-" } else { "" };
+
+        let code = currency.trim().to_uppercase();
+        match self.lookup(&format!("money.symbol.{}", code.to_lowercase())) {
+            Some(symbol) => format!("{}{}", sign, self.get("money.pattern").replace("{symbol}", symbol).replace("{amount}", &number)),
+            None => format!("{}{} {}", sign, number, code),
+        }
+    }
+
+    /// Translated name of a unit of measure from the API (e.g. "Pack"), or the name as given
+    pub fn unit_name<'a>(&'a self, unit: &'a str) -> &'a str {
+        self.lookup(&format!("unit.{}", unit.trim().to_lowercase())).unwrap_or(unit)
+    }
+
+    /// Look up a message and fill in `{name}` placeholders
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+        let mut message = self.get(key).to_string();
+        for (name, value) in args {
+            message = message.replace(&format!("{{{}}}", name), value);
+        }
+        message
+    }
+}
+
+/// Set the active locale; only the first call (or first lookup) takes effect
+pub fn set_locale(locale: &str) {
+    let _ = CATALOG.set(Catalog::load(locale));
+}
+
+/// The active catalog, detected from the environment on first use
+pub fn catalog() -> &'static Catalog {
+    CATALOG.get_or_init(|| Catalog::load(&detect_locale()))
+}
+
+/// Detect the locale from MMC_LANG, then the standard POSIX locale variables
+pub fn detect_locale() -> String {
+    ["MMC_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+/// Reduce a POSIX locale like "es_MX.UTF-8" to its language code
+fn normalize_locale(locale: &str) -> String {
+    let language = locale
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+    if language.is_empty() || language == "c" || language == "posix" {
+        DEFAULT_LOCALE.to_string()
+    } else {
+        language
+    }
+}
+
+/// Find a built-in catalog by language code
+fn builtin_catalog(language: &str) -> Option<&'static str> {
+    BUILTIN_CATALOGS.iter()
+        .find(|(code, _)| *code == language)
+        .map(|(_, content)| *content)
+}
+
+/// Parse a TOML catalog of `"key" = "message"` pairs
+fn parse_catalog(content: &str) -> HashMap<String, String> {
+    toml::from_str::<HashMap<String, String>>(content).unwrap_or_default()
+}
+
+/// Look up a localized message, optionally filling in named placeholders
+///
+/// ```text
+/// t!("product.added", product = part_number)
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::utils::i18n::catalog().get($key).to_string()
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::utils::i18n::catalog().format($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_locale() {
+        assert_eq!(normalize_locale("es_MX.UTF-8"), "es");
+        assert_eq!(normalize_locale("en-US"), "en");
+        assert_eq!(normalize_locale("C"), "en");
+        assert_eq!(normalize_locale(""), "en");
+    }
+
+    #[test]
+    fn test_format_and_fallback() {
+        let catalog = Catalog::load("xx");
+        assert_eq!(
+            catalog.format("product.added", &[("product", "91290A115".to_string())]),
+            "Added 91290A115 to subscription"
+        );
+        assert_eq!(catalog.get("no.such.key"), "no.such.key");
+
+        assert_eq!(catalog.format_money(1234.5, "usd", 2), "$1,234.50");
+        assert_eq!(catalog.format_money(9.5, "CHF", 4), "9.5000 CHF");
+        assert_eq!(catalog.unit_name("Pack"), "Pack");
+
+        let spanish = Catalog::load("es_MX");
+        assert_eq!(spanish.format_money(1234.5, "USD", 2), "1.234,50 US$");
+        assert_eq!(spanish.format_money(-0.25, "EUR", 2), "-0,25 €");
+        assert_eq!(spanish.unit_name("Pack"), "paquete");
+        assert_eq!(spanish.unit_name("Spool"), "Spool");
+    }
+
+    #[test]
+    fn test_builtin_catalogs_are_complete() {
+        let english = parse_catalog(builtin_catalog(DEFAULT_LOCALE).unwrap());
+        for (code, content) in BUILTIN_CATALOGS {
+            let messages = parse_catalog(content);
+            for key in english.keys() {
+                assert!(messages.contains_key(key), "locale '{}' is missing '{}'", code, key);
+            }
+        }
+    }
+}
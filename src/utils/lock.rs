@@ -0,0 +1,91 @@
+//! Advisory file locks for state shared between concurrent mmc processes
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// An advisory lock on a sidecar `.lock` file, released when dropped
+#[derive(Debug)]
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Block until no other process holds any lock on `path`
+    pub fn exclusive(path: &Path) -> io::Result<Self> {
+        let file = open_lock_file(path)?;
+        file.lock()?;
+        Ok(FileLock { _file: file })
+    }
+
+    /// Block until no other process holds an exclusive lock on `path`
+    pub fn shared(path: &Path) -> io::Result<Self> {
+        let file = open_lock_file(path)?;
+        file.lock_shared()?;
+        Ok(FileLock { _file: file })
+    }
+
+    /// [`FileLock::exclusive`] for async code, waiting on a blocking thread
+    pub async fn exclusive_async(path: &Path) -> io::Result<Self> {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || Self::exclusive(&path)).await?
+    }
+
+    /// [`FileLock::shared`] for async code, waiting on a blocking thread
+    pub async fn shared_async(path: &Path) -> io::Result<Self> {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || Self::shared(&path)).await?
+    }
+}
+
+/// Sidecar lock file for `path`, e.g. `token` -> `token.lock`
+pub fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+fn open_lock_file(path: &Path) -> io::Result<File> {
+    let lock_path = lock_path(path);
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).truncate(false).write(true).open(lock_path)
+}
+
+/// Replace the contents of `path` without readers ever seeing a partial file
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut tmp_name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    tmp_name.push(format!(".tmp{}", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_locks_and_atomic_write() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("token");
+        assert_eq!(lock_path(&path), temp_dir.path().join("token.lock"));
+
+        // Shared locks can be held together, and an exclusive one after they're released
+        let first = FileLock::shared(&path).unwrap();
+        let second = FileLock::shared(&path).unwrap();
+        drop((first, second));
+        drop(FileLock::exclusive(&path).unwrap());
+        let _exclusive = FileLock::exclusive_async(&path).await.unwrap();
+
+        write_atomic(&path, b"abc").unwrap();
+        write_atomic(&path, b"de").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "de");
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2); // token and token.lock
+    }
+}
@@ -3,8 +3,14 @@
 //! This module contains utility functions and types used throughout
 //! the application, including output formatting and error handling.
 
+pub mod archive;
+pub mod display;
 pub mod error;
+pub mod i18n;
+pub mod lock;
+pub mod open;
 pub mod output;
+pub mod qr;
 
 pub use error::ClientError;
 pub use output::{OutputFormat, ProductField};
\ No newline at end of file
@@ -0,0 +1,49 @@
+//! Opening URLs and files in the desktop's default browser or viewer
+
+use crate::utils::error::{ClientError, Result};
+use std::ffi::OsStr;
+use std::process::Command;
+
+/// The platform's command for opening `target` with its default application
+fn opener(target: &OsStr) -> Command {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        // `start` would need cmd.exe quoting, which mangles URLs with `&` in them
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command.arg(target);
+    command
+}
+
+/// Open a URL or file in the default browser or viewer, without waiting for it to close
+pub fn open_in_default_app(target: impl AsRef<OsStr>) -> Result<()> {
+    let mut command = opener(target.as_ref());
+    let program = command.get_program().to_string_lossy().into_owned();
+    let status = command
+        .status()
+        .map_err(|e| ClientError::Generic(format!("Could not run {}: {}", program, e)))?;
+    if !status.success() {
+        return Err(ClientError::Generic(format!("{} could not open '{}' ({})", program, target.as_ref().to_string_lossy(), status)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opener() {
+        let command = opener(OsStr::new("https://www.mcmaster.com/91290A115/?a=1&b=2"));
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args.last(), Some(&OsStr::new("https://www.mcmaster.com/91290A115/?a=1&b=2")));
+        if cfg!(target_os = "linux") {
+            assert_eq!(command.get_program(), "xdg-open");
+        }
+    }
+}
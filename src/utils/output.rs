@@ -1,15 +1,39 @@
 //! Output formatting utilities
 
 use std::fmt;
+use std::io::Write;
 use clap::ValueEnum;
+use serde::Deserialize;
+
+use comfy_table::presets::{ASCII_MARKDOWN, UTF8_FULL_CONDENSED};
+use comfy_table::{ContentArrangement, Table};
+
+use crate::client::alerts::AlertRule;
+use crate::client::alternates::Alternate;
+use crate::client::api::PartPrices;
+use crate::client::bom::{BomCheckReport, PurchaseOrder};
+use crate::client::cache::DatasheetMatch;
+use crate::client::crosswalk::CrosswalkEntry;
+use crate::client::manifest::{FileCheck, FileStatus};
+use crate::client::snapshot::SnapshotDiff;
+use crate::client::subscriptions::TrackedPart;
+use crate::integrations::vendors::VendorComparison;
+use crate::models::change::ChangeRecord;
+use crate::models::product::{PriceInfo, PriceQuote, ProductDetail, ProductDiff};
+use crate::utils::display::is_plain_output;
 
 /// Output format options for displaying product information
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     /// Human-friendly output with formatting and emojis (default)
     Human,
     /// Machine-readable JSON output
     Json,
+    /// Comma-separated values for spreadsheets
+    Csv,
+    /// Aligned table with borders
+    Table,
 }
 
 impl fmt::Display for OutputFormat {
@@ -17,6 +41,8 @@ impl fmt::Display for OutputFormat {
         match self {
             OutputFormat::Human => write!(f, "human"),
             OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Csv => write!(f, "csv"),
+            OutputFormat::Table => write!(f, "table"),
         }
     }
 }
@@ -29,6 +55,7 @@ pub enum ProductField {
     FamilyDescription,
     Category,
     Status,
+    ItemNumber,
     Specification(String),
     AllSpecs,
     BasicInfo,
@@ -44,6 +71,7 @@ impl ProductField {
                 ProductField::FamilyDescription,
                 ProductField::Category,
                 ProductField::Status,
+                ProductField::ItemNumber,
                 ProductField::AllSpecs,
             ];
         }
@@ -55,6 +83,7 @@ impl ProductField {
                 ProductField::FamilyDescription,
                 ProductField::Category,
                 ProductField::Status,
+                ProductField::ItemNumber,
             ];
         }
 
@@ -71,6 +100,7 @@ impl ProductField {
                 "family-description" | "family" => ProductField::FamilyDescription,
                 "category" | "product-category" => ProductField::Category,
                 "status" | "product-status" => ProductField::Status,
+                "item-number" | "item" => ProductField::ItemNumber,
                 "specs" | "specifications" => ProductField::AllSpecs,
                 "basic" => ProductField::BasicInfo,
                 _ => {
@@ -91,4 +121,384 @@ impl ProductField {
             })
             .collect()
     }
-}
\ No newline at end of file
+}
+
+/// Header and rows shared by the CSV and table formats
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rows {
+    pub header: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Rows {
+    fn new(header: &[&'static str]) -> Self {
+        Rows {
+            header: header.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Write the rows as CSV with a header line
+    pub fn write_csv<W: Write>(&self, writer: W) -> csv::Result<()> {
+        let mut csv = csv::Writer::from_writer(writer);
+        csv.write_record(&self.header)?;
+        for row in &self.rows {
+            csv.write_record(row)?;
+        }
+        csv.flush()?;
+        Ok(())
+    }
+
+    /// Render the rows as an aligned table, with ASCII borders in plain mode
+    pub fn to_table(&self) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(if is_plain_output() { ASCII_MARKDOWN } else { UTF8_FULL_CONDENSED })
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(&self.header);
+        for row in &self.rows {
+            table.add_row(row);
+        }
+        table.to_string()
+    }
+}
+
+/// Product fields as `field,value` rows (specifications use their attribute name)
+pub fn product_rows(product: &ProductDetail, fields_str: &str) -> Rows {
+    let mut rows = Rows::new(&["field", "value"]);
+    let mut push = |name: &str, value: &str| rows.push(vec![name.to_string(), value.to_string()]);
+
+    let basic = [
+        ("part_number", &product.part_number),
+        ("description", &product.detail_description),
+        ("family", &product.family_description),
+        ("category", &product.product_category),
+        ("status", &product.product_status),
+    ];
+
+    for field in ProductField::parse_fields(fields_str) {
+        match field {
+            ProductField::PartNumber => push("part_number", &product.part_number),
+            ProductField::DetailDescription => push("description", &product.detail_description),
+            ProductField::FamilyDescription => push("family", &product.family_description),
+            ProductField::Category => push("category", &product.product_category),
+            ProductField::Status => push("status", &product.product_status),
+            ProductField::ItemNumber => {
+                if let Some(item_number) = &product.item_number {
+                    push("item_number", item_number);
+                }
+            }
+            ProductField::AllSpecs => {
+                for spec in &product.specifications {
+                    push(&spec.attribute, &spec.values.join("; "));
+                }
+            }
+            ProductField::Specification(spec_name) => {
+                if let Some(spec) = product.specifications.iter()
+                    .find(|s| s.attribute.eq_ignore_ascii_case(&spec_name)) {
+                    push(&spec.attribute, &spec.values.join("; "));
+                }
+            }
+            ProductField::BasicInfo => {
+                for (name, value) in basic {
+                    push(name, value);
+                }
+                if let Some(item_number) = &product.item_number {
+                    push("item_number", item_number);
+                }
+            }
+        }
+    }
+
+    rows
+}
+
+/// Fields of several products, one `part_number,field,value` row per field
+pub fn products_rows(products: &[ProductDetail], fields_str: &str) -> Rows {
+    let mut rows = Rows::new(&["part_number", "field", "value"]);
+    for product in products {
+        for row in product_rows(product, fields_str).rows {
+            rows.push([vec![product.part_number.clone()], row].concat());
+        }
+    }
+    rows
+}
+
+/// Price breaks, one row per break
+pub fn price_rows(product: &str, price_infos: &[PriceInfo]) -> Rows {
+    let mut rows = Rows::new(&["part_number", "minimum_quantity", "amount", "unit_of_measure"]);
+    for price_info in price_infos {
+        rows.push(vec![
+            product.to_string(),
+            price_info.minimum_quantity.to_string(),
+            price_info.amount.to_string(),
+            price_info.unit_of_measure.clone(),
+        ]);
+    }
+    rows
+}
+
+/// An order quote as a single row
+pub fn quote_rows(quote: &PriceQuote) -> Rows {
+    quotes_rows(std::slice::from_ref(quote))
+}
+
+/// The parts of a `mmc bom order` purchase list, priced like `mmc price --qty`
+pub fn purchase_order_rows(order: &PurchaseOrder) -> Rows {
+    quotes_rows(&order.lines)
+}
+
+/// Order quotes for several parts, one row each
+pub fn quotes_rows(quotes: &[PriceQuote]) -> Rows {
+    let mut rows = Rows::new(&["part_number", "quantity", "pack_size", "units", "unit_of_measure", "break_quantity", "unit_price", "total"]);
+    for quote in quotes {
+        rows.push(vec![
+            quote.part_number.clone(),
+            quote.quantity.to_string(),
+            quote.pack_size.map(|size| size.to_string()).unwrap_or_default(),
+            quote.units.to_string(),
+            quote.unit_of_measure.clone(),
+            quote.break_quantity.to_string(),
+            quote.unit_price.to_string(),
+            quote.total.to_string(),
+        ]);
+    }
+    rows
+}
+
+/// Price breaks for several parts (failed lookups are skipped)
+pub fn price_all_rows(results: &[PartPrices]) -> Rows {
+    let mut rows = Rows::new(&["part_number", "minimum_quantity", "amount", "unit_of_measure"]);
+    for result in results {
+        rows.rows.extend(price_rows(&result.part_number, &result.prices).rows);
+    }
+    rows
+}
+
+/// Tracked parts (tags joined by commas, metadata as `key=value` pairs joined by `;`)
+pub fn tracked_part_rows(parts: &[TrackedPart]) -> Rows {
+    let mut rows = Rows::new(&["part_number", "tags", "note", "metadata", "item_number"]);
+    for part in parts {
+        let metadata = part.metadata.iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(";");
+        rows.push(vec![
+            part.part_number.clone(),
+            part.tags.join(","),
+            part.note.clone().unwrap_or_default(),
+            metadata,
+            part.item_number.clone().unwrap_or_default(),
+        ]);
+    }
+    rows
+}
+
+/// A vendor comparison, one row per supplier
+pub fn vendor_rows(comparison: &VendorComparison) -> Rows {
+    let mut rows = Rows::new(&["part_number", "vendor", "price", "currency", "url", "error"]);
+    for quote in &comparison.quotes {
+        rows.push(vec![
+            comparison.part_number.clone(),
+            quote.vendor.clone(),
+            quote.price.map(|price| price.to_string()).unwrap_or_default(),
+            quote.currency.clone().unwrap_or_default(),
+            quote.url.clone().unwrap_or_default(),
+            quote.error.clone().unwrap_or_default(),
+        ]);
+    }
+    rows
+}
+
+/// Alert rules, one row per rule
+pub fn alert_rows(alerts: &[AlertRule]) -> Rows {
+    let mut rows = Rows::new(&["part_number", "direction", "threshold", "last_price"]);
+    for alert in alerts {
+        rows.push(vec![
+            alert.part_number.clone(),
+            alert.direction.as_str().to_string(),
+            alert.threshold.to_string(),
+            alert.last_price.map(|price| price.to_string()).unwrap_or_default(),
+        ]);
+    }
+    rows
+}
+
+/// Item number crosswalk, one row per mapping (re-importable with `mmc xref import`)
+pub fn crosswalk_rows(crosswalk: &[CrosswalkEntry]) -> Rows {
+    let mut rows = Rows::new(&["part_number", "item_number"]);
+    for entry in crosswalk {
+        rows.push(vec![entry.part_number.clone(), entry.item_number.clone()]);
+    }
+    rows
+}
+
+/// Snapshot differences, one row per added or removed part and per changed field
+pub fn snapshot_diff_rows(diff: &SnapshotDiff) -> Rows {
+    let mut rows = Rows::new(&["part_number", "change", "field", "before", "after"]);
+    for part in &diff.added {
+        rows.push(vec![part.clone(), "added".to_string(), String::new(), String::new(), String::new()]);
+    }
+    for part in &diff.removed {
+        rows.push(vec![part.clone(), "removed".to_string(), String::new(), String::new(), String::new()]);
+    }
+    for part in &diff.changed {
+        for change in &part.changes {
+            rows.push(vec![part.part_number.clone(), "changed".to_string(), change.field.clone(), change.before.clone(), change.after.clone()]);
+        }
+    }
+    rows
+}
+
+/// Change records
+pub fn change_rows(changes: &[ChangeRecord]) -> Rows {
+    let mut rows = Rows::new(&["part_number", "change_date", "change_type", "link"]);
+    for change in changes {
+        rows.push(vec![
+            change.part_number.clone(),
+            change.change_date.clone().unwrap_or_default(),
+            change.change_type.clone().unwrap_or_default(),
+            change.link.clone().unwrap_or_default(),
+        ]);
+    }
+    rows
+}
+
+/// Datasheet search results
+pub fn datasheet_match_rows(matches: &[DatasheetMatch]) -> Rows {
+    let mut rows = Rows::new(&["part_number", "file", "snippet"]);
+    for datasheet in matches {
+        rows.push(vec![
+            datasheet.part_number.clone(),
+            datasheet.file.to_string_lossy().to_string(),
+            datasheet.snippet.clone(),
+        ]);
+    }
+    rows
+}
+
+/// Specifications of two products side by side, optionally only those that differ
+pub fn product_diff_rows(diff: &ProductDiff, differences_only: bool) -> Rows {
+    let mut rows = Rows::new(&["attribute", "first", "second", "differs"]);
+    for spec in diff.specifications.iter().filter(|spec| spec.differs || !differences_only) {
+        rows.push(vec![
+            spec.attribute.clone(),
+            spec.first.join("; "),
+            spec.second.join("; "),
+            spec.differs.to_string(),
+        ]);
+    }
+    rows
+}
+
+/// Alternates, most similar first, with their differing specifications as `attribute=value` pairs joined by `;`
+pub fn alternate_rows(alternates: &[Alternate]) -> Rows {
+    let mut rows = Rows::new(&["part_number", "description", "similarity", "differences"]);
+    for alternate in alternates {
+        rows.push(vec![
+            alternate.part_number.clone(),
+            alternate.description.clone(),
+            format!("{:.2}", alternate.similarity),
+            alternate.differences.iter()
+                .map(|spec| format!("{}={}", spec.attribute, spec.second.join(", ")))
+                .collect::<Vec<_>>()
+                .join("; "),
+        ]);
+    }
+    rows
+}
+
+/// `mmc verify` results as one row per file
+pub fn file_check_rows(checks: &[FileCheck]) -> Rows {
+    let mut rows = Rows::new(&["file", "status", "expected_sha256", "actual_sha256"]);
+    for check in checks {
+        rows.push(vec![
+            check.file_path.display().to_string(),
+            match check.status {
+                FileStatus::Ok => "ok",
+                FileStatus::Modified => "modified",
+                FileStatus::Missing => "missing",
+            }
+            .to_string(),
+            check.expected_sha256.clone(),
+            check.actual_sha256.clone().unwrap_or_default(),
+        ]);
+    }
+    rows
+}
+
+/// `mmc bom check` results as one row per BOM line
+pub fn bom_check_rows(report: &BomCheckReport) -> Rows {
+    let mut rows = Rows::new(&["line", "part_number", "quantity", "status", "problems"]);
+    for line in &report.lines {
+        rows.push(vec![
+            line.line.to_string(),
+            line.part_number.clone().unwrap_or_default(),
+            line.quantity.map(|quantity| quantity.to_string()).unwrap_or_default(),
+            if line.problems.is_empty() { "pass" } else { "fail" }.to_string(),
+            line.problems.join("; "),
+        ]);
+    }
+    rows
+}
+
+/// Write product fields as `field,value` rows (specifications use their attribute name)
+pub fn write_product_csv<W: Write>(writer: W, product: &ProductDetail, fields_str: &str) -> csv::Result<()> {
+    product_rows(product, fields_str).write_csv(writer)
+}
+
+/// Write price breaks as CSV rows
+pub fn write_prices_csv<W: Write>(writer: W, product: &str, price_infos: &[PriceInfo]) -> csv::Result<()> {
+    price_rows(product, price_infos).write_csv(writer)
+}
+
+/// Write price breaks for several parts as CSV rows (failed lookups are skipped)
+pub fn write_price_all_csv<W: Write>(writer: W, results: &[PartPrices]) -> csv::Result<()> {
+    price_all_rows(results).write_csv(writer)
+}
+
+/// Write tracked parts as CSV rows (tags joined by commas, metadata as `key=value` pairs joined by `;`)
+pub fn write_tracked_parts_csv<W: Write>(writer: W, parts: &[TrackedPart]) -> csv::Result<()> {
+    tracked_part_rows(parts).write_csv(writer)
+}
+
+/// Write change records as CSV rows
+pub fn write_changes_csv<W: Write>(writer: W, changes: &[ChangeRecord]) -> csv::Result<()> {
+    change_rows(changes).write_csv(writer)
+}
+
+/// Write datasheet search results as CSV rows
+pub fn write_datasheet_matches_csv<W: Write>(writer: W, matches: &[DatasheetMatch]) -> csv::Result<()> {
+    datasheet_match_rows(matches).write_csv(writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_as_csv_and_table() {
+        let prices: Vec<PriceInfo> = serde_json::from_str(
+            r#"[{"Amount": 9.5, "MinimumQuantity": 1, "UnitOfMeasure": "Pack"}, {"Amount": 8.75, "MinimumQuantity": 10, "UnitOfMeasure": "Pack"}]"#,
+        ).unwrap();
+        let rows = price_rows("91290A115", &prices);
+
+        let mut csv = Vec::new();
+        rows.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "part_number,minimum_quantity,amount,unit_of_measure\n91290A115,1,9.5,Pack\n91290A115,10,8.75,Pack\n"
+        );
+
+        let table = rows.to_table();
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines.iter().any(|line| line.contains("part_number") && line.contains("unit_of_measure")));
+        assert!(lines.iter().any(|line| line.contains("91290A115") && line.contains("8.75")));
+        // Every line is padded to the same width
+        assert!(lines.windows(2).all(|pair| pair[0].chars().count() == pair[1].chars().count()));
+    }
+}
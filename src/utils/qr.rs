@@ -0,0 +1,105 @@
+//! QR codes linking to a part, as PNG or SVG images
+
+use crate::utils::error::{ClientError, Result};
+use qrcode::{Color, QrCode};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// URL a QR code links to when `qr_url` isn't set in config.toml
+pub const DEFAULT_QR_URL: &str = "https://www.mcmaster.com/{part}/";
+
+/// Pixels per QR module in PNG output
+const PNG_SCALE: usize = 8;
+
+/// Blank modules around the code, as the QR spec requires
+const QUIET_ZONE: usize = 4;
+
+/// Fill a `{part}` URL template with a part number
+pub fn part_url(template: &str, part_number: &str) -> String {
+    template.replace("{part}", &urlencoding::encode(part_number))
+}
+
+/// Write a QR code of `data` to `path`: SVG when the extension is `.svg`, otherwise PNG
+pub fn write_qr(data: &str, path: &Path) -> Result<()> {
+    let code = QrCode::new(data.as_bytes())
+        .map_err(|e| ClientError::Generic(format!("Could not encode QR code: {}", e)))?;
+    let svg = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+    let image = if svg { to_svg(&code).into_bytes() } else { to_png(&code)? };
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, image)?;
+    Ok(())
+}
+
+/// Dark modules as (column, row), without the quiet zone
+fn dark_modules(code: &QrCode) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let width = code.width();
+    code.to_colors().into_iter().enumerate()
+        .filter(|(_, color)| *color == Color::Dark)
+        .map(move |(index, _)| (index % width, index / width))
+}
+
+/// An SVG with one unit per module, so it scales cleanly
+fn to_svg(code: &QrCode) -> String {
+    let size = code.width() + 2 * QUIET_ZONE;
+    let mut path = String::new();
+    for (column, row) in dark_modules(code) {
+        let _ = write!(path, "M{} {}h1v1h-1z", column + QUIET_ZONE, row + QUIET_ZONE);
+    }
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {0} {0}\" shape-rendering=\"crispEdges\">\
+         <rect width=\"{0}\" height=\"{0}\" fill=\"#fff\"/><path d=\"{1}\" fill=\"#000\"/></svg>\n",
+        size, path
+    )
+}
+
+fn to_png(code: &QrCode) -> Result<Vec<u8>> {
+    let size = (code.width() + 2 * QUIET_ZONE) * PNG_SCALE;
+    let mut pixels = vec![255u8; size * size];
+    for (column, row) in dark_modules(code) {
+        for dy in 0..PNG_SCALE {
+            let start = ((row + QUIET_ZONE) * PNG_SCALE + dy) * size + (column + QUIET_ZONE) * PNG_SCALE;
+            pixels[start..start + PNG_SCALE].fill(0);
+        }
+    }
+    encode_png(size as u32, size as u32, &pixels, None)
+}
+
+/// Encode 8-bit grayscale pixels as a PNG, optionally tagged with a print resolution
+pub(crate) fn encode_png(width: u32, height: u32, pixels: &[u8], dpi: Option<u32>) -> Result<Vec<u8>> {
+    let png_error = |e: png::EncodingError| ClientError::Generic(format!("Could not encode PNG: {}", e));
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    if let Some(dpi) = dpi {
+        let per_meter = (f64::from(dpi) / 0.0254).round() as u32;
+        encoder.set_pixel_dims(Some(png::PixelDimensions { xppu: per_meter, yppu: per_meter, unit: png::Unit::Meter }));
+    }
+    let mut writer = encoder.write_header().map_err(png_error)?;
+    writer.write_image_data(pixels).map_err(png_error)?;
+    writer.finish().map_err(png_error)?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_qr() {
+        assert_eq!(part_url(DEFAULT_QR_URL, "91290A115"), "https://www.mcmaster.com/91290A115/");
+        assert_eq!(part_url("https://inv.example.com/bins?part={part}", "A B"), "https://inv.example.com/bins?part=A%20B");
+
+        let dir = tempfile::tempdir().unwrap();
+        write_qr("https://www.mcmaster.com/91290A115/", &dir.path().join("qr.svg")).unwrap();
+        let svg = std::fs::read_to_string(dir.path().join("qr.svg")).unwrap();
+        assert!(svg.starts_with("<svg") && svg.contains("viewBox=\"0 0 37 37\""));
+
+        write_qr("https://www.mcmaster.com/91290A115/", &dir.path().join("qr.png")).unwrap();
+        let png = std::fs::read(dir.path().join("qr.png")).unwrap();
+        let info = png::Decoder::new(png.as_slice()).read_info().unwrap().info().clone();
+        assert_eq!((info.width, info.height), (37 * 8, 37 * 8));
+    }
+}